@@ -805,6 +805,13 @@ impl<'a, 'tcx> LateContext<'a, 'tcx> {
                 Ok(())
             }
 
+            fn print_fn_sig(
+                self,
+                _sig: ty::FnSig<'tcx>,
+            ) -> Result<Self::Type, Self::Error> {
+                Ok(())
+            }
+
             fn print_dyn_existential(
                 self,
                 _predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
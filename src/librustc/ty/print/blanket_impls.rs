@@ -0,0 +1,71 @@
+//! Printing the blanket impls of a trait (i.e. those for a type parameter,
+//! e.g. `impl<T: Display> ToString for T`) which could apply to a given type,
+//! for diagnostics explaining why (or how) that type implements the trait.
+
+use crate::hir::def_id::DefId;
+use crate::traits::{self, ObligationCause};
+use crate::ty::{self, Ty, TyCtxt, TypeFoldable};
+use crate::ty::subst::Subst;
+use syntax_pos::DUMMY_SP;
+
+impl TyCtxt<'_, 'tcx, 'tcx> {
+    /// Returns the blanket impls of `trait_def_id` which could apply to
+    /// `self_ty`, i.e. whose bounds it may meet, as far as is known without
+    /// a `ParamEnv` (so bounds on generic parameters in `self_ty` never hold).
+    /// All of them are returned, even overlapping ones (under specialization),
+    /// in the order of the specialization graph, i.e. an impl specializing
+    /// another always comes after it, as it's the more specific one.
+    pub fn applicable_blanket_impls(self, trait_def_id: DefId, self_ty: Ty<'tcx>) -> Vec<DefId> {
+        if self_ty.has_escaping_bound_vars() {
+            return vec![];
+        }
+        let graph = self.specialization_graph_of(trait_def_id);
+        let mut impls: Vec<_> = self.all_impls(trait_def_id).into_iter().filter(|&impl_def_id| {
+            match self.type_of(impl_def_id).sty {
+                ty::Param(_) => self.blanket_impl_may_apply(impl_def_id, self_ty),
+                _ => false,
+            }
+        }).map(|impl_def_id| {
+            // The depth of the impl in the specialization graph.
+            let mut depth = 0;
+            let mut parent = graph.parent(impl_def_id);
+            while parent != trait_def_id {
+                depth += 1;
+                parent = graph.parent(parent);
+            }
+            (depth, impl_def_id)
+        }).collect();
+        impls.sort_by_key(|&(depth, _)| depth);
+        impls.into_iter().map(|(_, impl_def_id)| impl_def_id).collect()
+    }
+
+    /// Returns the headers of `applicable_blanket_impls`, with their bounds,
+    /// e.g. `impl<T> ToString for T where T: std::fmt::Display, T: ?Sized`.
+    pub fn applicable_blanket_impl_headers(
+        self,
+        trait_def_id: DefId,
+        self_ty: Ty<'tcx>,
+    ) -> Vec<String> {
+        self.applicable_blanket_impls(trait_def_id, self_ty).into_iter().filter_map(|impl_def_id| {
+            traits::to_pretty_impl_header(self, impl_def_id)
+        }).collect()
+    }
+
+    /// Returns whether the blanket impl `impl_def_id` may apply to `self_ty`.
+    fn blanket_impl_may_apply(self, impl_def_id: DefId, self_ty: Ty<'tcx>) -> bool {
+        let param_env = ty::ParamEnv::reveal_all();
+        self.infer_ctxt().enter(|infcx| {
+            let impl_substs = infcx.fresh_substs_for_item(DUMMY_SP, impl_def_id);
+            let impl_self_ty = self.type_of(impl_def_id).subst(self, impl_substs);
+            let cause = ObligationCause::dummy();
+            if infcx.at(&cause, param_env).eq(impl_self_ty, self_ty).is_err() {
+                return false;
+            }
+            let predicates = self.predicates_of(impl_def_id).instantiate(self, impl_substs);
+            predicates.predicates.into_iter().all(|predicate| {
+                let obligation = traits::Obligation::new(cause.clone(), param_env, predicate);
+                infcx.predicate_may_hold(&obligation)
+            })
+        })
+    }
+}
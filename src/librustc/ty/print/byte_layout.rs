@@ -0,0 +1,140 @@
+//! Byte-layout diagrams of types, for low-level (e.g. FFI or performance)
+//! debugging, showing the (byte) ranges occupied by each field, and by the
+//! padding between them, in memory order:
+//!
+//! ```text
+//! Foo: [a:0..1][pad:1..4][b:4..8]
+//! ```
+//!
+//! Enums with more than one variant get a diagram for each of their variants,
+//! including the discriminant, either stored in its own tag, or in a niche of
+//! the dataful variant (in which case only the other variants show it):
+//!
+//! ```text
+//! E: A{[discr:0..1][pad:1..4][0:4..8]} B{[discr:0..1][pad:1..8]}
+//! Option<&u8>: None{[niche:0..8]} Some{[0:0..8]}
+//! ```
+//!
+//! Types without a layout, e.g. those with generic parameters, are printed
+//! followed by the reason they have none (see `LayoutError`) instead.
+
+use crate::ty::{self, Ty, TyCtxt};
+use crate::ty::layout::{DiscriminantKind, FieldPlacement, LayoutCx, LayoutOf};
+use crate::ty::layout::{Size, TyLayout, VariantIdx, Variants};
+use crate::ty::print::ty_to_string;
+use rustc_data_structures::indexed_vec::Idx;
+
+use std::fmt::Write;
+
+impl TyCtxt<'_, 'tcx, 'tcx> {
+    /// Returns `ty` followed by its byte-layout diagram, see the module docs.
+    /// Zero-sized fields are left out, as they don't occupy any bytes.
+    pub fn byte_layout_diagram(self, param_env: ty::ParamEnv<'tcx>, ty: Ty<'tcx>) -> String {
+        let mut s = format!("{}: ", ty_to_string(self, ty));
+        let cx = LayoutCx { tcx: self, param_env };
+        let layout = match cx.layout_of(ty) {
+            Ok(layout) => layout,
+            Err(err) => {
+                let _ = write!(s, "<no layout: {}>", err);
+                return s;
+            }
+        };
+
+        match layout.variants {
+            Variants::Single { index } => {
+                let segments = self.byte_layout_segments(&cx, layout, index);
+                write_segments(&mut s, segments, layout.size);
+            }
+            Variants::Multiple { ref discr, ref discr_kind, discr_index, ref variants } => {
+                let discr_start = layout.fields.offset(discr_index);
+                let discr_end = discr_start + discr.value.size(&cx);
+                for (i, index) in variants.indices().enumerate() {
+                    if i > 0 {
+                        s.push(' ');
+                    }
+                    let _ = write!(s, "{}{{", self.byte_layout_variant_name(ty, index));
+                    let variant = layout.for_variant(&cx, index);
+                    let mut segments = self.byte_layout_segments(&cx, variant, index);
+                    match *discr_kind {
+                        DiscriminantKind::Tag => {
+                            segments.push(("discr".to_string(), discr_start, discr_end));
+                        }
+                        // The niche is (part of) a field of the dataful variant.
+                        DiscriminantKind::Niche { dataful_variant, .. } => {
+                            if index != dataful_variant {
+                                segments.push(("niche".to_string(), discr_start, discr_end));
+                            }
+                        }
+                    }
+                    write_segments(&mut s, segments, layout.size);
+                    s.push('}');
+                }
+            }
+        }
+        s
+    }
+
+    /// Returns the name, and the range of bytes, of each (non-zero-sized) field
+    /// of `layout`, which is that of the `variant_index` variant, if an `enum`.
+    fn byte_layout_segments(
+        self,
+        cx: &LayoutCx<'tcx, TyCtxt<'_, 'tcx, 'tcx>>,
+        layout: TyLayout<'tcx>,
+        variant_index: VariantIdx,
+    ) -> Vec<(String, Size, Size)> {
+        // The elements of arrays all look the same, so they're not listed.
+        if let FieldPlacement::Array { .. } = layout.fields {
+            return vec![];
+        }
+
+        let mut segments = vec![];
+        for i in 0..layout.fields.count() {
+            let size = match layout.field(cx, i) {
+                Ok(field) => field.size,
+                Err(_) => continue,
+            };
+            if size == Size::ZERO {
+                continue;
+            }
+            let name = match layout.ty.sty {
+                ty::Adt(def, _) => def.variants.get(variant_index)
+                    .and_then(|variant| variant.fields.get(i))
+                    .map(|field| field.ident.to_string()),
+                _ => None,
+            };
+            let start = layout.fields.offset(i);
+            segments.push((name.unwrap_or_else(|| i.to_string()), start, start + size));
+        }
+        segments
+    }
+
+    fn byte_layout_variant_name(self, ty: Ty<'tcx>, variant_index: VariantIdx) -> String {
+        match ty.sty {
+            ty::Adt(def, _) => def.variants[variant_index].ident.to_string(),
+            // E.g. the states of generators, which have no names.
+            _ => variant_index.as_usize().to_string(),
+        }
+    }
+}
+
+/// Writes `segments` in memory order, filling any gaps between them (and up
+/// to `size`) with padding. Types with no (listed) fields are a single range.
+fn write_segments(s: &mut String, mut segments: Vec<(String, Size, Size)>, size: Size) {
+    if segments.is_empty() {
+        let _ = write!(s, "[0..{}]", size.bytes());
+        return;
+    }
+
+    segments.sort_by_key(|&(_, start, end)| (start, end));
+    let mut end_so_far = Size::ZERO;
+    for (name, start, end) in segments {
+        if start > end_so_far {
+            let _ = write!(s, "[pad:{}..{}]", end_so_far.bytes(), start.bytes());
+        }
+        let _ = write!(s, "[{}:{}..{}]", name, start.bytes(), end.bytes());
+        end_so_far = end_so_far.max(end);
+    }
+    if size > end_so_far {
+        let _ = write!(s, "[pad:{}..{}]", end_so_far.bytes(), size.bytes());
+    }
+}
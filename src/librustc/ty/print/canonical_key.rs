@@ -0,0 +1,302 @@
+//! A `Printer` producing a canonical string key for a type, such that types
+//! which only differ in their free lifetimes, or in the names of the regions
+//! bound by `for<...>` binders (i.e. alpha-equivalent types), get the same key,
+//! e.g. for deduplicating types in a `HashMap<String, _>` across tools which
+//! can't share the interned `Ty`s themselves.
+//!
+//! Free regions are erased, while bound regions are renumbered in order of
+//! appearance within their binder, and printed with their De Bruijn index, so
+//! `for<'a> fn(&'a u8)` and `for<'b> fn(&'b u8)` both get the key
+//! `extern "Rust" fn(&'^0.1 u8) -> ()`. Paths are printed along with their
+//! `DefPathHash`, to keep them unambiguous, and const values with their type.
+
+use crate::hir;
+use crate::hir::def::Namespace;
+use crate::hir::def_id::{CrateNum, DefId};
+use crate::hir::map::DisambiguatedDefPathData;
+use crate::mir::interpret::ConstValue;
+use crate::ty::{self, Ty, TyCtxt, TypeFoldable};
+use crate::ty::fold::TypeFolder;
+use crate::ty::print::{FmtPrinter, Print, Printer};
+use crate::ty::subst::Kind;
+
+pub struct CanonicalKeyPrinter<'a, 'gcx, 'tcx> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    key: String,
+}
+
+impl CanonicalKeyPrinter<'a, 'gcx, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>) -> Self {
+        CanonicalKeyPrinter {
+            tcx,
+            key: String::new(),
+        }
+    }
+
+    /// Returns the key for everything printed so far.
+    pub fn finish(self) -> String {
+        self.key
+    }
+
+    fn push(mut self, s: &str) -> Self {
+        self.key.push_str(s);
+        self
+    }
+
+    fn print_kinds(mut self, args: &[Kind<'tcx>]) -> Result<Self, !> {
+        for (i, arg) in args.iter().enumerate() {
+            if i > 0 {
+                self = self.push(", ");
+            }
+            self = arg.print(self)?;
+        }
+        Ok(self)
+    }
+}
+
+/// Renumbers the regions bound by every binder (see `anonymize_late_bound_regions`),
+/// innermost binders first, as the outer ones don't rename regions bound inside them.
+struct BoundRegionAnonymizer<'a, 'gcx, 'tcx> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+}
+
+impl TypeFolder<'gcx, 'tcx> for BoundRegionAnonymizer<'_, 'gcx, 'tcx> {
+    fn tcx<'b>(&'b self) -> TyCtxt<'b, 'gcx, 'tcx> { self.tcx }
+
+    fn fold_binder<T: TypeFoldable<'tcx>>(&mut self, t: &ty::Binder<T>) -> ty::Binder<T> {
+        let t = t.super_fold_with(self);
+        self.tcx.anonymize_late_bound_regions(&t)
+    }
+}
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns a key for `ty` that's the same for all types alpha-equivalent
+    /// to it, and ignores its free lifetimes.
+    pub fn canonical_type_key(self, ty: Ty<'tcx>) -> String {
+        let ty = self.erase_regions(&ty).fold_with(&mut BoundRegionAnonymizer { tcx: self });
+        CanonicalKeyPrinter::new(self).print_type(ty).unwrap().finish()
+    }
+}
+
+impl Printer<'gcx, 'tcx> for CanonicalKeyPrinter<'_, 'gcx, 'tcx> {
+    type Error = !;
+
+    type Path = Self;
+    type Region = Self;
+    type Type = Self;
+    type DynExistential = Self;
+    type Const = Self;
+
+    fn tcx(&'a self) -> TyCtxt<'a, 'gcx, 'tcx> {
+        self.tcx
+    }
+
+    fn print_def_path(
+        self,
+        def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        // The `DefPathHash` already identifies the whole path, which is
+        // only printed to keep keys readable, so all args get printed.
+        let path = format!("{}@{}", self.tcx.def_path_str(def_id),
+                           self.tcx.def_path_hash(def_id).0.to_hex());
+        let mut cx = self.push(&path);
+        if !substs.is_empty() {
+            cx = cx.push("<").print_kinds(substs)?.push(">");
+        }
+        Ok(cx)
+    }
+
+    fn print_region(
+        self,
+        region: ty::Region<'_>,
+    ) -> Result<Self::Region, Self::Error> {
+        let region = match *region {
+            ty::ReLateBound(debruijn, ty::BrAnon(i)) => format!("'^{}.{}", debruijn.index(), i),
+            ty::ReLateBound(..) => format!("{:?}", region),
+            _ => "'_".to_string(),
+        };
+        Ok(self.push(&region))
+    }
+
+    fn print_type(
+        mut self,
+        ty: Ty<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        match ty.sty {
+            ty::Bool => Ok(self.push("bool")),
+            ty::Char => Ok(self.push("char")),
+            ty::Int(t) => Ok(self.push(t.ty_to_string())),
+            ty::Uint(t) => Ok(self.push(t.ty_to_string())),
+            ty::Float(t) => Ok(self.push(t.ty_to_string())),
+            ty::Str => Ok(self.push("str")),
+            ty::Never => Ok(self.push("!")),
+
+            ty::Adt(def, substs) => self.print_def_path(def.did, substs),
+            ty::Foreign(def_id) => self.push("extern ").print_def_path(def_id, &[]),
+            ty::FnDef(def_id, substs) => self.push("fn ").print_def_path(def_id, substs),
+            ty::Closure(def_id, substs) => {
+                self.push("closure ").print_def_path(def_id, substs.substs)
+            }
+            ty::Generator(def_id, substs, movability) => {
+                self = self.push(match movability {
+                    hir::GeneratorMovability::Static => "static generator ",
+                    hir::GeneratorMovability::Movable => "generator ",
+                });
+                self.print_def_path(def_id, substs.substs)
+            }
+            ty::Opaque(def_id, substs) => self.push("impl ").print_def_path(def_id, substs),
+            ty::Projection(ref data) => {
+                self.push("projection ").print_def_path(data.item_def_id, data.substs)
+            }
+            ty::RawPtr(mt) => {
+                self.push(match mt.mutbl {
+                    hir::MutMutable => "*mut ",
+                    hir::MutImmutable => "*const ",
+                }).print_type(mt.ty)
+            }
+            ty::Ref(r, ty, mutbl) => {
+                self = self.push("&").print_region(r)?;
+                self.push(match mutbl {
+                    hir::MutMutable => " mut ",
+                    hir::MutImmutable => " ",
+                }).print_type(ty)
+            }
+            ty::Tuple(tys) => Ok(self.push("(").print_kinds(tys)?.push(")")),
+            ty::Array(ty, len) => {
+                Ok(self.push("[").print_type(ty)?.push("; ").print_const(len)?.push("]"))
+            }
+            ty::Slice(ty) => Ok(self.push("[").print_type(ty)?.push("]")),
+            ty::FnPtr(sig) => {
+                let sig = sig.skip_binder();
+                if sig.unsafety == hir::Unsafety::Unsafe {
+                    self = self.push("unsafe ");
+                }
+                self = self.push(&format!("extern {} fn(", sig.abi));
+                for (i, &ty) in sig.inputs().iter().enumerate() {
+                    if i > 0 {
+                        self = self.push(", ");
+                    }
+                    self = self.print_type(ty)?;
+                }
+                if sig.c_variadic {
+                    self = self.push(", ...");
+                }
+                self.push(") -> ").print_type(sig.output())
+            }
+            ty::Dynamic(data, r) => {
+                self = self.push("dyn ").print_dyn_existential(*data.skip_binder())?;
+                self.push(" + ").print_region(r)
+            }
+            ty::Param(ref param_ty) => {
+                Ok(self.push(&format!("{}/#{}", param_ty.name, param_ty.index)))
+            }
+
+            // These don't have a canonical form, but they are distinguished at least.
+            ty::Infer(_) |
+            ty::Bound(..) |
+            ty::Placeholder(_) |
+            ty::UnnormalizedProjection(_) |
+            ty::GeneratorWitness(_) |
+            ty::Error => Ok(self.push(&format!("{:?}", ty))),
+        }
+    }
+
+    fn print_fn_sig(
+        self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        let fn_ptr = self.tcx().mk_fn_ptr(ty::Binder::bind(sig));
+        self.print_type(fn_ptr)
+    }
+
+    fn print_dyn_existential(
+        mut self,
+        predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
+    ) -> Result<Self::DynExistential, Self::Error> {
+        for (i, predicate) in predicates.iter().enumerate() {
+            if i > 0 {
+                self = self.push(" + ");
+            }
+            self = match *predicate {
+                ty::ExistentialPredicate::Trait(trait_ref) => {
+                    self.print_def_path(trait_ref.def_id, trait_ref.substs)?
+                }
+                ty::ExistentialPredicate::Projection(projection) => {
+                    self = self.print_def_path(projection.item_def_id, projection.substs)?;
+                    self.push(" = ").print_type(projection.ty)?
+                }
+                ty::ExistentialPredicate::AutoTrait(def_id) => {
+                    self.print_def_path(def_id, &[])?
+                }
+            };
+        }
+        Ok(self)
+    }
+
+    fn print_const(
+        self,
+        ct: &'tcx ty::Const<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        // Const params are printed by name (and index), like type params,
+        // while values are printed with their type, to tell apart e.g.
+        // `3u8` from `3usize`, if the value itself doesn't already do so.
+        let mut value = String::new();
+        let _ = FmtPrinter::new(self.tcx, &mut value, Namespace::ValueNS).print_const(ct);
+        match ct.val {
+            ConstValue::Param(param) => Ok(self.push(&format!("{}/#{}", param.name, param.index))),
+            _ => self.push(&value).push(": ").print_type(ct.ty),
+        }
+    }
+
+    // Paths are printed by `print_def_path` as a whole, so these are
+    // only used by callers printing partial paths themselves.
+
+    fn path_crate(
+        self,
+        cnum: CrateNum,
+    ) -> Result<Self::Path, Self::Error> {
+        let disambiguator = self.tcx.crate_disambiguator(cnum);
+        let krate = format!("{}@{}", self.tcx.crate_name(cnum),
+                            disambiguator.to_fingerprint().to_hex());
+        Ok(self.push(&krate))
+    }
+    fn path_qualified(
+        mut self,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self = self.push("<").print_type(self_ty)?;
+        if let Some(trait_ref) = trait_ref {
+            self = self.push(" as ").print_def_path(trait_ref.def_id, trait_ref.substs)?;
+        }
+        Ok(self.push(">"))
+    }
+
+    fn path_append_impl(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        _disambiguated_data: &DisambiguatedDefPathData,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?.push("::");
+        self.path_qualified(self_ty, trait_ref)
+    }
+    fn path_append(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        disambiguated_data: &DisambiguatedDefPathData,
+    ) -> Result<Self::Path, Self::Error> {
+        let name = disambiguated_data.data.as_interned_str();
+        let component = format!("::{}#{}", name, disambiguated_data.disambiguator);
+        Ok(print_prefix(self)?.push(&component))
+    }
+    fn path_generic_args(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        args: &[Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        Ok(print_prefix(self)?.push("<").print_kinds(args)?.push(">"))
+    }
+}
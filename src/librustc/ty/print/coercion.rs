@@ -0,0 +1,145 @@
+//! Printing the steps a coercion goes through, for diagnostics explaining
+//! (failed) coercions, e.g. `&Box<String> → &String → &str` for deref coercion
+//! from `&Box<String>` to `&str`. Every step is printed by `ty_to_string`.
+
+use crate::traits;
+use crate::ty::{self, ToPredicate, Ty, TyCtxt, TypeFoldable};
+use crate::ty::print::ty_to_string;
+use syntax_pos::DUMMY_SP;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CoercionKind {
+    /// (Repeatedly) dereferencing the pointee, e.g. `&String` to `&str`.
+    Deref,
+    /// Unsizing the pointee, e.g. `&[u8; 4]` to `&[u8]`, or `&T` to `&dyn Trait`.
+    Unsize,
+}
+
+#[derive(Clone, Debug)]
+pub struct CoercionPath<'tcx> {
+    /// The types the coercion goes through, starting with the source type,
+    /// and ending with the last one reached, i.e. the target type, if `reached`.
+    pub steps: Vec<Ty<'tcx>>,
+    pub reached: bool,
+}
+
+impl TyCtxt<'_, 'tcx, 'tcx> {
+    /// Returns the steps a `kind` coercion from `source` to `target` goes through,
+    /// stopping where it breaks, if it can't reach `target`. Pointers (i.e. refs,
+    /// raw pointers and `Box`es) are coerced through their pointees, with every
+    /// step being the same kind of pointer, e.g. `&Vec<u8> → &[u8]`.
+    /// Regions are erased, as they don't matter for either kind of coercion.
+    pub fn coercion_path(
+        self,
+        source: Ty<'tcx>,
+        target: Ty<'tcx>,
+        kind: CoercionKind,
+    ) -> CoercionPath<'tcx> {
+        let (source, target) = (self.erase_regions(&source), self.erase_regions(&target));
+        let pointees = source.builtin_deref(true).and_then(|source_mt| {
+            target.builtin_deref(true).map(|target_mt| (source_mt.ty, target_mt.ty))
+        });
+        let (mut pointee, target_pointee) = pointees.unwrap_or((source, target));
+        let wrap = |ty: Ty<'tcx>| {
+            if pointees.is_none() {
+                return ty;
+            }
+            match source.sty {
+                ty::Ref(r, _, mutbl) => self.mk_ref(r, ty::TypeAndMut { ty, mutbl }),
+                ty::RawPtr(mt) => self.mk_ptr(ty::TypeAndMut { ty, mutbl: mt.mutbl }),
+                _ => self.mk_box(ty),
+            }
+        };
+
+        let mut steps = vec![source];
+        let recursion_limit = *self.sess.recursion_limit.get();
+        while pointee != target_pointee && steps.len() <= recursion_limit {
+            let next = match kind {
+                CoercionKind::Deref => self.coercion_deref_target(pointee),
+                // Unsizing is a single step, which either works or doesn't.
+                CoercionKind::Unsize if steps.len() == 1 &&
+                    self.unsizes_to(pointee, target_pointee) => Some(target_pointee),
+                CoercionKind::Unsize => None,
+            };
+            match next {
+                Some(next) => {
+                    pointee = next;
+                    steps.push(wrap(next));
+                }
+                None => break,
+            }
+        }
+
+        CoercionPath { steps, reached: pointee == target_pointee }
+    }
+
+    /// Returns the steps of `coercion_path` separated by arrows, followed
+    /// (if the coercion breaks) by the target type it couldn't reach, e.g.
+    /// `&Foo → &Bar (doesn't coerce further to `&str`)`.
+    pub fn coercion_path_str(
+        self,
+        source: Ty<'tcx>,
+        target: Ty<'tcx>,
+        kind: CoercionKind,
+    ) -> String {
+        let path = self.coercion_path(source, target, kind);
+        let steps: Vec<_> = path.steps.iter().map(|&ty| ty_to_string(self, ty)).collect();
+        let mut s = steps.join(" → ");
+        if !path.reached {
+            s.push_str(&format!(" (doesn't coerce further to `{}`)", ty_to_string(self, target)));
+        }
+        s
+    }
+
+    /// Returns the type `ty` dereferences to, either built-in (e.g. for refs),
+    /// or through its `Deref` impl, if it's known to have one.
+    fn coercion_deref_target(self, ty: Ty<'tcx>) -> Option<Ty<'tcx>> {
+        if let Some(mt) = ty.builtin_deref(true) {
+            return Some(mt.ty);
+        }
+
+        // Without a `ParamEnv`, impls can only be found for concrete types.
+        if ty.needs_subst() || ty.has_escaping_bound_vars() {
+            return None;
+        }
+        let deref_trait = self.lang_items().deref_trait()?;
+        let param_env = ty::ParamEnv::reveal_all();
+        let implemented = self.infer_ctxt().enter(|infcx| {
+            traits::type_known_to_meet_bound_modulo_regions(
+                &infcx,
+                param_env,
+                ty,
+                deref_trait,
+                DUMMY_SP,
+            )
+        });
+        if !implemented {
+            return None;
+        }
+        let target = self.associated_items(deref_trait)
+            .find(|item| item.kind == ty::AssocKind::Type)?;
+        let projection = self.mk_projection(target.def_id, self.mk_substs_trait(ty, &[]));
+        Some(self.normalize_erasing_regions(param_env, projection))
+    }
+
+    /// Returns whether `ty` is known to implement `Unsize<target>`.
+    fn unsizes_to(self, ty: Ty<'tcx>, target: Ty<'tcx>) -> bool {
+        let unsize_trait = match self.lang_items().unsize_trait() {
+            Some(unsize_trait) => unsize_trait,
+            None => return false,
+        };
+        if ty.needs_subst() || target.needs_subst() {
+            return false;
+        }
+        let trait_ref = ty::TraitRef {
+            def_id: unsize_trait,
+            substs: self.mk_substs_trait(ty, &[target.into()]),
+        };
+        let obligation = traits::Obligation::new(
+            traits::ObligationCause::dummy(),
+            ty::ParamEnv::reveal_all(),
+            trait_ref.to_predicate(),
+        );
+        self.infer_ctxt().enter(|infcx| infcx.predicate_must_hold_modulo_regions(&obligation))
+    }
+}
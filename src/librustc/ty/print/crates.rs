@@ -0,0 +1,191 @@
+//! A `Printer` that doesn't print anything, but instead records the crates
+//! whose items are referenced by the printed paths, e.g. for auditing which
+//! dependencies a type exposes. Paths are those of the defining crates,
+//! not of any reexports: `Vec<Rc<u32>>` references `alloc` only, whereas
+//! `HashMap<String, u8>` references `std` (including through its default
+//! `RandomState` hasher) and `alloc`.
+
+use crate::hir::def_id::{CrateNum, LOCAL_CRATE};
+use crate::hir::map::DisambiguatedDefPathData;
+use crate::ty::{self, Ty, TyCtxt};
+use crate::ty::print::Printer;
+use crate::ty::subst::{Kind, UnpackedKind};
+use rustc_data_structures::fx::FxHashSet;
+
+pub struct CrateCollectorPrinter<'a, 'gcx, 'tcx> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    crates: FxHashSet<CrateNum>,
+
+    /// Whether `LOCAL_CRATE` should be recorded like any other crate.
+    include_local: bool,
+}
+
+impl CrateCollectorPrinter<'a, 'gcx, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>, include_local: bool) -> Self {
+        CrateCollectorPrinter {
+            tcx,
+            crates: Default::default(),
+            include_local,
+        }
+    }
+
+    /// Returns the crates recorded so far.
+    pub fn into_crates(self) -> FxHashSet<CrateNum> {
+        self.crates
+    }
+}
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns the crates referenced by the paths in `ty`, ignoring
+    /// the local crate unless `include_local` is set.
+    pub fn crates_referenced_by(self, ty: Ty<'tcx>, include_local: bool) -> FxHashSet<CrateNum> {
+        CrateCollectorPrinter::new(self, include_local).print_type(ty).unwrap().into_crates()
+    }
+}
+
+impl Printer<'gcx, 'tcx> for CrateCollectorPrinter<'_, 'gcx, 'tcx> {
+    type Error = !;
+
+    type Path = Self;
+    type Region = Self;
+    type Type = Self;
+    type DynExistential = Self;
+    type Const = Self;
+
+    fn tcx(&'a self) -> TyCtxt<'a, 'gcx, 'tcx> {
+        self.tcx
+    }
+
+    // The args left out for being the defaults of their parameters
+    // (e.g. `RandomState`) still reference crates, so print them all.
+    fn should_print_verbose(&self) -> bool {
+        true
+    }
+
+    fn print_region(
+        self,
+        _region: ty::Region<'_>,
+    ) -> Result<Self::Region, Self::Error> {
+        Ok(self)
+    }
+
+    fn print_type(
+        mut self,
+        ty: Ty<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        match ty.sty {
+            ty::Adt(def, substs) => self.print_def_path(def.did, substs),
+            ty::Foreign(def_id) => self.print_def_path(def_id, &[]),
+            ty::FnDef(def_id, substs) |
+            ty::Opaque(def_id, substs) => self.print_def_path(def_id, substs),
+            ty::Closure(def_id, ty::ClosureSubsts { substs }) |
+            ty::Generator(def_id, ty::GeneratorSubsts { substs }, _) => {
+                self.print_def_path(def_id, substs)
+            }
+            ty::Projection(ref data) => self.print_def_path(data.item_def_id, data.substs),
+            ty::Dynamic(data, _) => self.print_dyn_existential(*data.skip_binder()),
+
+            // Everything else has no path of its own, so only its
+            // components can reference other crates.
+            _ => {
+                for ty in ty.walk_shallow() {
+                    self = self.print_type(ty)?;
+                }
+                Ok(self)
+            }
+        }
+    }
+
+    fn print_fn_sig(
+        self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        let fn_ptr = self.tcx().mk_fn_ptr(ty::Binder::bind(sig));
+        self.print_type(fn_ptr)
+    }
+
+    fn print_dyn_existential(
+        mut self,
+        predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
+    ) -> Result<Self::DynExistential, Self::Error> {
+        for predicate in predicates {
+            self = match *predicate {
+                ty::ExistentialPredicate::Trait(trait_ref) => {
+                    // Use a type that can't appear in defaults of type parameters.
+                    let dummy_self = self.tcx.mk_ty_infer(ty::FreshTy(0));
+                    let trait_ref = trait_ref.with_self_ty(self.tcx, dummy_self);
+                    self.print_def_path(trait_ref.def_id, trait_ref.substs)?
+                }
+                ty::ExistentialPredicate::Projection(projection) => {
+                    self = self.print_def_path(projection.item_def_id, projection.substs)?;
+                    self.print_type(projection.ty)?
+                }
+                ty::ExistentialPredicate::AutoTrait(def_id) => {
+                    self.print_def_path(def_id, &[])?
+                }
+            };
+        }
+        Ok(self)
+    }
+
+    fn print_const(
+        self,
+        ct: &'tcx ty::Const<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        self.print_type(ct.ty)
+    }
+
+    fn path_crate(
+        mut self,
+        cnum: CrateNum,
+    ) -> Result<Self::Path, Self::Error> {
+        if cnum != LOCAL_CRATE || self.include_local {
+            self.crates.insert(cnum);
+        }
+        Ok(self)
+    }
+    fn path_qualified(
+        mut self,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self = self.print_type(self_ty)?;
+        match trait_ref {
+            Some(trait_ref) => self.print_def_path(trait_ref.def_id, trait_ref.substs),
+            None => Ok(self),
+        }
+    }
+
+    fn path_append_impl(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        _disambiguated_data: &DisambiguatedDefPathData,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?;
+        self.path_qualified(self_ty, trait_ref)
+    }
+    fn path_append(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        _disambiguated_data: &DisambiguatedDefPathData,
+    ) -> Result<Self::Path, Self::Error> {
+        print_prefix(self)
+    }
+    fn path_generic_args(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        args: &[Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?;
+        for arg in args {
+            self = match arg.unpack() {
+                UnpackedKind::Lifetime(_) => self,
+                UnpackedKind::Type(ty) => self.print_type(ty)?,
+                UnpackedKind::Const(ct) => self.print_const(ct)?,
+            };
+        }
+        Ok(self)
+    }
+}
@@ -0,0 +1,78 @@
+//! Flattening of a type's fields into their leaf fields, each with the
+//! dotted access path leading to it, e.g. for reflection-like dumps:
+//!
+//! ```text
+//! s.a.x: u32
+//! s.a.y: u32
+//! s.b: bool
+//! ```
+//!
+//! Only structs and tuples are flattened, as the fields of an enum depend on
+//! its variant, and those of a union overlap. Those, and all other types, are
+//! leaves, printed like any other type (see `print_type`).
+
+use crate::ty::{self, Ty, TyCtxt};
+
+use std::fmt::Write;
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns the leaf fields of `ty`, with their access paths starting at
+    /// `base`, in declaration order. Aggregates that are nested more than
+    /// `max_depth` levels deep, or that (directly or indirectly) contain
+    /// themselves, are returned as leaves instead of being flattened further.
+    pub fn flattened_fields(
+        self,
+        base: &str,
+        ty: Ty<'tcx>,
+        max_depth: usize,
+    ) -> Vec<(String, Ty<'tcx>)> {
+        let mut fields = vec![];
+        let mut stack = vec![];
+        self.flatten_fields_into(base.to_string(), ty, max_depth, &mut stack, &mut fields);
+        fields
+    }
+
+    /// Like `flattened_fields`, but rendered one `path: type` per line.
+    pub fn flattened_fields_str(self, base: &str, ty: Ty<'tcx>, max_depth: usize) -> String {
+        let mut s = String::new();
+        for (path, ty) in self.flattened_fields(base, ty, max_depth) {
+            let _ = writeln!(s, "{}: {}", path, ty);
+        }
+        s
+    }
+
+    fn flatten_fields_into(
+        self,
+        path: String,
+        ty: Ty<'tcx>,
+        depth: usize,
+        stack: &mut Vec<Ty<'tcx>>,
+        fields: &mut Vec<(String, Ty<'tcx>)>,
+    ) {
+        let field_tys: Vec<(String, Ty<'tcx>)> = match ty.sty {
+            _ if depth == 0 || stack.contains(&ty) => vec![],
+            ty::Adt(def, substs) if def.is_struct() => {
+                def.non_enum_variant().fields.iter().map(|field| {
+                    (field.ident.to_string(), field.ty(self, substs))
+                }).collect()
+            }
+            ty::Tuple(tys) => {
+                tys.iter().enumerate().map(|(i, ty)| (i.to_string(), ty.expect_ty())).collect()
+            }
+            _ => vec![],
+        };
+
+        // Unit structs and `()` have no fields to flatten into.
+        if field_tys.is_empty() {
+            fields.push((path, ty));
+            return;
+        }
+
+        stack.push(ty);
+        for (name, field_ty) in field_tys {
+            let field_path = format!("{}.{}", path, name);
+            self.flatten_fields_into(field_path, field_ty, depth - 1, stack, fields);
+        }
+        stack.pop();
+    }
+}
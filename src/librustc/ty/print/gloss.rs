@@ -0,0 +1,192 @@
+//! Short English descriptions ("glosses") of types, to accompany their
+//! printed form in educational output, e.g. `Vec<u32>` is "a growable array
+//! of 32-bit unsigned integers". Well-known library types are described
+//! using the templates in `KNOWN_ADTS`, other composite types by describing
+//! their components, and anything else as "a value of type `X`".
+
+use crate::hir;
+use crate::ty::{self, Ty, TyCtxt};
+
+/// The templates for well-known ADTs, as `(crate, name, singular, plural)`,
+/// where `{sN}`, `{pN}` and `{aN}` stand for the singular, plural and
+/// singular-with-article descriptions of the `N`th type argument.
+const KNOWN_ADTS: &[(&str, &str, &str, &str)] = &[
+    ("alloc", "Vec", "growable array of {p0}", "growable arrays of {p0}"),
+    ("alloc", "String", "growable string", "growable strings"),
+    ("alloc", "Box", "box containing {a0}", "boxes containing {p0}"),
+    ("alloc", "Rc", "reference-counted pointer to {a0}", "reference-counted pointers to {p0}"),
+    (
+        "alloc",
+        "Arc",
+        "atomically reference-counted pointer to {a0}",
+        "atomically reference-counted pointers to {p0}",
+    ),
+    ("core", "Option", "optional {s0}", "optional {p0}"),
+    ("core", "Result", "result of either {a0} or {a1}", "results of either {p0} or {p1}"),
+    ("std", "HashMap", "hash map from {p0} to {p1}", "hash maps from {p0} to {p1}"),
+    ("std", "HashSet", "hash set of {p0}", "hash sets of {p0}"),
+];
+
+/// A description of a type, without an article, in both singular and plural.
+struct Gloss {
+    singular: String,
+    plural: String,
+}
+
+impl Gloss {
+    fn new(singular: impl Into<String>, plural: impl Into<String>) -> Self {
+        Gloss { singular: singular.into(), plural: plural.into() }
+    }
+
+    fn with_article(&self) -> String {
+        let article = if starts_with_vowel_sound(&self.singular) { "an" } else { "a" };
+        format!("{} {}", article, self.singular)
+    }
+}
+
+/// Returns whether `s` takes "an" rather than "a", going by how it sounds,
+/// e.g. "an unsigned integer" and "an 8-bit integer", but "a unit value",
+/// "a `usize`" and "a `u8`", where the `u` sounds like "you".
+fn starts_with_vowel_sound(s: &str) -> bool {
+    let s = s.trim_start_matches('`');
+    if s.starts_with('u') {
+        const YOU_SOUND: &[&str] = &["uni", "usa", "use", "usi", "usu", "uti"];
+        let is_uint_ty = s[1..].starts_with(|c: char| c.is_ascii_digit());
+        // "uni" sounds like "you-nee", except as the prefix "un-", e.g. in "uninhabited".
+        let is_un_prefix = s.starts_with("unin") || s.starts_with("unim");
+        return is_un_prefix || !is_uint_ty && !YOU_SOUND.iter().any(|p| s.starts_with(p));
+    }
+    s.starts_with(|c: char| "aeio8".contains(c)) || s.starts_with("11-") || s.starts_with("18-")
+}
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns `ty` as it's normally printed, along with its description,
+    /// e.g. `("Vec<u32>", "a growable array of 32-bit unsigned integers")`.
+    pub fn type_with_gloss(self, ty: Ty<'tcx>) -> (String, String) {
+        (ty.to_string(), self.type_gloss(ty).with_article())
+    }
+
+    fn type_gloss(self, ty: Ty<'tcx>) -> Gloss {
+        let int_gloss = |signedness: &str, bits: Option<usize>| {
+            let size = bits.map_or("pointer-sized".to_string(), |bits| format!("{}-bit", bits));
+            let singular = format!("{} {} integer", size, signedness);
+            Gloss::new(singular.clone(), singular + "s")
+        };
+        match ty.sty {
+            ty::Bool => Gloss::new("boolean", "booleans"),
+            ty::Char => Gloss::new("character", "characters"),
+            ty::Int(t) => int_gloss("signed", t.bit_width()),
+            ty::Uint(t) => int_gloss("unsigned", t.bit_width()),
+            ty::Float(t) => {
+                let singular = format!("{}-bit floating-point number", t.bit_width());
+                Gloss::new(singular.clone(), singular + "s")
+            }
+            ty::Str => Gloss::new("string slice", "string slices"),
+            ty::Never => Gloss::new("value that can never exist", "values that can never exist"),
+            ty::Tuple(tys) if tys.is_empty() => Gloss::new("unit value", "unit values"),
+            ty::Tuple(tys) => {
+                let glosses: Vec<_> = tys.types().map(|ty| self.type_gloss(ty).with_article())
+                    .collect();
+                let (last, init) = glosses.split_last().unwrap();
+                let elems = if init.is_empty() {
+                    last.clone()
+                } else {
+                    format!("{} and {}", init.join(", "), last)
+                };
+                Gloss::new(format!("tuple of {}", elems), format!("tuples of {}", elems))
+            }
+            ty::Array(ty, len) => {
+                let elems = self.type_gloss(ty).plural;
+                match len.assert_usize(self) {
+                    Some(len) => Gloss::new(
+                        format!("array of {} {}", len, elems),
+                        format!("arrays of {} {}", len, elems),
+                    ),
+                    None => Gloss::new(
+                        format!("fixed-size array of {}", elems),
+                        format!("fixed-size arrays of {}", elems),
+                    ),
+                }
+            }
+            ty::Slice(ty) => {
+                let elems = self.type_gloss(ty).plural;
+                Gloss::new(format!("slice of {}", elems), format!("slices of {}", elems))
+            }
+            ty::Ref(_, pointee, mutbl) |
+            ty::RawPtr(ty::TypeAndMut { ty: pointee, mutbl }) => {
+                let is_ref = if let ty::Ref(..) = ty.sty { true } else { false };
+                let kind = match (is_ref, mutbl) {
+                    (true, hir::MutImmutable) => "shared reference",
+                    (true, hir::MutMutable) => "mutable reference",
+                    (false, hir::MutImmutable) => "raw pointer",
+                    (false, hir::MutMutable) => "mutable raw pointer",
+                };
+                let pointee = self.type_gloss(pointee).with_article();
+                Gloss::new(format!("{} to {}", kind, pointee), format!("{}s to {}", kind, pointee))
+            }
+            ty::FnPtr(_) => Gloss::new("function pointer", "function pointers"),
+            ty::Closure(..) => Gloss::new("closure", "closures"),
+            ty::Generator(..) => Gloss::new("generator", "generators"),
+            ty::Dynamic(..) => {
+                let singular = format!("value of some type implementing `{}`", ty);
+                let plural = format!("values of types implementing `{}`", ty);
+                Gloss::new(singular, plural)
+            }
+            ty::Param(_) => {
+                let singular = format!("value of the generic type `{}`", ty);
+                let plural = format!("values of the generic type `{}`", ty);
+                Gloss::new(singular, plural)
+            }
+            ty::Adt(def, substs) => {
+                let crate_name = self.crate_name(def.did.krate).as_str();
+                let name = self.item_name(def.did).as_str();
+                let template = KNOWN_ADTS.iter().find(|&&(krate, item, ..)| {
+                    krate == &*crate_name && item == &*name
+                });
+                match template {
+                    Some(&(_, _, singular, plural)) => {
+                        let args: Vec<_> = substs.types().map(|ty| self.type_gloss(ty)).collect();
+                        let fill = |template: &str| {
+                            let mut s = template.to_string();
+                            for (i, arg) in args.iter().enumerate() {
+                                s = s.replace(&format!("{{s{}}}", i), &arg.singular)
+                                    .replace(&format!("{{p{}}}", i), &arg.plural)
+                                    .replace(&format!("{{a{}}}", i), &arg.with_article());
+                            }
+                            s
+                        };
+                        Gloss::new(fill(singular), fill(plural))
+                    }
+                    None => self.unknown_type_gloss(ty),
+                }
+            }
+            _ => self.unknown_type_gloss(ty),
+        }
+    }
+
+    fn unknown_type_gloss(self, ty: Ty<'tcx>) -> Gloss {
+        Gloss::new(format!("value of type `{}`", ty), format!("values of type `{}`", ty))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::starts_with_vowel_sound;
+
+    #[test]
+    fn test_starts_with_vowel_sound() {
+        assert!(!starts_with_vowel_sound("unit value"));
+        assert!(!starts_with_vowel_sound("union"));
+        assert!(starts_with_vowel_sound("unsigned integer"));
+        assert!(starts_with_vowel_sound("uninhabited type"));
+        assert!(!starts_with_vowel_sound("`usize`"));
+        assert!(!starts_with_vowel_sound("usize"));
+        assert!(!starts_with_vowel_sound("u8"));
+        assert!(!starts_with_vowel_sound("u16"));
+        assert!(starts_with_vowel_sound("8-bit integer"));
+        assert!(starts_with_vowel_sound("18-tuple"));
+        assert!(!starts_with_vowel_sound("16-bit integer"));
+        assert!(starts_with_vowel_sound("optional boolean"));
+        assert!(!starts_with_vowel_sound("boolean"));
+    }
+}
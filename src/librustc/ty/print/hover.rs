@@ -0,0 +1,68 @@
+//! Printing for "hover" queries in IDE backends, which need to map a byte
+//! offset into printed output (e.g. reported by a client) back to the item
+//! that was printed there, without re-printing or searching the output.
+
+use crate::hir::def::Namespace;
+use crate::hir::def_id::DefId;
+use crate::ty::TyCtxt;
+use crate::ty::print::FmtPrinter;
+
+use std::fmt;
+
+/// Prints with a `FmtPrinter` into a `String`, recording which `DefId`'s
+/// path covers each byte of the output, to produce a `HoverText`.
+pub struct HoverPrinter<'a, 'gcx, 'tcx> {
+    cx: FmtPrinter<'a, 'gcx, 'tcx, String>,
+}
+
+/// Printed output, along with the innermost `DefId` printed at each byte.
+pub struct HoverText {
+    pub text: String,
+
+    /// Indexed by byte offset into `text`, with every byte of a multi-byte
+    /// UTF-8 character mapping to the same `DefId` as the whole character.
+    def_ids: Vec<Option<DefId>>,
+}
+
+impl HoverPrinter<'a, 'gcx, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>, ns: Namespace) -> Self {
+        let mut cx = FmtPrinter::new(tcx, String::new(), ns);
+        cx.record_def_paths();
+        HoverPrinter { cx }
+    }
+
+    /// Prints with the underlying `FmtPrinter`, e.g.
+    /// `HoverPrinter::new(tcx, ns).print(|cx| cx.print_type(ty))?.finish()`.
+    pub fn print(
+        self,
+        f: impl FnOnce(FmtPrinter<'a, 'gcx, 'tcx, String>)
+            -> Result<FmtPrinter<'a, 'gcx, 'tcx, String>, fmt::Error>,
+    ) -> Result<Self, fmt::Error> {
+        Ok(HoverPrinter { cx: f(self.cx)? })
+    }
+
+    pub fn finish(self) -> HoverText {
+        let mut ranges = self.cx.def_path_ranges().to_vec();
+        let text = self.cx.into_fmt();
+
+        // Paths are properly nested (e.g. `std::vec::Vec<Foo>` contains both
+        // `std::vec` and `Foo`), so filling in the longest ranges first leaves
+        // every byte with the innermost `DefId` covering it.
+        ranges.sort_by_key(|(range, _)| std::cmp::Reverse(range.len()));
+        let mut def_ids = vec![None; text.len()];
+        for (range, def_id) in ranges {
+            for slot in &mut def_ids[range] {
+                *slot = Some(def_id);
+            }
+        }
+
+        HoverText { text, def_ids }
+    }
+}
+
+impl HoverText {
+    /// Returns the innermost `DefId` printed at byte `offset` of `text`, if any.
+    pub fn def_id_at(&self, offset: usize) -> Option<DefId> {
+        self.def_ids.get(offset).cloned().and_then(|def_id| def_id)
+    }
+}
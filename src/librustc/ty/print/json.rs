@@ -0,0 +1,321 @@
+//! A `Printer` producing JSON trees describing "type-system entities",
+//! for tools (e.g. IDEs) consuming types as structured data, instead of
+//! parsing their pretty-printed form, e.g. `Option<&'a u32>`:
+//!
+//! ```text
+//! {"args":[{"kind":"Ref","mutable":false,"pointee":{"kind":"Uint","name":"u32"},
+//!   "region":{"kind":"Named","name":"'a"}}],"def_path":[{"name":"core"},
+//!   {"disambiguator":0,"name":"option"},{"disambiguator":0,"name":"Option"}],"kind":"Adt"}
+//! ```
+//!
+//! Unlike other printers, `JsonPrinter` is `Copy`, and returns the JSON
+//! for everything printed, instead of itself, so the values printed by
+//! e.g. `print_prefix` in `path_append` are simply extended and passed on.
+
+use crate::hir;
+use crate::hir::def::Namespace;
+use crate::hir::def_id::{CrateNum, DefId};
+use crate::hir::map::{DefPathData, DisambiguatedDefPathData};
+use crate::ty::{self, Ty, TyCtxt};
+use crate::ty::print::{FmtPrinterBuilder, Print, PrintOptions, Printer};
+use crate::ty::subst::Kind;
+use serialize::json::Json;
+
+#[derive(Copy, Clone)]
+pub struct JsonPrinter<'a, 'gcx, 'tcx> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    options: PrintOptions,
+}
+
+/// Builds a JSON object from its `(key, value)` entries.
+fn object(entries: Vec<(&str, Json)>) -> Json {
+    Json::Object(entries.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+}
+
+/// Builds a JSON object with a `kind` entry, along with `entries`.
+fn node(kind: &str, mut entries: Vec<(&str, Json)>) -> Json {
+    entries.push(("kind", Json::String(kind.to_string())));
+    object(entries)
+}
+
+/// Returns the segments of the path `path`, to append to them.
+fn into_segments(path: Json) -> Vec<Json> {
+    match path {
+        Json::Array(segments) => segments,
+        path => bug!("JsonPrinter: expected a path, found {}", path),
+    }
+}
+
+impl JsonPrinter<'a, 'gcx, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>) -> Self {
+        JsonPrinter { tcx, options: PrintOptions::default() }
+    }
+
+    fn print_kinds(self, args: &[Kind<'tcx>]) -> Json {
+        Json::Array(args.iter().map(|arg| arg.print(self).unwrap()).collect())
+    }
+
+    fn print_tys(self, tys: impl Iterator<Item = Ty<'tcx>>) -> Json {
+        Json::Array(tys.map(|ty| self.print_type(ty).unwrap()).collect())
+    }
+
+    /// Prints the path of `def_id` (without generic args) and `substs`,
+    /// as the `def_path` and `args` entries of a `kind` node.
+    fn print_path_of(
+        self,
+        kind: &str,
+        def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+    ) -> Result<Json, !> {
+        let def_path = self.print_def_path(def_id, &[])?;
+        Ok(node(kind, vec![("def_path", def_path), ("args", self.print_kinds(substs))]))
+    }
+
+    fn print_opt_trait_ref(
+        self,
+        entries: &mut Vec<(&str, Json)>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<(), !> {
+        if let Some(trait_ref) = trait_ref {
+            let trait_ref = self.print_path_of("Trait", trait_ref.def_id, trait_ref.substs)?;
+            entries.push(("trait", trait_ref));
+        }
+        Ok(())
+    }
+}
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns the JSON tree describing `ty`, see `JsonPrinter`.
+    pub fn type_json(self, ty: Ty<'tcx>) -> Json {
+        JsonPrinter::new(self).print_type(ty).unwrap()
+    }
+}
+
+impl Printer<'gcx, 'tcx> for JsonPrinter<'_, 'gcx, 'tcx> {
+    type Error = !;
+
+    type Path = Json;
+    type Region = Json;
+    type Type = Json;
+    type DynExistential = Json;
+    type Const = Json;
+
+    fn tcx(&'a self) -> TyCtxt<'a, 'gcx, 'tcx> {
+        self.tcx
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.options
+    }
+
+    fn set_print_options(&mut self, options: PrintOptions) {
+        self.options = options;
+    }
+
+    fn print_region(
+        self,
+        region: ty::Region<'_>,
+    ) -> Result<Self::Region, Self::Error> {
+        let mut name = String::new();
+        let _ = FmtPrinterBuilder::new(self.tcx, Namespace::TypeNS)
+            .options(self.options)
+            .build(&mut name)
+            .print_region(region);
+        if name.is_empty() || name == "'_" {
+            Ok(node("Anonymous", vec![]))
+        } else {
+            Ok(node("Named", vec![("name", Json::String(name))]))
+        }
+    }
+
+    fn print_type(
+        self,
+        ty: Ty<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        let name = |name: &str| vec![("name", Json::String(name.to_string()))];
+        let json = match ty.sty {
+            ty::Bool => node("Bool", vec![]),
+            ty::Char => node("Char", vec![]),
+            ty::Int(t) => node("Int", name(t.ty_to_string())),
+            ty::Uint(t) => node("Uint", name(t.ty_to_string())),
+            ty::Float(t) => node("Float", name(t.ty_to_string())),
+            ty::Str => node("Str", vec![]),
+            ty::Never => node("Never", vec![]),
+
+            ty::Adt(def, substs) => self.print_path_of("Adt", def.did, substs)?,
+            ty::Foreign(def_id) => self.print_path_of("Foreign", def_id, &[])?,
+            ty::FnDef(def_id, substs) => self.print_path_of("FnDef", def_id, substs)?,
+            ty::Closure(def_id, substs) => {
+                self.print_path_of("Closure", def_id, substs.substs)?
+            }
+            ty::Generator(def_id, substs, _) => {
+                self.print_path_of("Generator", def_id, substs.substs)?
+            }
+            ty::Opaque(def_id, substs) => self.print_path_of("Opaque", def_id, substs)?,
+            ty::Projection(ref data) => {
+                self.print_path_of("Projection", data.item_def_id, data.substs)?
+            }
+            ty::RawPtr(mt) => node("RawPtr", vec![
+                ("mutable", Json::Boolean(mt.mutbl == hir::MutMutable)),
+                ("pointee", self.print_type(mt.ty)?),
+            ]),
+            ty::Ref(r, ty, mutbl) => node("Ref", vec![
+                ("region", self.print_region(r)?),
+                ("mutable", Json::Boolean(mutbl == hir::MutMutable)),
+                ("pointee", self.print_type(ty)?),
+            ]),
+            ty::Tuple(tys) => node("Tuple", vec![("elements", self.print_kinds(tys))]),
+            ty::Array(ty, len) => node("Array", vec![
+                ("element", self.print_type(ty)?),
+                ("len", self.print_const(len)?),
+            ]),
+            ty::Slice(ty) => node("Slice", vec![("element", self.print_type(ty)?)]),
+            ty::FnPtr(sig) => {
+                let sig = sig.skip_binder();
+                node("FnPtr", vec![
+                    ("unsafe", Json::Boolean(sig.unsafety == hir::Unsafety::Unsafe)),
+                    ("abi", Json::String(sig.abi.name().to_string())),
+                    ("inputs", self.print_tys(sig.inputs().iter().cloned())),
+                    ("c_variadic", Json::Boolean(sig.c_variadic)),
+                    ("output", self.print_type(sig.output())?),
+                ])
+            }
+            ty::Dynamic(data, r) => node("Dynamic", vec![
+                ("bounds", self.print_dyn_existential(*data.skip_binder())?),
+                ("region", self.print_region(r)?),
+            ]),
+            ty::Param(ref param_ty) => {
+                let name = self.options.sanitized_ident(&param_ty.name.as_str()).into_owned();
+                node("Param", vec![
+                    ("name", Json::String(name)),
+                    ("index", Json::U64(param_ty.index as u64)),
+                ])
+            }
+
+            // These don't have a stable structure worth exposing to tools.
+            ty::Infer(_) |
+            ty::Bound(..) |
+            ty::Placeholder(_) |
+            ty::UnnormalizedProjection(_) |
+            ty::GeneratorWitness(_) |
+            ty::Error => node("Other", vec![("debug", Json::String(format!("{:?}", ty)))]),
+        };
+        Ok(json)
+    }
+
+    fn print_fn_sig(
+        self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        let fn_ptr = self.tcx().mk_fn_ptr(ty::Binder::bind(sig));
+        self.print_type(fn_ptr)
+    }
+
+    fn print_dyn_existential(
+        self,
+        predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
+    ) -> Result<Self::DynExistential, Self::Error> {
+        let predicates = predicates.iter().map(|predicate| match *predicate {
+            // The args of existential trait refs already exclude `Self`.
+            ty::ExistentialPredicate::Trait(trait_ref) => {
+                self.print_path_of("Trait", trait_ref.def_id, trait_ref.substs)
+            }
+            ty::ExistentialPredicate::Projection(projection) => {
+                let name = self.tcx.associated_item(projection.item_def_id).ident.as_str();
+                let name = self.options.sanitized_ident(&name).into_owned();
+                Ok(node("Projection", vec![
+                    ("name", Json::String(name)),
+                    ("ty", self.print_type(projection.ty)?),
+                ]))
+            }
+            ty::ExistentialPredicate::AutoTrait(def_id) => {
+                self.print_path_of("AutoTrait", def_id, &[])
+            }
+        }.unwrap()).collect();
+        Ok(Json::Array(predicates))
+    }
+
+    fn print_const(
+        self,
+        ct: &'tcx ty::Const<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        let mut value = String::new();
+        let _ = FmtPrinterBuilder::new(self.tcx, Namespace::ValueNS)
+            .options(self.options)
+            .build(&mut value)
+            .print_const(ct);
+        Ok(node("Const", vec![("ty", self.print_type(ct.ty)?), ("value", Json::String(value))]))
+    }
+
+    fn path_crate(
+        self,
+        cnum: CrateNum,
+    ) -> Result<Self::Path, Self::Error> {
+        let name = self.tcx.original_crate_name(cnum).as_str();
+        let name = self.options.sanitized_ident(&name).into_owned();
+        Ok(Json::Array(vec![object(vec![("name", Json::String(name))])]))
+    }
+    fn path_qualified(
+        self,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        let mut entries = vec![("self", self.print_type(self_ty)?)];
+        self.print_opt_trait_ref(&mut entries, trait_ref)?;
+        Ok(Json::Array(vec![object(entries)]))
+    }
+
+    fn path_append_impl(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        disambiguated_data: &DisambiguatedDefPathData,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        let mut segments = into_segments(print_prefix(self)?);
+        let mut entries = vec![
+            ("impl_for", self.print_type(self_ty)?),
+            ("disambiguator", Json::U64(disambiguated_data.disambiguator as u64)),
+        ];
+        self.print_opt_trait_ref(&mut entries, trait_ref)?;
+        segments.push(object(entries));
+        Ok(Json::Array(segments))
+    }
+    fn path_append(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        disambiguated_data: &DisambiguatedDefPathData,
+    ) -> Result<Self::Path, Self::Error> {
+        let path = print_prefix(self)?;
+
+        // Skip `::{{constructor}}` on tuple/unit structs.
+        if let DefPathData::Ctor = disambiguated_data.data {
+            return Ok(path);
+        }
+
+        let mut segments = into_segments(path);
+        let name = disambiguated_data.data.as_interned_str();
+        let name = self.options.sanitized_ident(&name.as_str()).into_owned();
+        segments.push(object(vec![
+            ("name", Json::String(name)),
+            ("disambiguator", Json::U64(disambiguated_data.disambiguator as u64)),
+        ]));
+        Ok(Json::Array(segments))
+    }
+    fn path_generic_args(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        args: &[Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        let mut segments = into_segments(print_prefix(self)?);
+        if args.is_empty() {
+            return Ok(Json::Array(segments));
+        }
+
+        // Attach the arguments to the last segment.
+        if let Some(Json::Object(segment)) = segments.last_mut() {
+            segment.insert("args".to_string(), self.print_kinds(args));
+        }
+        Ok(Json::Array(segments))
+    }
+}
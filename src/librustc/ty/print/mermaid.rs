@@ -0,0 +1,433 @@
+//! A `Printer` rendering the structure of types as Mermaid flowcharts, for
+//! embedding in Markdown documentation (e.g. rendered by GitHub), with one
+//! node per component, linked to its own components. E.g. `Option<&'a u32>`:
+//!
+//! ```text
+//! graph TD
+//!     n0["std::option::Option"]
+//!     n1["#amp;"]
+//!     n2["'a"]
+//!     n1 --> n2
+//!     n3["u32"]
+//!     n1 --> n3
+//!     n0 --> n1
+//! ```
+//!
+//! Links are listed after everything their target links to. Paths are printed
+//! as a single node, labeled with the path without its generic args (which
+//! become the nodes it links to), by `print_def_path`.
+
+use crate::hir;
+use crate::hir::def::Namespace;
+use crate::hir::def_id::{CrateNum, DefId};
+use crate::hir::map::{DefPathData, DisambiguatedDefPathData};
+use crate::ty::{self, Ty, TyCtxt};
+use crate::ty::print::{FmtPrinter, Print, PrintOptions, Printer};
+use crate::ty::subst::{Kind, UnpackedKind};
+use rustc_target::spec::abi::Abi;
+
+use std::fmt;
+
+/// The identifier of a node, of the form `nN`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NodeId(usize);
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "n{}", self.0)
+    }
+}
+
+/// Quotes `s` as a Mermaid label, escaping the characters Mermaid would
+/// otherwise interpret (as syntax, or as HTML) with their entity codes.
+fn label(s: &str) -> String {
+    let mut label = String::with_capacity(s.len() + 2);
+    label.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => label.push_str("#quot;"),
+            '#' => label.push_str("#35;"),
+            '&' => label.push_str("#amp;"),
+            '<' => label.push_str("#lt;"),
+            '>' => label.push_str("#gt;"),
+            '\n' => label.push(' '),
+            c => label.push(c),
+        }
+    }
+    label.push('"');
+    label
+}
+
+/// Returns the output of `print` on a `FmtPrinter`, in the namespace `ns`.
+fn fmt_label<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    ns: Namespace,
+    print: impl FnOnce(FmtPrinter<'a, 'gcx, 'tcx, &mut String>)
+        -> Result<FmtPrinter<'a, 'gcx, 'tcx, &mut String>, fmt::Error>,
+) -> String {
+    let mut s = String::new();
+    let _ = print(FmtPrinter::new(tcx, &mut s, ns));
+    s
+}
+
+pub struct MermaidPrinter<'a, 'gcx, 'tcx> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    options: PrintOptions,
+    lines: Vec<String>,
+    node_count: usize,
+
+    /// The node of the last component printed.
+    last: Option<NodeId>,
+}
+
+impl MermaidPrinter<'a, 'gcx, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>) -> Self {
+        MermaidPrinter {
+            tcx,
+            options: PrintOptions::default(),
+            lines: vec![],
+            node_count: 0,
+            last: None,
+        }
+    }
+
+    /// Returns the node of the last component printed, which after
+    /// e.g. a `print_type` call is the node of the whole type.
+    pub fn root(&self) -> Option<NodeId> {
+        self.last
+    }
+
+    /// Returns the flowchart of everything printed so far.
+    pub fn into_diagram(self) -> String {
+        let mut diagram = "graph TD\n".to_string();
+        for line in self.lines {
+            diagram.push_str("    ");
+            diagram.push_str(&line);
+            diagram.push('\n');
+        }
+        diagram
+    }
+
+    fn last(&self) -> NodeId {
+        self.last.expect("MermaidPrinter: nothing was printed")
+    }
+
+    /// Declares a fresh node labeled `name`, which becomes the last one.
+    fn node(mut self, name: &str) -> Self {
+        let id = NodeId(self.node_count);
+        self.node_count += 1;
+        self.lines.push(format!("{}[{}]", id, label(name)));
+        self.last = Some(id);
+        self
+    }
+
+    /// Prints a component of `parent` with `print`, linking `parent` to it
+    /// (with `edge` as the label of the link, if any), which then becomes
+    /// the last node again, so that more of its components can be printed.
+    fn child(
+        mut self,
+        parent: NodeId,
+        edge: Option<&str>,
+        print: impl FnOnce(Self) -> Result<Self, !>,
+    ) -> Result<Self, !> {
+        self = print(self)?;
+        let child = self.last();
+        match edge {
+            Some(edge) => self.lines.push(format!("{} -->|{}| {}", parent, label(edge), child)),
+            None => self.lines.push(format!("{} --> {}", parent, child)),
+        }
+        self.last = Some(parent);
+        Ok(self)
+    }
+
+    /// Prints the generic args `args` as components of the last node,
+    /// skipping erased lifetimes, which carry no information.
+    fn print_args(mut self, args: &[Kind<'tcx>]) -> Result<Self, !> {
+        let parent = self.last();
+        for &arg in args {
+            if let UnpackedKind::Lifetime(ty::ReErased) = arg.unpack() {
+                continue;
+            }
+            self = self.child(parent, None, |cx| arg.print(cx))?;
+        }
+        Ok(self)
+    }
+
+    fn print_opt_trait_ref(
+        self,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self, !> {
+        match trait_ref {
+            Some(trait_ref) => {
+                let parent = self.last();
+                self.child(parent, Some("as"), |cx| {
+                    cx.print_def_path(trait_ref.def_id, trait_ref.substs)
+                })
+            }
+            None => Ok(self),
+        }
+    }
+}
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns the Mermaid flowchart of `ty`, see `MermaidPrinter`.
+    pub fn type_mermaid_diagram(self, ty: Ty<'tcx>) -> String {
+        MermaidPrinter::new(self).print_type(ty).unwrap().into_diagram()
+    }
+}
+
+impl Printer<'gcx, 'tcx> for MermaidPrinter<'_, 'gcx, 'tcx> {
+    type Error = !;
+
+    type Path = Self;
+    type Region = Self;
+    type Type = Self;
+    type DynExistential = Self;
+    type Const = Self;
+
+    fn tcx(&'a self) -> TyCtxt<'a, 'gcx, 'tcx> {
+        self.tcx
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.options
+    }
+
+    fn set_print_options(&mut self, options: PrintOptions) {
+        self.options = options;
+    }
+
+    fn print_def_path(
+        self,
+        def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        let path = fmt_label(self.tcx, Namespace::TypeNS, |cx| cx.print_def_path(def_id, &[]));
+        self.node(&path).print_args(substs)
+    }
+
+    fn print_region(
+        self,
+        region: ty::Region<'_>,
+    ) -> Result<Self::Region, Self::Error> {
+        let name = match *region {
+            ty::ReErased => "'_".to_string(),
+            _ => fmt_label(self.tcx, Namespace::TypeNS, |cx| cx.print_region(region)),
+        };
+        Ok(self.node(&name))
+    }
+
+    fn print_type(
+        mut self,
+        ty: Ty<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        match ty.sty {
+            ty::Bool => Ok(self.node("bool")),
+            ty::Char => Ok(self.node("char")),
+            ty::Int(t) => Ok(self.node(t.ty_to_string())),
+            ty::Uint(t) => Ok(self.node(t.ty_to_string())),
+            ty::Float(t) => Ok(self.node(t.ty_to_string())),
+            ty::Str => Ok(self.node("str")),
+            ty::Never => Ok(self.node("!")),
+
+            ty::Adt(def, substs) => self.print_def_path(def.did, substs),
+            ty::Foreign(def_id) => self.print_def_path(def_id, &[]),
+            ty::FnDef(def_id, substs) |
+            ty::Opaque(def_id, substs) => self.print_def_path(def_id, substs),
+            ty::Closure(def_id, ty::ClosureSubsts { substs }) |
+            ty::Generator(def_id, ty::GeneratorSubsts { substs }, _) => {
+                self.print_def_path(def_id, substs)
+            }
+            ty::Projection(ref data) => self.print_def_path(data.item_def_id, data.substs),
+            ty::RawPtr(mt) => {
+                self = self.node(match mt.mutbl {
+                    hir::MutMutable => "*mut",
+                    hir::MutImmutable => "*const",
+                });
+                let ptr = self.last();
+                self.child(ptr, None, |cx| cx.print_type(mt.ty))
+            }
+            ty::Ref(r, ty, mutbl) => {
+                self = self.node(match mutbl {
+                    hir::MutMutable => "&mut",
+                    hir::MutImmutable => "&",
+                });
+                let reference = self.last();
+                if *r != ty::ReErased {
+                    self = self.child(reference, None, |cx| cx.print_region(r))?;
+                }
+                self.child(reference, None, |cx| cx.print_type(ty))
+            }
+            ty::Tuple(tys) => {
+                self = self.node("()");
+                let tuple = self.last();
+                for (i, ty) in tys.iter().enumerate() {
+                    self = self.child(tuple, Some(&format!(".{}", i)), |cx| {
+                        cx.print_type(ty.expect_ty())
+                    })?;
+                }
+                Ok(self)
+            }
+            ty::Array(ty, len) => {
+                self = self.node("[_; _]");
+                let array = self.last();
+                self = self.child(array, None, |cx| cx.print_type(ty))?;
+                self.child(array, Some("len"), |cx| cx.print_const(len))
+            }
+            ty::Slice(ty) => {
+                self = self.node("[_]");
+                let slice = self.last();
+                self.child(slice, None, |cx| cx.print_type(ty))
+            }
+            ty::FnPtr(sig) => {
+                let sig = sig.skip_binder();
+                let mut name = String::new();
+                if sig.unsafety == hir::Unsafety::Unsafe {
+                    name.push_str("unsafe ");
+                }
+                if sig.abi != Abi::Rust {
+                    name.push_str(&format!("extern {} ", sig.abi));
+                }
+                name.push_str(if sig.c_variadic { "fn(...)" } else { "fn" });
+                self = self.node(&name);
+                let fn_ptr = self.last();
+                for (i, &input) in sig.inputs().iter().enumerate() {
+                    self = self.child(fn_ptr, Some(&i.to_string()), |cx| cx.print_type(input))?;
+                }
+                self.child(fn_ptr, Some("->"), |cx| cx.print_type(sig.output()))
+            }
+            ty::Dynamic(data, r) => {
+                self = self.node("dyn");
+                let dynamic = self.last();
+                self = self.child(dynamic, None, |cx| {
+                    cx.print_dyn_existential(*data.skip_binder())
+                })?;
+                self.child(dynamic, None, |cx| cx.print_region(r))
+            }
+            ty::Param(ref param_ty) => {
+                let name = param_ty.name.as_str();
+                let name = self.options.sanitized_ident(&name);
+                Ok(self.node(&name))
+            }
+
+            // These don't have a structure worth drawing.
+            ty::Infer(_) |
+            ty::Bound(..) |
+            ty::Placeholder(_) |
+            ty::UnnormalizedProjection(_) |
+            ty::GeneratorWitness(_) |
+            ty::Error => {
+                let name = fmt_label(self.tcx, Namespace::TypeNS, |cx| cx.print_type(ty));
+                Ok(self.node(&name))
+            }
+        }
+    }
+
+    fn print_fn_sig(
+        self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        let fn_ptr = self.tcx().mk_fn_ptr(ty::Binder::bind(sig));
+        self.print_type(fn_ptr)
+    }
+
+    fn print_dyn_existential(
+        mut self,
+        predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
+    ) -> Result<Self::DynExistential, Self::Error> {
+        self = self.node("+");
+        let bounds = self.last();
+        for predicate in predicates.iter() {
+            self = self.child(bounds, None, |cx| match *predicate {
+                ty::ExistentialPredicate::Trait(trait_ref) => {
+                    // The `Self` type is left out of `ExistentialTraitRef`'s substs.
+                    cx.print_def_path(trait_ref.def_id, trait_ref.substs)
+                }
+                ty::ExistentialPredicate::Projection(projection) => {
+                    let name = cx.tcx.associated_item(projection.item_def_id).ident;
+                    let label = format!("{} =", cx.options.sanitized_ident(&name.as_str()));
+                    let cx = cx.node(&label);
+                    let binding = cx.last();
+                    cx.child(binding, None, |cx| cx.print_type(projection.ty))
+                }
+                ty::ExistentialPredicate::AutoTrait(def_id) => cx.print_def_path(def_id, &[]),
+            })?;
+        }
+        Ok(self)
+    }
+
+    fn print_const(
+        self,
+        ct: &'tcx ty::Const<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        let value = fmt_label(self.tcx, Namespace::ValueNS, |cx| cx.print_const(ct));
+        Ok(self.node(&value))
+    }
+
+    // Paths are printed by `print_def_path` as a single node, so these are
+    // only used by callers printing partial paths themselves, which get a
+    // node for each segment, linked to the one before it.
+
+    fn path_crate(
+        self,
+        cnum: CrateNum,
+    ) -> Result<Self::Path, Self::Error> {
+        let name = self.tcx.original_crate_name(cnum).as_str();
+        let name = self.options.sanitized_ident(&name);
+        Ok(self.node(&name))
+    }
+    fn path_qualified(
+        mut self,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self = self.node("<_>");
+        let qualified = self.last();
+        self = self.child(qualified, None, |cx| cx.print_type(self_ty))?;
+        self.print_opt_trait_ref(trait_ref)
+    }
+
+    fn path_append_impl(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        _disambiguated_data: &DisambiguatedDefPathData,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?;
+        let prefix = self.last();
+        self = self.node("impl");
+        let impl_node = self.last();
+        self.lines.push(format!("{} --> {}", prefix, impl_node));
+        self = self.child(impl_node, Some("for"), |cx| cx.print_type(self_ty))?;
+        self.print_opt_trait_ref(trait_ref)
+    }
+    fn path_append(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        disambiguated_data: &DisambiguatedDefPathData,
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?;
+
+        // Skip `::{{constructor}}` on tuple/unit structs.
+        if let DefPathData::Ctor = disambiguated_data.data {
+            return Ok(self);
+        }
+
+        let prefix = self.last();
+        let name = disambiguated_data.data.as_interned_str().as_str();
+        let name = self.options.sanitized_ident(&name);
+        self = self.node(&name);
+        let segment = self.last();
+        self.lines.push(format!("{} --> {}", prefix, segment));
+        Ok(self)
+    }
+    fn path_generic_args(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        args: &[Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?;
+        self.print_args(args)
+    }
+}
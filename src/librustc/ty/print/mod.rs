@@ -1,7 +1,8 @@
+use crate::hir::def::DefKind;
 use crate::hir::map::{DefPathData, DisambiguatedDefPathData};
 use crate::hir::def_id::{CrateNum, DefId};
 use crate::ty::{self, DefIdTree, Ty, TyCtxt};
-use crate::ty::subst::{Kind, Subst};
+use crate::ty::subst::{Kind, Subst, UnpackedKind};
 
 use rustc_data_structures::fx::FxHashSet;
 
@@ -10,6 +11,25 @@ mod pretty;
 pub use self::pretty::*;
 
 pub mod obsolete;
+pub mod blanket_impls;
+pub mod byte_layout;
+pub mod canonical_key;
+pub mod coercion;
+pub mod crates;
+pub mod fields;
+pub mod gloss;
+pub mod hover;
+pub mod json;
+pub mod mermaid;
+pub mod prolog;
+pub mod repro;
+pub mod sanitizing;
+pub mod search_tokens;
+pub mod segments;
+pub mod sexp;
+pub mod token_stream;
+pub mod type_hash;
+pub mod yaml;
 
 // FIXME(eddyb) false positive, the lifetime parameters are used with `P:  Printer<...>`.
 #[allow(unused_lifetimes)]
@@ -39,6 +59,36 @@ pub trait Printer<'gcx: 'tcx, 'tcx>: Sized {
 
     fn tcx(&'a self) -> TyCtxt<'a, 'gcx, 'tcx>;
 
+    /// Whether to print verbosely, e.g. for `-Z verbose` debugging output,
+    /// including all generic args, even those which are the defaults of
+    /// their respective parameters.
+    fn should_print_verbose(&self) -> bool {
+        false
+    }
+
+    /// Whether to print every path in full, from the root of its crate, e.g.
+    /// for reproducible snapshots, instead of shortening the paths of impls
+    /// co-located with their self type or trait (see `default_print_impl_path`).
+    fn should_fully_qualify_paths(&self) -> bool {
+        false
+    }
+
+    /// Whether a value path (see `print_value_path`) is being printed, in which
+    /// the args of functions are all printed, even those matching defaults.
+    fn in_value_path(&self) -> bool {
+        false
+    }
+
+    /// The optional printing modes in effect, which printers support by
+    /// storing them and overriding this and `set_print_options`.
+    fn print_options(&self) -> PrintOptions {
+        PrintOptions::default()
+    }
+
+    /// Changes the optional printing modes (see `print_options`), e.g. to
+    /// print part of the output without some of them.
+    fn set_print_options(&mut self, _options: PrintOptions) {}
+
     fn print_def_path(
         self,
         def_id: DefId,
@@ -46,6 +96,16 @@ pub trait Printer<'gcx: 'tcx, 'tcx>: Sized {
     ) -> Result<Self::Path, Self::Error> {
         self.default_print_def_path(def_id, substs)
     }
+    /// Like `print_def_path` but for value paths, e.g. the path of a function
+    /// in its `FnDef` type, or of an associated const. Printers distinguishing
+    /// value paths (see `in_value_path`) override this to keep track of them.
+    fn print_value_path(
+        self,
+        def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        self.print_def_path(def_id, substs)
+    }
     fn print_impl_path(
         self,
         impl_def_id: DefId,
@@ -55,6 +115,12 @@ pub trait Printer<'gcx: 'tcx, 'tcx>: Sized {
     ) -> Result<Self::Path, Self::Error> {
         self.default_print_impl_path(impl_def_id, substs, self_ty, trait_ref)
     }
+    fn print_trait_ref(
+        self,
+        trait_ref: ty::TraitRef<'tcx>,
+    ) -> Result<Self::Path, Self::Error> {
+        self.print_def_path(trait_ref.def_id, trait_ref.substs)
+    }
 
     fn print_region(
         self,
@@ -66,6 +132,16 @@ pub trait Printer<'gcx: 'tcx, 'tcx>: Sized {
         ty: Ty<'tcx>,
     ) -> Result<Self::Type, Self::Error>;
 
+    /// Prints a bare function signature, e.g. `unsafe extern "C" fn(u8, ...) -> i32`.
+    /// Most printers print it as the `fn` pointer type with that signature, but
+    /// `PrettyPrinter` implementors print those types through this method, so
+    /// they have to print it with `pretty_print_fn_sig` instead, which is why
+    /// there's no default.
+    fn print_fn_sig(
+        self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error>;
+
     fn print_dyn_existential(
         self,
         predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
@@ -76,6 +152,17 @@ pub trait Printer<'gcx: 'tcx, 'tcx>: Sized {
         ct: &'tcx ty::Const<'tcx>,
     ) -> Result<Self::Const, Self::Error>;
 
+    fn print_generic_arg(
+        self,
+        arg: Kind<'tcx>,
+    ) -> Result<PrintedGenericArg<Self::Region, Self::Type, Self::Const>, Self::Error> {
+        Ok(match arg.unpack() {
+            UnpackedKind::Lifetime(r) => PrintedGenericArg::Lifetime(self.print_region(r)?),
+            UnpackedKind::Type(ty) => PrintedGenericArg::Type(self.print_type(ty)?),
+            UnpackedKind::Const(ct) => PrintedGenericArg::Const(self.print_const(ct)?),
+        })
+    }
+
     fn path_crate(
         self,
         cnum: CrateNum,
@@ -140,6 +227,7 @@ pub trait Printer<'gcx: 'tcx, 'tcx>: Sized {
                 if !substs.is_empty() {
                     let generics = self.tcx().generics_of(def_id);
                     parent_substs = &substs[..generics.parent_count.min(substs.len())];
+                    let own_self_param = generics.has_self && generics.parent_count == 0;
 
                     match key.disambiguated_data.data {
                         // Closures' own generics are only captures, don't print them.
@@ -147,7 +235,10 @@ pub trait Printer<'gcx: 'tcx, 'tcx>: Sized {
 
                         // If we have any generic arguments to print, we do that
                         // on top of the same path, but without its own generics.
-                        _ => if !generics.params.is_empty() && substs.len() >= generics.count() {
+                        // The `Self` parameter of traits is never printed, so
+                        // it doesn't count as the trait being generic.
+                        _ => if generics.params.len() > own_self_param as usize &&
+                                substs.len() >= generics.count() {
                             let args = self.generic_args_to_print(generics, substs);
                             return self.path_generic_args(
                                 |cx| cx.print_def_path(def_id, parent_substs),
@@ -158,6 +249,9 @@ pub trait Printer<'gcx: 'tcx, 'tcx>: Sized {
 
                     // FIXME(eddyb) try to move this into the parent's printing
                     // logic, instead of doing it when printing the child.
+                    // Only trait-qualify when all of the trait's arguments,
+                    // including `Self`, are available, e.g. for partially
+                    // substituted paths fall back to printing `Trait::method`.
                     trait_qualify_parent =
                         generics.has_self &&
                         generics.parent == Some(parent_def_id) &&
@@ -186,17 +280,36 @@ pub trait Printer<'gcx: 'tcx, 'tcx>: Sized {
         generics: &'tcx ty::Generics,
         substs: &'tcx [Kind<'tcx>],
     ) -> &'tcx [Kind<'tcx>] {
-        let mut own_params = generics.parent_count..generics.count();
+        // Partial `substs` (e.g. during inference) may lack the args of
+        // trailing parameters, so only the args present get printed.
+        let count = generics.count().min(substs.len());
+        let mut own_params = generics.parent_count.min(count)..count;
 
         // Don't print args for `Self` parameters (of traits).
-        if generics.has_self && own_params.start == 0 {
+        if generics.has_self && own_params.start == 0 && own_params.end > 0 {
             own_params.start = 1;
         }
 
-        // Don't print args that are the defaults of their respective parameters.
+        // Don't print args that are the defaults of their respective parameters,
+        // unless printing verbosely, or printing the turbofish of a function in
+        // a value path. Only trailing args can be elided, as later args couldn't
+        // be matched up with their parameters otherwise, so e.g. `Foo<u8, i8>`
+        // for `struct Foo<A = u8, B = u16>` is printed in full.
+        let is_fn = generics.params.first()
+            .and_then(|param| self.tcx().parent(param.def_id))
+            .map_or(false, |def_id| match self.tcx().def_kind(def_id) {
+                Some(DefKind::Fn) | Some(DefKind::Method) => true,
+                _ => false,
+            });
+        if self.should_print_verbose() || self.in_value_path() && is_fn {
+            return &substs[own_params];
+        }
         own_params.end -= generics.params.iter().rev().take_while(|param| {
             match param.kind {
                 ty::GenericParamDefKind::Lifetime => false,
+                // Nothing gets elided after args missing from partial `substs`.
+                ty::GenericParamDefKind::Type { .. }
+                    if param.index as usize >= substs.len() => false,
                 ty::GenericParamDefKind::Type { has_default, .. } => {
                     has_default && substs[param.index as usize] == Kind::from(
                         self.tcx().type_of(param.def_id).subst(self.tcx(), substs)
@@ -227,7 +340,7 @@ pub trait Printer<'gcx: 'tcx, 'tcx>: Sized {
         // users may find it useful. Currently, we omit the parent if
         // the impl is either in the same module as the self-type or
         // as the trait.
-        let in_self_mod = match characteristic_def_id_of_type(self_ty) {
+        let in_self_mod = match characteristic_def_id_of_type(self.tcx(), self_ty) {
             None => false,
             Some(ty_def_id) => self.tcx().parent(ty_def_id) == Some(parent_def_id),
         };
@@ -236,10 +349,10 @@ pub trait Printer<'gcx: 'tcx, 'tcx>: Sized {
             Some(trait_ref) => self.tcx().parent(trait_ref.def_id) == Some(parent_def_id),
         };
 
-        if !in_self_mod && !in_trait_mod {
+        if self.should_fully_qualify_paths() || !in_self_mod && !in_trait_mod {
             // If the impl is not co-located with either self-type or
             // trait-type, then fallback to a format that identifies
-            // the module more clearly.
+            // the module more clearly (as is always done, if asked to).
             self.path_append_impl(
                 |cx| cx.print_def_path(parent_def_id, &[]),
                 &key.disambiguated_data,
@@ -254,32 +367,58 @@ pub trait Printer<'gcx: 'tcx, 'tcx>: Sized {
     }
 }
 
+/// The output of `Printer::print_generic_arg`, for either kind of generic arg.
+pub enum PrintedGenericArg<R, T, C> {
+    Lifetime(R),
+    Type(T),
+    Const(C),
+}
+
+impl<T> PrintedGenericArg<T, T, T> {
+    /// Returns the output for printers printing all kinds of generic args the same.
+    pub fn into_inner(self) -> T {
+        match self {
+            PrintedGenericArg::Lifetime(x) |
+            PrintedGenericArg::Type(x) |
+            PrintedGenericArg::Const(x) => x,
+        }
+    }
+}
+
 /// As a heuristic, when we see an impl, if we see that the
 /// 'self type' is a type defined in the same module as the impl,
 /// we can omit including the path to the impl itself. This
 /// function tries to find a "characteristic `DefId`" for a
 /// type. It's just a heuristic so it makes some questionable
 /// decisions and we may want to adjust it later.
-pub fn characteristic_def_id_of_type(ty: Ty<'_>) -> Option<DefId> {
+///
+/// Projections are characterized by their trait, so that e.g. impls
+/// on `<T as Trait>::Assoc` next to `Trait` are treated like the
+/// impls next to their self-type.
+pub fn characteristic_def_id_of_type(tcx: TyCtxt<'_, '_, '_>, ty: Ty<'_>) -> Option<DefId> {
     match ty.sty {
         ty::Adt(adt_def, _) => Some(adt_def.did),
 
         ty::Dynamic(data, ..) => data.principal_def_id(),
 
         ty::Array(subty, _) |
-        ty::Slice(subty) => characteristic_def_id_of_type(subty),
+        ty::Slice(subty) => characteristic_def_id_of_type(tcx, subty),
 
-        ty::RawPtr(mt) => characteristic_def_id_of_type(mt.ty),
+        ty::RawPtr(mt) => characteristic_def_id_of_type(tcx, mt.ty),
 
-        ty::Ref(_, ty, _) => characteristic_def_id_of_type(ty),
+        ty::Ref(_, ty, _) => characteristic_def_id_of_type(tcx, ty),
 
         ty::Tuple(ref tys) => tys.iter()
-                                   .filter_map(|ty| characteristic_def_id_of_type(ty.expect_ty()))
-                                   .next(),
+            .filter_map(|ty| characteristic_def_id_of_type(tcx, ty.expect_ty()))
+            .next(),
+
+        ty::Projection(ref data) |
+        ty::UnnormalizedProjection(ref data) => tcx.parent(data.item_def_id),
 
         ty::FnDef(def_id, _) |
         ty::Closure(def_id, _) |
         ty::Generator(def_id, _, _) |
+        ty::Opaque(def_id, _) |
         ty::Foreign(def_id) => Some(def_id),
 
         ty::Bool |
@@ -288,11 +427,8 @@ pub fn characteristic_def_id_of_type(ty: Ty<'_>) -> Option<DefId> {
         ty::Uint(_) |
         ty::Str |
         ty::FnPtr(_) |
-        ty::Projection(_) |
         ty::Placeholder(..) |
-        ty::UnnormalizedProjection(..) |
         ty::Param(_) |
-        ty::Opaque(..) |
         ty::Infer(_) |
         ty::Bound(..) |
         ty::Error |
@@ -326,6 +462,14 @@ impl<'gcx: 'tcx, 'tcx, P: Printer<'gcx, 'tcx>> Print<'gcx, 'tcx, P> for Ty<'tcx>
     }
 }
 
+impl<'gcx: 'tcx, 'tcx, P: Printer<'gcx, 'tcx>> Print<'gcx, 'tcx, P> for ty::FnSig<'tcx> {
+    type Output = P::Type;
+    type Error = P::Error;
+    fn print(&self, cx: P) -> Result<Self::Output, Self::Error> {
+        cx.print_fn_sig(*self)
+    }
+}
+
 impl<'gcx: 'tcx, 'tcx, P: Printer<'gcx, 'tcx>> Print<'gcx, 'tcx, P>
     for &'tcx ty::List<ty::ExistentialPredicate<'tcx>>
 {
@@ -343,3 +487,23 @@ impl<'gcx: 'tcx, 'tcx, P: Printer<'gcx, 'tcx>> Print<'gcx, 'tcx, P> for &'tcx ty
         cx.print_const(self)
     }
 }
+
+// For printers with a common output for all kinds of generic args, which
+// are the only ones that can print lists of them.
+impl<'gcx: 'tcx, 'tcx, P, T> Print<'gcx, 'tcx, P> for Kind<'tcx>
+    where P: Printer<'gcx, 'tcx, Region = T, Type = T, Const = T>
+{
+    type Output = T;
+    type Error = P::Error;
+    fn print(&self, cx: P) -> Result<Self::Output, Self::Error> {
+        cx.print_generic_arg(*self).map(PrintedGenericArg::into_inner)
+    }
+}
+
+impl<'gcx: 'tcx, 'tcx, P: Printer<'gcx, 'tcx>> Print<'gcx, 'tcx, P> for ty::TraitRef<'tcx> {
+    type Output = P::Path;
+    type Error = P::Error;
+    fn print(&self, cx: P) -> Result<Self::Output, Self::Error> {
+        cx.print_trait_ref(*self)
+    }
+}
@@ -1,22 +1,34 @@
 use crate::hir;
-use crate::hir::def::{Namespace, DefKind};
+use crate::hir::def::{CtorKind, Namespace, DefKind, Res};
 use crate::hir::map::{DefPathData, DisambiguatedDefPathData};
 use crate::hir::def_id::{CrateNum, DefId, CRATE_DEF_INDEX, LOCAL_CRATE};
 use crate::middle::cstore::{ExternCrate, ExternCrateSource};
+use crate::middle::lang_items::{self, LangItem};
 use crate::middle::region;
+use crate::traits;
 use crate::ty::{self, DefIdTree, ParamConst, Ty, TyCtxt, TypeFoldable};
-use crate::ty::subst::{Kind, Subst, UnpackedKind};
-use crate::ty::layout::Size;
-use crate::mir::interpret::{ConstValue, sign_extend, Scalar};
+use crate::ty::query::Query;
+use crate::ty::subst::{InternalSubsts, Kind, Subst, SubstsRef, UnpackedKind};
+use crate::ty::layout::{DiscriminantKind, LayoutCx, LayoutOf, Size, Variants};
+use crate::mir::interpret::{
+    Allocation, ConstValue, GlobalAlloc, GlobalId, Pointer, sign_extend, Scalar, ScalarMaybeUndef,
+    truncate,
+};
 use syntax::ast;
 use rustc_apfloat::ieee::{Double, Single};
 use rustc_apfloat::Float;
 use rustc_target::spec::abi::Abi;
+use syntax::source_map::CompilerDesugaringKind;
+use syntax_pos::DUMMY_SP;
 use syntax::symbol::{kw, InternedString};
 
+use rustc_data_structures::fx::FxHashMap;
+use unicode_normalization::UnicodeNormalization;
+
+use std::borrow::Cow;
 use std::cell::Cell;
 use std::fmt::{self, Write as _};
-use std::ops::{Deref, DerefMut};
+use std::ops::{Deref, DerefMut, Range};
 
 // `pretty` is a separate module only for organization.
 use super::*;
@@ -63,7 +75,10 @@ pub fn with_forced_impl_filename_line<F: FnOnce() -> R, R>(f: F) -> R {
     })
 }
 
-/// Adds the `crate::` prefix to paths where appropriate.
+/// Adds the `crate::` prefix to paths where appropriate, i.e. to paths
+/// of local items when compiling for Rust 2018, where they'd otherwise
+/// start with the item's module (in Rust 2015, those paths are already
+/// crate-relative, so they're left alone, as existing output expects).
 pub fn with_crate_prefix<F: FnOnce() -> R, R>(f: F) -> R {
     SHOULD_PREFIX_WITH_CRATE.with(|flag| {
         let old = flag.get();
@@ -74,6 +89,294 @@ pub fn with_crate_prefix<F: FnOnce() -> R, R>(f: F) -> R {
     })
 }
 
+/// Defines `PrintOptions`, with one `bool` field per optional printing
+/// mode, all disabled by default, and a way to enable them by name.
+macro_rules! define_print_options {
+    ($($(#[$attr:meta])* $field:ident,)+) => {
+        /// Optional printing modes, read by the printers supporting them through
+        /// `Printer::print_options`, and set e.g. with `FmtPrinterBuilder::options`.
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+        pub struct PrintOptions {
+            $($(#[$attr])* pub $field: bool,)+
+        }
+
+        impl PrintOptions {
+            /// Enables the option whose field is named `name`, returning whether
+            /// there is one, e.g. for tests reading options from attributes.
+            pub fn enable(&mut self, name: &str) -> bool {
+                match name {
+                    $(stringify!($field) => self.$field = true,)+
+                    _ => return false,
+                }
+                true
+            }
+        }
+    };
+}
+
+define_print_options! {
+    /// Makes the markers left behind by truncated output state how much
+    /// was omitted (e.g. `…(47 more chars)…`), instead of a bare `...`.
+    elision_counts,
+
+    /// Prints newtype indices (non-generic single-field tuple structs
+    /// wrapping a primitive) along with the wrapped type, e.g. `NodeId(u32)`.
+    newtype_inner_types,
+
+    /// Escapes invisible or otherwise suspicious characters in printed
+    /// identifiers, so that untrusted names can't spoof others in a terminal.
+    /// See `SanitizingPrinter` for applying this to a single printer.
+    sanitized_idents,
+
+    /// Marks `#[non_exhaustive]` structs and enums with `/* non_exhaustive */`.
+    /// Enums with only some `#[non_exhaustive]` variants are not marked,
+    /// as that doesn't affect matching on the enum itself.
+    non_exhaustive_markers,
+
+    /// Annotates generic parameters (types, consts and early-bound lifetimes)
+    /// with their index, e.g. `T#0`, to disambiguate same-named parameters.
+    param_indices,
+
+    /// Lists the (unerased) lifetimes captured by `impl Trait` types after
+    /// their bounds, e.g. `impl Trait+captures<'a, 'b>`.
+    opaque_captures,
+
+    /// Appends the type to consts printed symbolically (e.g. `N: usize`).
+    /// Literals are left alone, as they either have a suffix (`5usize`)
+    /// or an obvious type (`true`, `'c'`, `"str"`).
+    const_types,
+
+    /// Marks structs, enums and unions with `/* local */` or `/* foreign */`,
+    /// depending on whether they're defined in the crate being compiled,
+    /// which is what the orphan rules care about.
+    locality_markers,
+
+    /// Prints the signatures of fn items with their parameter names, e.g.
+    /// `fn(x: i32, y: i32) -> i32 {add}`. Parameters bound by patterns other
+    /// than a plain binding, or with unknown names, are printed as `_0`, `_1`...
+    fn_param_names,
+
+    /// Omits the lifetime bound of trait objects when it's the default one,
+    /// i.e. `'a` in `&'a (dyn Trait + 'a)` and `'static` everywhere else.
+    /// Note that the default for type arguments of a parameter with lifetime
+    /// bounds (e.g. `T: 'a`) is that lifetime, but that's not checked here.
+    default_object_lifetimes_elided,
+
+    /// Describes anonymous regions where their origin is known, e.g. as
+    /// `'<function body>`, instead of printing them as `'_` (or omitting them).
+    descriptive_regions,
+
+    /// Lists the variables captured by closures in a more readable form, e.g.
+    /// `[closure@.. capturing: x: u32, y: &String]` instead of `x:u32, y:&String`.
+    /// By-reference captures show up as reference types, once upvar inference
+    /// has chosen the capture modes.
+    closure_captures,
+
+    /// Marks `async` closures (and closures whose body is an `async` block), along
+    /// with their future-returning signature, once type inference has picked it,
+    /// e.g. `[async closure@..; fn(u8) -> impl Future<Output = u8>]`.
+    async_closure_signatures,
+
+    /// Appends the discriminant value to the paths of enum variants, e.g.
+    /// `E::A = 3`, including implicit discriminants. Note that discriminants
+    /// can't depend on generic parameters, so they're known for any enum.
+    variant_discriminants,
+
+    /// Marks unsized types with `/* unsized */`, right after them, e.g.
+    /// `&[u8] /* unsized */` for the slice. Types whose sizedness depends
+    /// on the bounds of generic parameters in scope are left unmarked.
+    unsized_markers,
+
+    /// Marks the paths of traits which aren't object-safe, along with the
+    /// first reason why, e.g. `Clone /* not object-safe: the trait cannot
+    /// require that `Self : Sized` */`.
+    object_safety_markers,
+
+    /// Prints `<>` (or `::<>` in value paths) after generic items whose
+    /// arguments were all omitted, e.g. `Foo<>` for `Foo<u8>` given
+    /// `struct Foo<T = u8>`, to tell them apart from non-generic items.
+    /// This includes erased lifetime arguments, e.g. `Bar<>` for `Bar<'_>`.
+    empty_generic_brackets,
+
+    /// Prints types as they'd be written in signatures, hiding everything
+    /// derived from bodies: closures and generators are printed as the
+    /// traits they implement, e.g. `impl FnMut(u8) -> bool`, and the hidden
+    /// types of `impl Trait` types (see `opaque_hidden_ty`) are omitted.
+    signature_mode,
+
+    /// Marks fn items with the behavior-relevant attributes they have, e.g.
+    /// `fn() {foo /* attrs: cold, no_mangle */}`, which isn't possible for fn pointers.
+    /// FIXME: add `#[track_caller]` once it's supported by the compiler.
+    fn_attribute_markers,
+
+    /// Prints const arrays of integers, `bool`s and `char`s by their elements,
+    /// using the repeat syntax (e.g. `[0u8; 1000]`) for arrays of all-equal
+    /// elements, and otherwise at most `MAX_CONST_ARRAY_ELEMS` elements,
+    /// followed by an `elision_marker`, e.g. `[1u8, 2u8, 3u8, ...]`.
+    const_array_elems,
+
+    /// Prints the values of (associated) consts after their paths, e.g.
+    /// `Type::CONST /* = 42u32 */`, including those computed by `const fn`
+    /// calls. Consts which can't be evaluated (e.g. because they depend on
+    /// generic parameters) are printed as before. Note that evaluation errors
+    /// get reported, as they would be if the const was used.
+    const_values,
+
+    /// Prints (anonymous) consts by their values where those can be computed,
+    /// e.g. `4usize` instead of `LEN` (or `{LEN}`), for integers, `bool`s and
+    /// `char`s, falling back to their paths (or snippets) for generic consts,
+    /// statics, and under `-Zverbose`. Like `const_values`, this runs
+    /// const evaluation, which can be expensive and reports its errors.
+    evaluated_consts,
+
+    /// Always prints the args of paths whose generic args are all lifetimes,
+    /// even when erased (as `'_`), e.g. `Ref<'_>` for `struct Ref<'a>`, so
+    /// they stand out from non-generic paths. This takes precedence over
+    /// erased lifetimes being omitted, which otherwise prints just `Ref`.
+    lifetime_only_args,
+
+    /// Prints the traits of qualified paths by their name alone, e.g.
+    /// `<T as Iterator>::Item` instead of `<T as std::iter::Iterator>::Item`,
+    /// unless another trait (in any crate) has the same name. The self type,
+    /// and the rest of the path after the `<...>`, are printed as before.
+    short_qualified_traits,
+
+    /// Lists the `DERIVABLE_TRAITS` that structs, enums and unions support,
+    /// e.g. `Foo /* derives: Clone, Debug */`, for tools mirroring types.
+    /// These are the traits implemented, whether derived or not, as manual
+    /// impls can't be told apart. As nothing is known about generic parameters,
+    /// implementations that depend on their bounds are omitted.
+    derive_hints,
+
+    /// Describes the ownership semantics of the smart pointers in `OWNERSHIP_HINTS`,
+    /// for teaching, e.g. `Box<u8> /* ownership: heap-allocated owned u8 */`.
+    /// Other types, including user-defined smart pointers, are printed as before.
+    ownership_hints,
+
+    /// Summarizes structs, enums and unions implementing `Iterator` by their
+    /// item type, e.g. `impl Iterator<Item = u8>` for (deeply nested) adapters
+    /// like `Map<Filter<std::slice::Iter<'_, u8>, [closure]>, [closure]>`.
+    /// Iterators whose item type can't be resolved (e.g. because it depends
+    /// on generic parameters) are printed as before.
+    iterator_summaries,
+
+    /// Marks function items callable in const contexts (i.e. `const fn`s and
+    /// constructors), e.g. `fn() {foo /* const-callable */}`, or those which
+    /// would be with a feature gate enabled, with the feature to enable, e.g.
+    /// `/* const-callable with #![feature(const_foo)] */`.
+    const_callability,
+
+    /// Prefixes the elements of tuples with their field index, as used to access
+    /// them, e.g. `(.0: i32, .1: bool)`, or `(.0: i32,)` for single elements,
+    /// and the element type of arrays with the range of their indices, when
+    /// there are any and their length is known, e.g. `[.0..3: i32; 3]`.
+    tuple_indices,
+
+    /// Appends the `DefId` of each segment of a path to it, as `{krate:index}`,
+    /// e.g. `std{1:0}::vec{1:205}::Vec{1:4781}` (only for debugging, as these
+    /// aren't stable). Segments of re-exported items, printed by their visible
+    /// paths (see `try_print_visible_def_path`), only get the `DefId` of the item.
+    def_id_suffixes,
+
+    /// Prints region inference variables by their index, e.g. `'?3`, followed
+    /// by their SCC in the region constraint graph, if the caller provides it
+    /// (see `FmtPrinterData::region_vid_scc`), e.g. `'?3(scc1)`. This is meant
+    /// for debugging region inference (e.g. NLL), and never enabled otherwise.
+    region_vids,
+
+    /// Prints the expressions of anonymous consts (e.g. the `{ N + T::SIZE }`
+    /// of a const arg) by their structure (see `pretty_print_const_expr`),
+    /// with const params replaced by their args, instead of as written.
+    structural_const_exprs,
+}
+
+/// The traits (all from `core`) listed under `derive_hints`, by name.
+const DERIVABLE_TRAITS: &[&str] = &[
+    "Clone", "Copy", "Debug", "Default", "PartialEq", "Eq", "PartialOrd", "Ord", "Hash",
+];
+
+/// The descriptions of the ownership semantics of smart pointers, printed
+/// (followed by the pointee) under `ownership_hints`, by their lang items.
+const OWNERSHIP_HINTS: &[(LangItem, &str)] = &[
+    (lang_items::OwnedBoxLangItem, "heap-allocated owned"),
+    (lang_items::Rc, "shared owned"),
+    (lang_items::Arc, "thread-safe shared owned"),
+    (lang_items::PinTypeLangItem, "pinned"),
+    (lang_items::ManuallyDropItem, "never automatically dropped"),
+];
+
+/// The number of elements to print of const arrays under `const_array_elems`.
+const MAX_CONST_ARRAY_ELEMS: usize = 16;
+
+/// How deeply nested the fields of struct and enum consts get printed (see
+/// `const_adt_value`), before the values of the remaining ones are elided.
+const MAX_CONST_ADT_DEPTH: usize = 8;
+
+impl PrintOptions {
+    /// Returns the marker to print in place of `omitted` elided `unit`s
+    /// (e.g. `"char"` or `"type arg"`), respecting `elision_counts`.
+    ///
+    /// Every truncation point emits its own marker, describing only what
+    /// it omitted itself, except for the types elided in the same list, adjacent
+    /// or not, e.g. `HashMap<…(3 more types)…>`, which share one (see
+    /// `comma_sep_eliding`).
+    pub fn elision_marker(self, omitted: usize, unit: &str) -> String {
+        if self.elision_counts {
+            format!("…({} more {}{})…", omitted, unit, if omitted == 1 { "" } else { "s" })
+        } else {
+            "...".to_string()
+        }
+    }
+
+    /// Returns the identifier `ident`, escaped like `write_ident` does under
+    /// `sanitized_idents`, for printers building their output out of strings
+    /// (see `SanitizingPrinter`).
+    pub fn sanitized_ident(self, ident: &str) -> Cow<'_, str> {
+        if !self.sanitized_idents {
+            return Cow::Borrowed(ident);
+        }
+        let mut sanitized = String::with_capacity(ident.len());
+        let _ = write_ident(&mut sanitized, ident, true);
+        Cow::Owned(sanitized)
+    }
+}
+
+/// Whether `c` is a letter of the Latin script, which is what identifiers
+/// mixing scripts are checked against (see `write_ident`).
+fn is_latin_letter(c: char) -> bool {
+    c.is_ascii_alphabetic() || (c.is_alphabetic() && match c {
+        '\u{c0}'..='\u{24f}' | '\u{1e00}'..='\u{1eff}' => true,
+        _ => false,
+    })
+}
+
+/// Writes the identifier `ident`, if `sanitize` is set (see `sanitized_idents`)
+/// normalized to NFC, like the lexer does, and with these characters escaped
+/// (as `\u{...}`):
+/// - those that are neither printable ASCII nor alphanumeric, e.g. zero-width
+///   joiners and bidirectional overrides,
+/// - in identifiers containing Latin letters, the letters of other scripts,
+///   which may be confusables, e.g. the Cyrillic `а` in `pаypal`.
+///
+/// This still allows non-ASCII identifiers written in a single script, e.g.
+/// `naïve` or `Москва`, as unlike mixed ones, they don't get mistaken for others.
+fn write_ident(w: &mut impl fmt::Write, ident: &str, sanitize: bool) -> fmt::Result {
+    if !sanitize {
+        return w.write_str(ident);
+    }
+    let ident: String = ident.nfc().collect();
+    let has_latin = ident.chars().any(is_latin_letter);
+    for c in ident.chars() {
+        let confusable = has_latin && c.is_alphabetic() && !is_latin_letter(c);
+        if !confusable && (c.is_ascii_graphic() || (!c.is_ascii() && c.is_alphanumeric())) {
+            w.write_char(c)?;
+        } else {
+            write!(w, "\\u{{{:x}}}", c as u32)?;
+        }
+    }
+    Ok(())
+}
+
 /// The "region highlights" are used to control region printing during
 /// specific error messages. When a "region highlight" is enabled, it
 /// gives an alternate way to print specific regions. For now, we
@@ -140,6 +443,11 @@ impl RegionHighlightMode {
         self.highlighting_region(&ty::ReVar(vid), number)
     }
 
+    /// Returns `true` if no regions are highlighted.
+    fn is_empty(&self) -> bool {
+        self.highlight_regions.iter().all(|h| h.is_none()) && self.highlight_bound_region.is_none()
+    }
+
     /// Returns `Some(n)` with the number to use for the given region, if any.
     fn region_highlighted(&self, region: ty::Region<'_>) -> Option<usize> {
         self
@@ -177,13 +485,39 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
     > +
     fmt::Write
 {
-    /// Like `print_def_path` but for value paths.
-    fn print_value_path(
-        self,
-        def_id: DefId,
-        substs: &'tcx [Kind<'tcx>],
-    ) -> Result<Self::Path, Self::Error> {
-        self.print_def_path(def_id, substs)
+    /// Prints `predicate`, e.g. `T: Trait` for trait predicates, `'a : 'b` for
+    /// outlives predicates and `<T as Trait>::Item == U` for projection predicates,
+    /// with the `for<...>` binders of higher-ranked predicates.
+    fn print_predicate(
+        mut self,
+        predicate: &ty::Predicate<'tcx>,
+    ) -> Result<Self, Self::Error> {
+        define_scoped_cx!(self);
+
+        match *predicate {
+            ty::Predicate::Trait(ref data) => p!(print(data)),
+            ty::Predicate::Subtype(ref predicate) => p!(print(predicate)),
+            ty::Predicate::RegionOutlives(ref predicate) => p!(print(predicate)),
+            ty::Predicate::TypeOutlives(ref predicate) => p!(print(predicate)),
+            ty::Predicate::Projection(ref predicate) => p!(print(predicate)),
+            ty::Predicate::WellFormed(ty) => p!(print(ty), write(" well-formed")),
+            ty::Predicate::ObjectSafe(trait_def_id) => {
+                p!(write("the trait `"),
+                   print_def_path(trait_def_id, &[]),
+                   write("` is object-safe"))
+            }
+            ty::Predicate::ClosureKind(closure_def_id, _closure_substs, kind) => {
+                p!(write("the closure `"),
+                   print_value_path(closure_def_id, &[]),
+                   write("` implements the trait `{}`", kind))
+            }
+            ty::Predicate::ConstEvaluatable(def_id, substs) => {
+                p!(write("the constant `"),
+                   print_value_path(def_id, substs),
+                   write("` can be evaluated"))
+            }
+        }
+        Ok(self)
     }
 
     fn in_binder<T>(
@@ -212,6 +546,57 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
         Ok(self)
     }
 
+    /// Print comma-separated elements, like `comma_sep`, but with all the types
+    /// which would be elided (see `elided_types`) merged into a single
+    /// `elision_marker`, counting the types of all of them, even if they aren't
+    /// adjacent, printed in place of the first of them.
+    /// The elements printed are each preceded by what `prefix` returns for their index.
+    fn comma_sep_eliding(
+        mut self,
+        elems: impl Iterator<Item = Kind<'tcx>>,
+        prefix: impl Fn(usize) -> String,
+    ) -> Result<Self, Self::Error> {
+        let elems: Vec<_> = elems.map(|elem| {
+            let elided = match elem.unpack() {
+                UnpackedKind::Type(ty) => self.elided_types(ty).map(|count| (ty, count)),
+                _ => None,
+            };
+            (elem, elided)
+        }).collect();
+        let elided_tys: Vec<_> = elems.iter().filter_map(|&(_, elided)| elided).collect();
+        let elided: usize = elided_tys.iter().map(|&(_, count)| count).sum();
+        let elided_tys: Vec<_> = elided_tys.into_iter().map(|(ty, _)| ty).collect();
+
+        let mut first = true;
+        let mut printed_marker = false;
+        for (i, (elem, elided_ty)) in elems.into_iter().enumerate() {
+            let sep = if first { "" } else { ", " };
+            match elided_ty {
+                Some(_) if printed_marker => continue,
+                Some(_) => {
+                    let marker = self.print_options().elision_marker(elided, "type");
+                    write!(self, "{}{}", sep, marker)?;
+                    self.note_elided_types(&marker, &elided_tys);
+                    printed_marker = true;
+                }
+                None => {
+                    write!(self, "{}{}", sep, prefix(i))?;
+                    self = elem.print(self)?;
+                }
+            }
+            first = false;
+        }
+        Ok(self)
+    }
+
+    /// Print the identifier `ident` (e.g. of a parameter, field or region),
+    /// sanitized under `sanitized_idents`, like path segments are.
+    fn print_ident(mut self, ident: &str) -> Result<Self, Self::Error> {
+        let sanitize = self.print_options().sanitized_idents;
+        write_ident(&mut self, ident, sanitize)?;
+        Ok(self)
+    }
+
     /// Print `<...>` around what `f` prints.
     fn generic_delimiters(
         self,
@@ -279,13 +664,14 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
             }
         }
 
-        if def_id.is_local() {
+        if def_id.is_local() || self.prints_full_paths_for(def_id.krate) {
             return Ok((self, false));
         }
 
         let visible_parent_map = self.tcx().visible_parent_map(LOCAL_CRATE);
 
         let mut cur_def_key = self.tcx().def_key(def_id);
+        let mut segment_def_id = def_id;
         debug!("try_print_visible_def_path: cur_def_key={:?}", cur_def_key);
 
         // For a constructor we want the name of its parent rather than <unnamed>.
@@ -298,6 +684,7 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
                 };
 
                 cur_def_key = self.tcx().def_key(parent);
+                segment_def_id = parent;
             },
             _ => {},
         }
@@ -377,7 +764,7 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
         }
         debug!("try_print_visible_def_path: data={:?}", data);
 
-        Ok((self.path_append(Ok, &DisambiguatedDefPathData {
+        Ok((self.path_append_visible(segment_def_id, &DisambiguatedDefPathData {
             data,
             disambiguator: 0,
         })?, true))
@@ -408,7 +795,27 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
 
             p!(print(self_ty));
             if let Some(trait_ref) = trait_ref {
-                p!(write(" as "), print(trait_ref));
+                p!(write(" as "));
+                let short_name = if cx.print_options().short_qualified_traits &&
+                    !cx.should_fully_qualify_paths() {
+                    cx.with_lookup_cache(|tcx, cache| {
+                        unambiguous_trait_name(tcx, cache, trait_ref.def_id)
+                    })
+                } else {
+                    None
+                };
+                match short_name {
+                    Some(name) => {
+                        let generics = cx.tcx().generics_of(trait_ref.def_id);
+                        let args = cx.generic_args_to_print(generics, trait_ref.substs);
+                        if args.is_empty() {
+                            p!(print_ident(&name.as_str()));
+                        } else {
+                            p!(path_generic_args(|cx| cx.print_ident(&name.as_str()), args));
+                        }
+                    }
+                    None => p!(print(trait_ref)),
+                }
             }
             Ok(cx)
         })
@@ -455,30 +862,102 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
                 p!(print(tm.ty))
             }
             ty::Ref(r, ty, mutbl) => {
+                // Always `&`, then the region (unless omitted), then `mut`,
+                // and only then the pointee, e.g. `&'a mut T` or `&mut T`.
                 p!(write("&"));
                 if self.region_should_not_be_omitted(r) {
                     p!(print(r), write(" "));
                 }
-                p!(print(ty::TypeAndMut { ty, mutbl }))
+                match ty.sty {
+                    // The default object lifetime behind a reference is that of the reference,
+                    // so any other (even `'static`) has to be printed.
+                    ty::Dynamic(data, obj_r) if
+                        self.print_options().default_object_lifetimes_elided => {
+                        if mutbl == hir::MutMutable {
+                            p!(write("mut "));
+                        }
+                        if obj_r == r || !self.region_should_not_be_omitted(obj_r) {
+                            p!(write("dyn "), print(data));
+                        } else {
+                            p!(write("(dyn "), print(data), write(" + "), print(obj_r), write(")"));
+                        }
+                    }
+                    _ => p!(print(ty::TypeAndMut { ty, mutbl })),
+                }
             }
             ty::Never => p!(write("!")),
             ty::Tuple(ref tys) => {
-                p!(write("("));
-                let mut tys = tys.iter();
-                if let Some(&ty) = tys.next() {
-                    p!(print(ty), write(","));
-                    if let Some(&ty) = tys.next() {
-                        p!(write(" "), print(ty));
-                        for &ty in tys {
-                            p!(write(", "), print(ty));
-                        }
-                    }
+                let print_indices = self.print_options().tuple_indices;
+                let index = |i: usize| {
+                    if print_indices { format!(".{}: ", i) } else { String::new() }
+                };
+                p!(write("("), comma_sep_eliding(tys.iter().map(|&ty| Kind::from(ty)), index));
+                if tys.len() == 1 {
+                    p!(write(","));
                 }
                 p!(write(")"))
             }
             ty::FnDef(def_id, substs) => {
                 let sig = self.tcx().fn_sig(def_id).subst(self.tcx(), substs);
-                p!(print(sig), write(" {{"), print_value_path(def_id, substs), write("}}"));
+                if self.print_options().fn_param_names {
+                    // Named late-bound lifetimes are kept (as free regions),
+                    // while anonymous ones get elided, as they would in source.
+                    let sig = self.tcx().liberate_late_bound_regions(def_id, &sig);
+                    let names = fn_param_names(self.tcx(), def_id);
+
+                    if sig.unsafety == hir::Unsafety::Unsafe {
+                        p!(write("unsafe "));
+                    }
+                    if sig.abi != Abi::Rust {
+                        p!(write("extern {} ", sig.abi));
+                    }
+                    p!(write("fn("));
+                    for (i, &ty) in sig.inputs().iter().enumerate() {
+                        if i > 0 {
+                            p!(write(", "));
+                        }
+                        match names.get(i) {
+                            Some(&name) if name != kw::Invalid && name != kw::Underscore => {
+                                p!(print_ident(&name.as_str()), write(": "))
+                            }
+                            _ => p!(write("_{}: ", i)),
+                        }
+                        p!(print(ty));
+                    }
+                    if sig.c_variadic {
+                        p!(write(", ..."));
+                    }
+                    p!(write(")"));
+                    if !sig.output().is_unit() {
+                        p!(write(" -> "), print(sig.output()));
+                    }
+                } else {
+                    p!(print(sig));
+                }
+                p!(write(" {{"), print_value_path(def_id, substs));
+                if self.print_options().fn_attribute_markers {
+                    let flags = self.tcx().codegen_fn_attrs(def_id).flags;
+                    let attrs: Vec<_> = [
+                        (hir::CodegenFnAttrFlags::COLD, "cold"),
+                        (hir::CodegenFnAttrFlags::NAKED, "naked"),
+                        (hir::CodegenFnAttrFlags::NO_MANGLE, "no_mangle"),
+                        (hir::CodegenFnAttrFlags::UNWIND, "unwind"),
+                        (hir::CodegenFnAttrFlags::FFI_RETURNS_TWICE, "ffi_returns_twice"),
+                    ].iter().filter(|&&(flag, _)| flags.contains(flag)).map(|&(_, name)| name)
+                        .collect();
+                    if !attrs.is_empty() {
+                        p!(write(" /* attrs: {} */", attrs.join(", ")));
+                    }
+                }
+                if self.print_options().const_callability {
+                    let tcx = self.tcx().global_tcx();
+                    if tcx.is_const_fn(def_id) {
+                        p!(write(" /* const-callable */"));
+                    } else if let Some(feature) = tcx.is_unstable_const_fn(def_id) {
+                        p!(write(" /* const-callable with #![feature({})] */", feature));
+                    }
+                }
+                p!(write("}}"));
             }
             ty::FnPtr(ref bare_fn) => {
                 p!(print(bare_fn))
@@ -486,7 +965,7 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
             ty::Infer(infer_ty) => {
                 if let ty::TyVar(ty_vid) = infer_ty {
                     if let Some(name) = self.infer_ty_name(ty_vid) {
-                        p!(write("{}", name))
+                        p!(print_ident(&name))
                     } else {
                         p!(write("{}", infer_ty))
                     }
@@ -495,7 +974,12 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
                 }
             },
             ty::Error => p!(write("[type error]")),
-            ty::Param(ref param_ty) => p!(write("{}", param_ty)),
+            ty::Param(ref param_ty) => {
+                p!(print(param_ty));
+                if self.print_options().param_indices {
+                    p!(write("#{}", param_ty.index));
+                }
+            }
             ty::Bound(debruijn, bound_ty) => {
                 match bound_ty.kind {
                     ty::BoundTyKind::Anon => {
@@ -510,10 +994,64 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
                 }
             }
             ty::Adt(def, substs) => {
-                p!(print_def_path(def.did, substs));
+                if self.print_options().iterator_summaries {
+                    let item_ty = self.with_lookup_cache(|tcx, cache| {
+                        iterator_item_ty(tcx, cache, ty)
+                    });
+                    if let Some(item_ty) = item_ty {
+                        p!(write("impl Iterator<Item = "), print(item_ty), write(">"));
+                        return Ok(self);
+                    }
+                }
+                match self.collapse_type_alias(def.did, substs) {
+                    Some((name, args)) => {
+                        p!(print_ident(&name));
+                        if !args.is_empty() {
+                            p!(generic_delimiters(|cx| cx.comma_sep(args.into_iter())));
+                        }
+                    }
+                    None => p!(print_def_path(def.did, substs)),
+                }
+                if self.print_options().newtype_inner_types {
+                    if let Some(inner) = newtype_index_inner_ty(self.tcx(), def, substs) {
+                        p!(write("("), print(inner), write(")"));
+                    }
+                }
+                if self.print_options().non_exhaustive_markers {
+                    let non_exhaustive = if def.is_enum() {
+                        def.is_variant_list_non_exhaustive()
+                    } else {
+                        def.non_enum_variant().is_field_list_non_exhaustive()
+                    };
+                    if non_exhaustive {
+                        p!(write(" /* non_exhaustive */"));
+                    }
+                }
+                if self.print_options().locality_markers {
+                    p!(write(" /* {} */", if def.did.is_local() { "local" } else { "foreign" }));
+                }
+                if self.print_options().derive_hints {
+                    let derives = self.with_lookup_cache(|tcx, cache| {
+                        supported_derives(tcx, cache, ty)
+                    });
+                    if !derives.is_empty() {
+                        p!(write(" /* derives: {} */", derives.join(", ")));
+                    }
+                }
+                if self.print_options().ownership_hints {
+                    let lang_items = self.tcx().lang_items();
+                    let hint = OWNERSHIP_HINTS.iter().find(|&&(item, _)| {
+                        lang_items.items()[item as usize] == Some(def.did)
+                    });
+                    if let (Some(&(_, hint)), Some(pointee)) = (hint, substs.types().next()) {
+                        p!(write(" /* ownership: {} ", hint), print(pointee), write(" */"));
+                    }
+                }
             }
             ty::Dynamic(data, r) => {
-                let print_r = self.region_should_not_be_omitted(r);
+                let elide_default = *r == ty::ReStatic &&
+                    self.print_options().default_object_lifetimes_elided;
+                let print_r = self.region_should_not_be_omitted(r) && !elide_default;
                 if print_r {
                     p!(write("("));
                 }
@@ -534,14 +1072,18 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
             }
             ty::Opaque(def_id, substs) => {
                 // FIXME(eddyb) print this with `print_def_path`.
-                if self.tcx().sess.verbose() {
+                if self.should_print_verbose() {
                     p!(write("Opaque({:?}, {:?})", def_id, substs));
                     return Ok(self);
                 }
 
                 let def_key = self.tcx().def_key(def_id);
-                if let Some(name) = def_key.disambiguated_data.data.get_opt_name() {
-                    p!(write("{}", name));
+                let name = match self.opaque_position() {
+                    TypePosition::Return => None,
+                    TypePosition::Binding => def_key.disambiguated_data.data.get_opt_name(),
+                };
+                if let Some(name) = name {
+                    p!(print_ident(&name.as_str()));
                     let mut substs = substs.iter();
                     // FIXME(eddyb) print this with `print_def_path`.
                     if let Some(first) = substs.next() {
@@ -552,37 +1094,36 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
                         }
                         p!(write(">"));
                     }
-                    return Ok(self);
-                }
-                // Grab the "TraitA + TraitB" from `impl TraitA + TraitB`,
-                // by looking up the projections associated with the def_id.
-                let bounds = self.tcx().predicates_of(def_id).instantiate(self.tcx(), substs);
-
-                let mut first = true;
-                let mut is_sized = false;
-                p!(write("impl"));
-                for predicate in bounds.predicates {
-                    if let Some(trait_ref) = predicate.to_opt_poly_trait_ref() {
-                        // Don't print +Sized, but rather +?Sized if absent.
-                        if Some(trait_ref.def_id()) == self.tcx().lang_items().sized_trait() {
-                            is_sized = true;
-                            continue;
+                } else {
+                    p!(write("impl "), pretty_print_opaque_bounds(def_id, substs));
+
+                    if self.print_options().opaque_captures {
+                        let captures: Vec<_> = substs.regions()
+                            .filter(|r| **r != ty::ReErased)
+                            .collect();
+                        if !captures.is_empty() {
+                            p!(write("+captures<"), comma_sep(captures.into_iter()), write(">"));
                         }
-
-                        p!(
-                                write("{}", if first { " " } else { "+" }),
-                                print(trait_ref));
-                        first = false;
                     }
                 }
-                if !is_sized {
-                    p!(write("{}?Sized", if first { " " } else { "+" }));
-                } else if first {
-                    p!(write(" Sized"));
+
+                if !self.print_options().signature_mode {
+                    if let Some(hidden_ty) = self.opaque_hidden_ty(def_id, substs) {
+                        p!(write(" /* = "), print(hidden_ty), write(" */"));
+                    }
                 }
             }
             ty::Str => p!(write("str")),
             ty::Generator(did, substs, movability) => {
+                if self.print_options().signature_mode {
+                    p!(write("impl Generator<Yield = "),
+                       print(substs.yield_ty(did, self.tcx())),
+                       write(", Return = "),
+                       print(substs.return_ty(did, self.tcx())),
+                       write(">"));
+                    return Ok(self);
+                }
+
                 let upvar_tys = substs.upvar_tys(did, self.tcx());
                 let witness = substs.witness(did, self.tcx());
                 if movability == hir::GeneratorMovability::Movable {
@@ -601,11 +1142,11 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
                         .flat_map(|v| v.keys())
                         .zip(upvar_tys)
                     {
-                        p!(
-                            write("{}{}:",
-                                    sep,
-                                    self.tcx().hir().name_by_hir_id(var_id)),
-                            print(upvar_ty));
+                        let name = self.tcx().hir().name_by_hir_id(var_id);
+                        p!(write("{}", sep),
+                           print_ident(&name.as_str()),
+                           write(":"),
+                           print(upvar_ty));
                         sep = ", ";
                     }
                 } else {
@@ -627,8 +1168,28 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
                 p!(in_binder(&types));
             }
             ty::Closure(did, substs) => {
+                if self.print_options().signature_mode {
+                    // Before upvar inference, all closures may as well be `FnOnce`.
+                    let kind = substs.closure_kind_ty(did, self.tcx()).to_opt_closure_kind();
+                    p!(write("impl {}", match kind {
+                        Some(ty::ClosureKind::Fn) => "Fn",
+                        Some(ty::ClosureKind::FnMut) => "FnMut",
+                        Some(ty::ClosureKind::FnOnce) | None => "FnOnce",
+                    }));
+                    if let ty::FnPtr(sig) = substs.closure_sig_ty(did, self.tcx()).sty {
+                        let sig = sig.skip_binder();
+                        p!(write("("), comma_sep(sig.inputs().iter().cloned()), write(")"));
+                        if !sig.output().is_unit() {
+                            p!(write(" -> "), print(sig.output()));
+                        }
+                    }
+                    return Ok(self);
+                }
+
                 let upvar_tys = substs.upvar_tys(did, self.tcx());
-                p!(write("[closure"));
+                let is_async = self.print_options().async_closure_signatures &&
+                    is_async_closure(self.tcx(), did);
+                p!(write("{}", if is_async { "[async closure" } else { "[closure" }));
 
                 // FIXME(eddyb) should use `def_span`.
                 if let Some(hir_id) = self.tcx().hir().as_local_hir_id(did) {
@@ -637,18 +1198,19 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
                     } else {
                         p!(write("@{:?}", self.tcx().hir().span_by_hir_id(hir_id)));
                     }
-                    let mut sep = " ";
+                    let list_captures = self.print_options().closure_captures;
+                    let mut sep = if list_captures { " capturing: " } else { " " };
                     for (&var_id, upvar_ty) in self.tcx().upvars(did)
                         .as_ref()
                         .iter()
                         .flat_map(|v| v.keys())
                         .zip(upvar_tys)
                     {
-                        p!(
-                            write("{}{}:",
-                                    sep,
-                                    self.tcx().hir().name_by_hir_id(var_id)),
-                            print(upvar_ty));
+                        let name = self.tcx().hir().name_by_hir_id(var_id);
+                        p!(write("{}", sep),
+                           print_ident(&name.as_str()),
+                           write(":{}", if list_captures { " " } else { "" }),
+                           print(upvar_ty));
                         sep = ", ";
                     }
                 } else {
@@ -664,7 +1226,7 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
                     }
                 }
 
-                if self.tcx().sess.verbose() {
+                if self.should_print_verbose() {
                     p!(write(
                         " closure_kind_ty={:?} closure_sig_ty={:?}",
                         substs.closure_kind_ty(did, self.tcx()),
@@ -672,11 +1234,36 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
                     ));
                 }
 
+                // FIXME: also list the `AsyncFn*` traits the closure implements,
+                // once those exist.
+                if is_async {
+                    if let ty::FnPtr(sig) = substs.closure_sig_ty(did, self.tcx()).sty {
+                        let sig = sig.skip_binder();
+                        p!(write("; fn("),
+                           comma_sep(sig.inputs().iter().cloned()),
+                           write(") -> impl Future"));
+                        if let Some(output) = async_future_output(self.tcx(), sig.output()) {
+                            p!(write("<Output = "), print(output), write(">"));
+                        }
+                    }
+                }
+
                 p!(write("]"))
             },
             ty::Array(ty, sz) => {
-                p!(write("["), print(ty), write("; "));
-                if let Some(n) = sz.assert_usize(self.tcx()) {
+                // Always print the `; N` suffix, even when the length is
+                // unknown (as `_`), so that arrays can't be confused with
+                // slices (which never print a length) in any output mode.
+                let len = sz.assert_usize(self.tcx());
+                p!(write("["));
+                match len {
+                    Some(n) if n > 0 && self.print_options().tuple_indices => {
+                        p!(write(".0..{}: ", n))
+                    }
+                    _ => {}
+                }
+                p!(print(ty), write("; "));
+                if let Some(n) = len {
                     p!(write("{}", n));
                 } else {
                     p!(write("_"));
@@ -695,6 +1282,201 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
         None
     }
 
+    /// Returns the number of types (as counted by `Ty::walk`) that printing
+    /// `ty` now would elide, if it would, e.g. beyond `FmtPrinter`'s depth limit.
+    fn elided_types(&self, _: Ty<'tcx>) -> Option<usize> {
+        None
+    }
+
+    /// Records that the `marker` just printed (see `elision_marker`)
+    /// stands for the types `tys`, e.g. for `FmtPrinter::legend`.
+    fn note_elided_types(&mut self, _marker: &str, _tys: &[Ty<'tcx>]) {}
+
+    /// Calls `f` with the `LookupCache` to do the trait system lookups of the
+    /// printing options in, which is a new one every time, unless overridden.
+    fn with_lookup_cache<R>(
+        &mut self,
+        f: impl FnOnce(TyCtxt<'_, 'gcx, 'tcx>, &mut LookupCache<'tcx>) -> R,
+    ) -> R {
+        f(self.tcx(), &mut LookupCache::default())
+    }
+
+    /// Returns whether items of the crate `cnum` should always be printed
+    /// with their full (defining) path, instead of the shortest path through
+    /// which they're visible from the local crate (see `visible_parent_map`),
+    /// e.g. `alloc::vec::Vec` instead of `std::vec::Vec`.
+    fn prints_full_paths_for(&self, _: CrateNum) -> bool {
+        false
+    }
+
+    /// Appends the path segment of `def_id` (possibly under the name it was
+    /// re-exported as) to the visible path printed so far, for printers that
+    /// need to know which def each of the segments of such paths belongs to,
+    /// as they don't all go through `print_def_path`.
+    fn path_append_visible(
+        self,
+        _: DefId,
+        disambiguated_data: &DisambiguatedDefPathData,
+    ) -> Result<Self::Path, Self::Error> {
+        self.path_append(Ok, disambiguated_data)
+    }
+
+    /// Returns the name of a type alias to print instead of the ADT `def_id`
+    /// instantiated with `substs`, along with the generic arguments that are
+    /// left to pass to the alias, e.g. `("std::io::Result", [T])` for
+    /// `Result<T, std::io::Error>`.
+    fn collapse_type_alias(
+        &self,
+        _: DefId,
+        _: SubstsRef<'tcx>,
+    ) -> Option<(String, Vec<Kind<'tcx>>)> {
+        None
+    }
+
+    /// Returns the hidden (concrete) type of the opaque type `def_id`
+    /// instantiated with `substs`, if it's known to the caller, in which
+    /// case it's printed after the opaque type, e.g. `impl Trait /* = u8 */`.
+    fn opaque_hidden_ty(&self, _: DefId, _: SubstsRef<'tcx>) -> Option<Ty<'tcx>> {
+        None
+    }
+
+    /// Returns where the `impl Trait` type being printed is written, which
+    /// decides how those defined by an alias are printed, see `TypePosition`.
+    fn opaque_position(&self) -> TypePosition {
+        TypePosition::Binding
+    }
+
+    /// Prints the bounds of the anonymous `impl Trait` type `def_id`, e.g.
+    /// `Iterator<Item: Debug>+Send` (without the leading `impl `).
+    ///
+    /// Associated type equality constraints are not printed, unless the same
+    /// trait also has `AssocTy: Bounds` constraints, which desugar to (and get
+    /// resugared from) `AssocTy = impl Bounds` - an actual `AssocTy = impl Bounds`
+    /// in the source, being equivalent, is printed this way as well.
+    fn pretty_print_opaque_bounds(
+        mut self,
+        def_id: DefId,
+        substs: SubstsRef<'tcx>,
+    ) -> Result<Self, Self::Error> {
+        define_scoped_cx!(self);
+
+        // Grab the "TraitA + TraitB" from `impl TraitA + TraitB`,
+        // by looking up the projections associated with the def_id.
+        let bounds = self.tcx().predicates_of(def_id).instantiate(self.tcx(), substs);
+
+        let mut traits = vec![];
+        let mut auto_traits = vec![];
+        let mut projections = vec![];
+        let mut is_sized = false;
+        for predicate in bounds.predicates {
+            if let ty::Predicate::Projection(projection) = predicate {
+                projections.push(*projection.skip_binder());
+            } else if let Some(trait_ref) = predicate.to_opt_poly_trait_ref() {
+                // Don't print +Sized, but rather +?Sized if absent.
+                if Some(trait_ref.def_id()) == self.tcx().lang_items().sized_trait() {
+                    is_sized = true;
+                    continue;
+                }
+
+                if self.tcx().trait_is_auto(trait_ref.def_id()) {
+                    auto_traits.push(trait_ref);
+                } else {
+                    traits.push(trait_ref);
+                }
+            }
+        }
+
+        // Keep the other traits in their declared order, which the
+        // user most likely placed the primary trait first in, but
+        // sort the auto traits after them, as `dyn` printing does.
+        auto_traits.sort_by_cached_key(|trait_ref| {
+            self.tcx().def_path_str(trait_ref.def_id())
+        });
+
+        let mut first = true;
+        for trait_ref in traits.into_iter().chain(auto_traits) {
+            if !first {
+                p!(write("+"));
+            }
+            first = false;
+
+            let constraints: Vec<_> = projections.iter().cloned().filter(|projection| {
+                projection.projection_ty.trait_ref(self.tcx()).def_id == trait_ref.def_id()
+            }).collect();
+            let has_assoc_bounds = constraints.iter().any(|projection| {
+                assoc_bounds_opaque(self.tcx(), projection.ty).is_some()
+            });
+            if !has_assoc_bounds {
+                p!(print(trait_ref));
+                continue;
+            }
+
+            // HACK(eddyb) this duplicates `FmtPrinter`'s `path_generic_args`,
+            // in order to place the constraints inside the `<...>`.
+            let trait_ref = *trait_ref.skip_binder();
+            p!(print_def_path(trait_ref.def_id, &[]));
+            let args: Vec<_> = self.generic_args_to_print(
+                self.tcx().generics_of(trait_ref.def_id),
+                trait_ref.substs,
+            ).iter().cloned().filter(|arg| {
+                match arg.unpack() {
+                    UnpackedKind::Lifetime(r) => *r != ty::ReErased,
+                    _ => true,
+                }
+            }).collect();
+            p!(generic_delimiters(|mut cx| {
+                let has_args = !args.is_empty();
+                cx = cx.comma_sep(args.into_iter())?;
+                for (i, projection) in constraints.into_iter().enumerate() {
+                    if i > 0 || has_args {
+                        write!(cx, ", ")?;
+                    }
+                    let item_def_id = projection.projection_ty.item_def_id;
+                    let name = cx.tcx().associated_item(item_def_id).ident;
+                    match assoc_bounds_opaque(cx.tcx(), projection.ty) {
+                        Some((def_id, substs)) => {
+                            cx = cx.print_ident(&name.as_str())?;
+                            write!(cx, ": ")?;
+                            cx = cx.pretty_print_opaque_bounds(def_id, substs)?;
+                        }
+                        None => {
+                            cx = cx.print_ident(&name.as_str())?;
+                            write!(cx, " = ")?;
+                            cx = cx.print_type(projection.ty)?;
+                        }
+                    }
+                }
+                Ok(cx)
+            }));
+        }
+        if !is_sized {
+            p!(write("{}?Sized", if first { "" } else { "+" }));
+        } else if first {
+            p!(write("Sized"));
+        }
+
+        Ok(self)
+    }
+
+    /// Prints `#[repr(align(N))]` for the alignment `align` (in bytes),
+    /// which may also be symbolic, e.g. a const parameter.
+    /// Nothing is printed for an alignment of `0`, which is how
+    /// `ty::ReprOptions` represents the default alignment.
+    fn pretty_print_repr_align(
+        mut self,
+        align: &'tcx ty::Const<'tcx>,
+    ) -> Result<Self, Self::Error> {
+        define_scoped_cx!(self);
+
+        match align.assert_usize(self.tcx()) {
+            Some(0) => {}
+            // Don't use `print_const` here, as it would add the `usize` suffix.
+            Some(n) => p!(write("#[repr(align({}))]", n)),
+            None => p!(write("#[repr(align("), print(align), write("))]")),
+        }
+        Ok(self)
+    }
+
     fn pretty_print_dyn_existential(
         mut self,
         predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
@@ -711,7 +1493,7 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
 
             // Special-case `Fn(...) -> ...` and resugar it.
             let fn_trait_kind = self.tcx().lang_items().fn_trait_kind(principal.def_id);
-            if !self.tcx().sess.verbose() && fn_trait_kind.is_some() {
+            if !self.should_print_verbose() && fn_trait_kind.is_some() {
                 if let ty::Tuple(ref args) = principal.substs.type_at(0).sty {
                     let mut projections = predicates.projection_bounds();
                     if let (Some(proj), None) = (projections.next(), projections.next()) {
@@ -747,7 +1529,14 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
                         _ => true,
                     }
                 });
-                let mut projections = predicates.projection_bounds();
+                // The projections come ordered by `DefPathHash`, like the
+                // auto traits below, so sort them by path, for the same
+                // reason, falling back to the `DefId` for equal paths.
+                let mut projections: Vec<_> = predicates.projection_bounds().collect();
+                projections.sort_by_cached_key(|proj| {
+                    (self.tcx().def_path_str(proj.item_def_id), proj.item_def_id)
+                });
+                let mut projections = projections.into_iter();
 
                 let arg0 = args.next();
                 let projection0 = projections.next();
@@ -780,7 +1569,9 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
         // "pseudorandomly" on the compiler version and the target.
         //
         // To avoid that causing instabilities in compiletest
-        // output, sort the auto-traits alphabetically.
+        // output, sort the auto-traits alphabetically, by their
+        // paths, e.g. `dyn Trait + std::marker::Send + std::marker::Sync`,
+        // after the principal trait (if any), which always comes first.
         auto_traits.sort();
 
         for (_, def_id) in auto_traits {
@@ -822,6 +1613,24 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
         Ok(self)
     }
 
+    fn pretty_print_fn_sig(
+        mut self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        define_scoped_cx!(self);
+
+        if sig.unsafety == hir::Unsafety::Unsafe {
+            p!(write("unsafe "));
+        }
+
+        if sig.abi != Abi::Rust {
+            p!(write("extern {} ", sig.abi));
+        }
+
+        p!(write("fn"), pretty_fn_sig(sig.inputs(), sig.c_variadic, sig.output()));
+        Ok(self)
+    }
+
     fn pretty_print_const(
         mut self,
         ct: &'tcx ty::Const<'tcx>,
@@ -834,14 +1643,47 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
             return Ok(self);
         }
         if let ConstValue::Unevaluated(did, substs) = ct.val {
+            if self.print_options().evaluated_consts &&
+                !self.should_print_verbose() &&
+                self.tcx().def_kind(did) != Some(DefKind::Static) {
+                if let Some(value) = eval_const(self.tcx(), did, substs) {
+                    match (value.val, &value.ty.sty) {
+                        (ConstValue::Scalar(Scalar::Raw { .. }), ty::Bool) |
+                        (ConstValue::Scalar(Scalar::Raw { .. }), ty::Char) |
+                        (ConstValue::Scalar(Scalar::Raw { .. }), ty::Int(_)) |
+                        (ConstValue::Scalar(Scalar::Raw { .. }), ty::Uint(_)) => {
+                            p!(print(value));
+                            return Ok(self);
+                        }
+                        _ => {}
+                    }
+                }
+            }
             match self.tcx().def_kind(did) {
                 | Some(DefKind::Static)
                 | Some(DefKind::Const)
-                | Some(DefKind::AssocConst) => p!(print_value_path(did, substs)),
+                | Some(DefKind::AssocConst) => {
+                    p!(print_value_path(did, substs));
+                    self = print_const_ty_annotation(self, ct.ty)?;
+                    if self.print_options().const_values &&
+                        self.tcx().def_kind(did) != Some(DefKind::Static) {
+                        if let Some(value) = eval_const(self.tcx(), did, substs) {
+                            p!(write(" /* = "), print(value), write(" */"));
+                        }
+                    }
+                }
                 _ => if did.is_local() {
+                    let hir = self.tcx().global_tcx().hir();
+                    let body_id = hir.as_local_hir_id(did)
+                        .and_then(|hir_id| hir.maybe_body_owned_by_by_hir_id(hir_id))
+                        .filter(|_| self.print_options().structural_const_exprs);
                     let span = self.tcx().def_span(did);
-                    if let Ok(snip) = self.tcx().sess.source_map().span_to_snippet(span) {
-                        p!(write("{}", snip))
+                    if let Some(body_id) = body_id {
+                        p!(pretty_print_const_expr(&hir.body(body_id).value, did, substs));
+                        self = print_const_ty_annotation(self, ct.ty)?;
+                    } else if let Ok(snip) = self.tcx().sess.source_map().span_to_snippet(span) {
+                        p!(write("{}", snip));
+                        self = print_const_ty_annotation(self, ct.ty)?;
                     } else {
                         p!(write("_: "), print(ct.ty))
                     }
@@ -855,8 +1697,12 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
             p!(write("_: "), print(ct.ty));
             return Ok(self);
         }
-        if let ConstValue::Param(ParamConst { name, .. }) = ct.val {
-            p!(write("{}", name));
+        if let ConstValue::Param(ParamConst { name, index }) = ct.val {
+            p!(print_ident(&name.as_str()));
+            if self.print_options().param_indices {
+                p!(write("#{}", index));
+            }
+            self = print_const_ty_annotation(self, ct.ty)?;
             return Ok(self);
         }
         if let ConstValue::Scalar(Scalar::Raw { data, .. }) = ct.val {
@@ -892,18 +1738,52 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
                 _ => {},
             }
         }
-        if let ty::Ref(_, ref_ty, _) = ct.ty.sty {
-            let byte_str = match (ct.val, &ref_ty.sty) {
-                (ConstValue::Scalar(Scalar::Ptr(ptr)), ty::Array(t, n)) if *t == u8 => {
-                    let n = n.unwrap_usize(self.tcx());
-                    Some(self.tcx()
-                        .alloc_map.lock()
-                        .unwrap_memory(ptr.alloc_id)
-                        .get_bytes(&self.tcx(), ptr, Size::from_bytes(n)).unwrap())
-                },
-                (ConstValue::Slice { data, start, end }, ty::Slice(t)) if *t == u8 => {
-                    Some(&data.bytes[start..end])
-                },
+        if let ty::RawPtr(_) = ct.ty.sty {
+            // Raw pointers are printed by their address, unless they point
+            // into an allocation, in which case that's printed instead (as
+            // a path for statics and functions), with the offset into it.
+            match ct.val {
+                ConstValue::Scalar(Scalar::Raw { data: 0, .. }) => {
+                    p!(write("null"));
+                    return Ok(self);
+                }
+                ConstValue::Scalar(Scalar::Raw { data, .. }) => {
+                    p!(write("ptr<{:#x}>", data));
+                    return Ok(self);
+                }
+                ConstValue::Scalar(Scalar::Ptr(ptr)) => {
+                    let alloc = self.tcx().alloc_map.lock().get(ptr.alloc_id);
+                    p!(write("ptr<"));
+                    match alloc {
+                        Some(GlobalAlloc::Static(def_id)) => p!(print_value_path(def_id, &[])),
+                        Some(GlobalAlloc::Function(instance)) => {
+                            p!(print_value_path(instance.def_id(), instance.substs))
+                        }
+                        Some(GlobalAlloc::Memory(_)) | None => {
+                            p!(write("alloc{}", ptr.alloc_id))
+                        }
+                    }
+                    if ptr.offset.bytes() != 0 {
+                        p!(write("+{:#x}", ptr.offset.bytes()));
+                    }
+                    p!(write(">"));
+                    return Ok(self);
+                }
+                _ => {}
+            }
+        }
+        if let ty::Ref(_, ref_ty, _) = ct.ty.sty {
+            let byte_str = match (ct.val, &ref_ty.sty) {
+                (ConstValue::Scalar(Scalar::Ptr(ptr)), ty::Array(t, n)) if *t == u8 => {
+                    let n = n.unwrap_usize(self.tcx());
+                    Some(self.tcx()
+                        .alloc_map.lock()
+                        .unwrap_memory(ptr.alloc_id)
+                        .get_bytes(&self.tcx(), ptr, Size::from_bytes(n)).unwrap())
+                },
+                (ConstValue::Slice { data, start, end }, ty::Slice(t)) if *t == u8 => {
+                    Some(&data.bytes[start..end])
+                },
                 (ConstValue::Slice { data, start, end }, ty::Str) => {
                     let slice = &data.bytes[start..end];
                     let s = ::std::str::from_utf8(slice)
@@ -924,10 +1804,826 @@ pub trait PrettyPrinter<'gcx: 'tcx, 'tcx>:
                 return Ok(self);
             }
         }
+        if let (ConstValue::ByRef(ptr, alloc), ty::Array(elem_ty, len)) = (ct.val, &ct.ty.sty) {
+            if self.print_options().const_array_elems {
+                let elem_ty = *elem_ty;
+                if let Some(elems) = const_array_elems(self.tcx(), ptr, alloc, elem_ty, *len) {
+                    let elem = |cx: &Self, i: usize| {
+                        ty::Const::from_scalar(cx.tcx(), elems[i], elem_ty)
+                    };
+                    p!(write("["));
+                    if elems.len() > 1 && elems.iter().all(|&elem| elem == elems[0]) {
+                        p!(print(elem(&self, 0)), write("; {}", elems.len()));
+                    } else {
+                        for i in 0..elems.len().min(MAX_CONST_ARRAY_ELEMS) {
+                            if i > 0 {
+                                p!(write(", "));
+                            }
+                            p!(print(elem(&self, i)));
+                        }
+                        if elems.len() > MAX_CONST_ARRAY_ELEMS {
+                            let omitted = elems.len() - MAX_CONST_ARRAY_ELEMS;
+                            let marker = self.print_options().elision_marker(omitted, "element");
+                            p!(write(", {}", marker));
+                        }
+                    }
+                    p!(write("]"));
+                    return Ok(self);
+                }
+            }
+        }
+        if let (ConstValue::ByRef(ptr, alloc), ty::Adt(..)) = (ct.val, &ct.ty.sty) {
+            let tcx = self.tcx().global_tcx();
+            let value = self.tcx().lift_to_global(&ct.ty).and_then(|ty| {
+                const_adt_value(tcx, ptr, alloc, ty, MAX_CONST_ADT_DEPTH)
+            });
+            if let Some(value) = value {
+                p!(pretty_print_const_adt_value(value));
+                return Ok(self);
+            }
+        }
         p!(write("{:?} : ", ct.val), print(ct.ty));
 
         Ok(self)
     }
+
+    /// Prints a struct or enum const, decoded by `const_adt_value`, as it would
+    /// be written as an expression, e.g. `Foo { x: 3u8 }` or `E::A(true)`.
+    fn pretty_print_const_adt_value(
+        mut self,
+        value: ConstAdtValue<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        define_scoped_cx!(self);
+
+        let (variant_def_id, ctor_kind, fields) = match value {
+            ConstAdtValue::Scalar(ct) => {
+                p!(print(ct));
+                return Ok(self);
+            }
+            ConstAdtValue::Elided => {
+                p!(write("{}", self.print_options().elision_marker(1, "value")));
+                return Ok(self);
+            }
+            ConstAdtValue::Adt { variant_def_id, ctor_kind, fields } => {
+                (variant_def_id, ctor_kind, fields)
+            }
+        };
+        p!(print_value_path(variant_def_id, &[]));
+        match ctor_kind {
+            CtorKind::Const => {}
+            CtorKind::Fn => {
+                p!(write("("));
+                for (i, (_, field)) in fields.into_iter().enumerate() {
+                    if i > 0 {
+                        p!(write(", "));
+                    }
+                    p!(pretty_print_const_adt_value(field));
+                }
+                p!(write(")"));
+            }
+            CtorKind::Fictive => {
+                p!(write(" {{"));
+                for (i, (name, field)) in fields.into_iter().enumerate() {
+                    p!(write("{} ", if i > 0 { "," } else { "" }),
+                       print_ident(&name.as_str()),
+                       write(": "),
+                       pretty_print_const_adt_value(field));
+                }
+                p!(write(" }}"));
+            }
+        }
+        Ok(self)
+    }
+
+    /// Prints `expr`, from the body of the anonymous const `def_id` (e.g. the
+    /// `{ N + T::SIZE }` of a const arg, under `structural_const_exprs`),
+    /// instantiated with `substs`, keeping its structure (with operands that
+    /// are operations parenthesized): const params are printed as their args,
+    /// and unqualified paths to (associated) consts as value paths. It's never
+    /// normalized or evaluated, faithfully keeping it abstract. Anything else
+    /// is printed as written.
+    fn pretty_print_const_expr(
+        mut self,
+        expr: &hir::Expr,
+        def_id: DefId,
+        substs: SubstsRef<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        define_scoped_cx!(self);
+
+        match expr.node {
+            hir::ExprKind::Block(ref block, None) if block.stmts.is_empty() &&
+                block.expr.is_some() => {
+                let value = block.expr.as_ref().unwrap();
+                p!(write("{{ "), pretty_print_const_expr(value, def_id, substs), write(" }}"));
+                return Ok(self);
+            }
+            hir::ExprKind::Binary(op, ref lhs, ref rhs) => {
+                p!(pretty_print_const_operand(lhs, def_id, substs),
+                   write(" {} ", op.node.as_str()),
+                   pretty_print_const_operand(rhs, def_id, substs));
+                return Ok(self);
+            }
+            hir::ExprKind::Unary(op, ref operand) => {
+                p!(write("{}", op.as_str()), pretty_print_const_operand(operand, def_id, substs));
+                return Ok(self);
+            }
+            hir::ExprKind::Path(hir::QPath::Resolved(None, ref path)) => match path.res {
+                Res::Def(DefKind::ConstParam, param_def_id) => {
+                    match const_param_arg(self.tcx(), def_id, param_def_id, substs) {
+                        Some(arg) => p!(print(arg)),
+                        None => p!(print_ident(&path.segments.last().unwrap().ident.as_str())),
+                    }
+                    return Ok(self);
+                }
+                Res::Def(DefKind::Const, did) |
+                Res::Def(DefKind::AssocConst, did) => {
+                    p!(print_value_path(did, &[]));
+                    return Ok(self);
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+
+        p!(write("{}", hir::print::to_string(hir::print::NO_ANN, |s| s.print_expr(expr))));
+        Ok(self)
+    }
+
+    /// Prints the operand `expr` of an operation with `pretty_print_const_expr`,
+    /// parenthesized if it's an operation itself, e.g. `(N + 1) * 2`.
+    fn pretty_print_const_operand(
+        mut self,
+        expr: &hir::Expr,
+        def_id: DefId,
+        substs: SubstsRef<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        define_scoped_cx!(self);
+
+        match expr.node {
+            hir::ExprKind::Binary(..) | hir::ExprKind::Unary(..) => {
+                p!(write("("), pretty_print_const_expr(expr, def_id, substs), write(")"));
+            }
+            _ => p!(pretty_print_const_expr(expr, def_id, substs)),
+        }
+        Ok(self)
+    }
+}
+
+/// Returns the arg in `substs`, the substs of `def_id`, for the const param
+/// `param_def_id`, which may be a param of `def_id` itself, or of its parents.
+fn const_param_arg<'tcx>(
+    tcx: TyCtxt<'_, '_, 'tcx>,
+    def_id: DefId,
+    param_def_id: DefId,
+    substs: SubstsRef<'tcx>,
+) -> Option<Kind<'tcx>> {
+    let mut generics = tcx.generics_of(def_id);
+    loop {
+        if let Some(&index) = generics.param_def_id_to_index.get(&param_def_id) {
+            return substs.get(index as usize).cloned();
+        }
+        generics = tcx.generics_of(generics.parent?);
+    }
+}
+
+/// Evaluates the (associated) const `def_id`, instantiated with `substs`,
+/// if it's fully known, i.e. doesn't depend on any generic parameters.
+fn eval_const<'tcx>(
+    tcx: TyCtxt<'_, '_, 'tcx>,
+    def_id: DefId,
+    substs: SubstsRef<'tcx>,
+) -> Option<&'tcx ty::Const<'tcx>> {
+    if substs.needs_subst() || in_nested_query(tcx) {
+        return None;
+    }
+    let substs = tcx.lift_to_global(&substs)?;
+    let param_env = ty::ParamEnv::reveal_all();
+    let instance = ty::Instance::resolve(tcx.global_tcx(), param_env, def_id, substs)?;
+    let cid = GlobalId {
+        instance,
+        promoted: None,
+    };
+    tcx.const_eval(param_env.and(cid)).ok()
+}
+
+/// Reads the elements of a const array of integers, `bool`s or `char`s,
+/// if all of them are initialized (and none of them are pointers).
+fn const_array_elems<'tcx>(
+    tcx: TyCtxt<'_, '_, 'tcx>,
+    ptr: Pointer,
+    alloc: &Allocation,
+    elem_ty: Ty<'tcx>,
+    len: &'tcx ty::Const<'tcx>,
+) -> Option<Vec<Scalar>> {
+    match elem_ty.sty {
+        ty::Bool | ty::Char | ty::Int(_) | ty::Uint(_) => {}
+        _ => return None,
+    }
+    let len = len.assert_usize(tcx)?;
+    let global_elem_ty = tcx.lift_to_global(&elem_ty)?;
+    let size = tcx.layout_of(ty::ParamEnv::empty().and(global_elem_ty)).ok()?.size;
+    (0..len).map(|i| {
+        let ptr = ptr.offset(size * i, &tcx).ok()?;
+        match alloc.read_scalar(&tcx, ptr, size).ok()? {
+            ScalarMaybeUndef::Scalar(scalar @ Scalar::Raw { .. }) => Some(scalar),
+            _ => None,
+        }
+    }).collect()
+}
+
+/// The value of a struct or enum const (e.g. a const generic arg, under
+/// `adt_const_params`), or of one of its fields, see `const_adt_value`.
+enum ConstAdtValue<'tcx> {
+    /// An integer, `bool` or `char`.
+    Scalar(&'tcx ty::Const<'tcx>),
+    /// A struct, or an enum variant, with the names and values of its fields.
+    Adt {
+        variant_def_id: DefId,
+        ctor_kind: CtorKind,
+        fields: Vec<(ast::Name, ConstAdtValue<'tcx>)>,
+    },
+    /// A struct or enum nested more than `MAX_CONST_ADT_DEPTH` levels deep.
+    Elided,
+}
+
+/// Decodes the struct or enum value of type `ty` at `ptr` in `alloc`, field
+/// by field, down to the integers, `bool`s and `char`s in it, and at most
+/// `depth` levels deep. Returns `None` for values containing anything else
+/// (e.g. references or unions), and for enums with niche-encoded variants.
+fn const_adt_value<'tcx>(
+    tcx: TyCtxt<'_, 'tcx, 'tcx>,
+    ptr: Pointer,
+    alloc: &Allocation,
+    ty: Ty<'tcx>,
+    depth: usize,
+) -> Option<ConstAdtValue<'tcx>> {
+    let cx = LayoutCx { tcx, param_env: ty::ParamEnv::reveal_all() };
+    let layout = cx.layout_of(ty).ok()?;
+    let (def, substs) = match ty.sty {
+        ty::Bool | ty::Char | ty::Int(_) | ty::Uint(_) => {
+            return match alloc.read_scalar(&tcx, ptr, layout.size).ok()? {
+                ScalarMaybeUndef::Scalar(scalar @ Scalar::Raw { .. }) => {
+                    Some(ConstAdtValue::Scalar(ty::Const::from_scalar(tcx, scalar, ty)))
+                }
+                _ => None,
+            };
+        }
+        ty::Adt(def, substs) if !def.is_union() => (def, substs),
+        _ => return None,
+    };
+    if depth == 0 {
+        return Some(ConstAdtValue::Elided);
+    }
+
+    let variant_index = match layout.variants {
+        Variants::Single { index } => index,
+        Variants::Multiple { ref discr, discr_kind: DiscriminantKind::Tag, discr_index, .. } => {
+            let discr_ptr = ptr.offset(layout.fields.offset(discr_index), &tcx).ok()?;
+            let discr_size = discr.value.size(&tcx);
+            let bits = match alloc.read_scalar(&tcx, discr_ptr, discr_size).ok()? {
+                ScalarMaybeUndef::Scalar(Scalar::Raw { data, .. }) => data,
+                _ => return None,
+            };
+            def.discriminants(tcx).find(|(_, discr)| truncate(discr.val, discr_size) == bits)?.0
+        }
+        Variants::Multiple { .. } => return None,
+    };
+    // Uninhabited enums have no values, let alone ones to decode.
+    let variant = def.variants.get(variant_index)?;
+    let variant_layout = layout.for_variant(&cx, variant_index);
+    let fields = variant.fields.iter().enumerate().map(|(i, field)| {
+        let field_ptr = ptr.offset(variant_layout.fields.offset(i), &tcx).ok()?;
+        let value = const_adt_value(tcx, field_ptr, alloc, field.ty(tcx, substs), depth - 1)?;
+        Some((field.ident.name, value))
+    }).collect::<Option<_>>()?;
+    Some(ConstAdtValue::Adt {
+        variant_def_id: variant.def_id,
+        ctor_kind: variant.ctor_kind,
+        fields,
+    })
+}
+
+/// Prints `: ty` after a const, if under `const_types`. The option is
+/// disabled while printing `ty` itself, so consts in it (e.g. array lengths)
+/// don't get annotated in turn.
+fn print_const_ty_annotation<'gcx: 'tcx, 'tcx, P: PrettyPrinter<'gcx, 'tcx>>(
+    mut cx: P,
+    ty: Ty<'tcx>,
+) -> Result<P, fmt::Error> {
+    let options = cx.print_options();
+    if !options.const_types {
+        return Ok(cx);
+    }
+    write!(cx, ": ")?;
+    cx.set_print_options(PrintOptions { const_types: false, ..options });
+    let mut cx = ty.print(cx)?;
+    cx.set_print_options(options);
+    Ok(cx)
+}
+
+/// How `print_type_diff` marks the parts of types that differ (and `FmtPrinter`
+/// its `highlighted_tys`), by wrapping them in `start` and `end`, e.g. in ANSI
+/// escapes, to print them in a distinct style.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DiffHighlight {
+    pub start: &'static str,
+    pub end: &'static str,
+}
+
+impl Default for DiffHighlight {
+    /// Marks differences with `«` and `»`, which can't otherwise appear in types.
+    fn default() -> Self {
+        DiffHighlight { start: "«", end: "»" }
+    }
+}
+
+/// Prints `expected` and `found` side by side, as `expected vs found`, with
+/// the parts of each that differ from the other marked with `highlight`, e.g.
+/// `Vec<«u32»> vs Vec<«i32»>`. Both types are walked in lockstep, as long as
+/// their structure (and the defs they refer to) match, and only the first
+/// subtrees diverging get highlighted, as a whole, even if some of their
+/// components match, e.g. `«Option<u8>» vs «Result<u8, E>»`.
+pub fn print_type_diff<'gcx: 'tcx, 'tcx, P: PrettyPrinter<'gcx, 'tcx>>(
+    mut cx: P,
+    expected: Ty<'tcx>,
+    found: Ty<'tcx>,
+    highlight: DiffHighlight,
+) -> Result<P, fmt::Error> {
+    cx = print_type_diff_side(cx, expected, found, highlight)?;
+    write!(cx, " vs ")?;
+    print_type_diff_side(cx, found, expected, highlight)
+}
+
+/// Prints `ty`, highlighting the parts of it that differ from `other`,
+/// i.e. one side of `print_type_diff`.
+fn print_type_diff_side<'gcx: 'tcx, 'tcx, P: PrettyPrinter<'gcx, 'tcx>>(
+    mut cx: P,
+    ty: Ty<'tcx>,
+    other: Ty<'tcx>,
+    highlight: DiffHighlight,
+) -> Result<P, fmt::Error> {
+    define_scoped_cx!(cx);
+
+    if ty == other {
+        p!(print(ty));
+        return Ok(cx);
+    }
+    match (&ty.sty, &other.sty) {
+        (&ty::Adt(def, substs), &ty::Adt(other_def, other_substs)) if def.did == other_def.did => {
+            // Print as many args as either side needs (see `generic_args_to_print`),
+            // so e.g. an arg matching its default is still printed, if different.
+            let generics = cx.tcx().generics_of(def.did);
+            let count = cx.generic_args_to_print(generics, substs).len()
+                .max(cx.generic_args_to_print(generics, other_substs).len());
+            let own_params = generics.parent_count..generics.parent_count + count;
+            let args: Vec<_> = substs[own_params.clone()].iter()
+                .zip(&other_substs[own_params])
+                .filter(|(arg, _)| match arg.unpack() {
+                    UnpackedKind::Lifetime(r) => *r != ty::ReErased,
+                    _ => true,
+                })
+                .collect();
+            p!(print_def_path(def.did, &[]));
+            if !args.is_empty() {
+                p!(write("<"));
+                for (i, (&arg, &other_arg)) in args.into_iter().enumerate() {
+                    if i > 0 {
+                        p!(write(", "));
+                    }
+                    match (arg.unpack(), other_arg.unpack()) {
+                        (UnpackedKind::Type(ty), UnpackedKind::Type(other)) => {
+                            cx = print_type_diff_side(cx, ty, other, highlight)?;
+                        }
+                        _ if arg == other_arg => p!(print(arg)),
+                        _ => cx = print_highlighted(cx, arg, highlight)?,
+                    }
+                }
+                p!(write(">"));
+            }
+        }
+        (&ty::Ref(r, ty, mutbl), &ty::Ref(_, other, other_mutbl)) if mutbl == other_mutbl => {
+            p!(write("&"));
+            if cx.region_should_not_be_omitted(r) {
+                p!(print(r), write(" "));
+            }
+            if mutbl == hir::MutMutable {
+                p!(write("mut "));
+            }
+            cx = print_type_diff_side(cx, ty, other, highlight)?;
+        }
+        (&ty::RawPtr(mt), &ty::RawPtr(other_mt)) if mt.mutbl == other_mt.mutbl => {
+            p!(write("*{} ", match mt.mutbl {
+                hir::MutMutable => "mut",
+                hir::MutImmutable => "const",
+            }));
+            cx = print_type_diff_side(cx, mt.ty, other_mt.ty, highlight)?;
+        }
+        (&ty::Tuple(tys), &ty::Tuple(other_tys)) if tys.len() == other_tys.len() => {
+            p!(write("("));
+            for (i, (ty, other)) in tys.iter().zip(other_tys.iter()).enumerate() {
+                if i > 0 {
+                    p!(write(", "));
+                }
+                cx = print_type_diff_side(cx, ty.expect_ty(), other.expect_ty(), highlight)?;
+            }
+            if tys.len() == 1 {
+                p!(write(","));
+            }
+            p!(write(")"));
+        }
+        (&ty::Array(ty, len), &ty::Array(other, other_len)) => {
+            p!(write("["));
+            cx = print_type_diff_side(cx, ty, other, highlight)?;
+            p!(write("; "));
+            // Unknown lengths are printed as `_`, like `pretty_print_type` does.
+            let n = len.assert_usize(cx.tcx());
+            let len = n.map_or("_".to_string(), |n| n.to_string());
+            if n.is_some() && n == other_len.assert_usize(cx.tcx()) {
+                p!(write("{}", len));
+            } else {
+                p!(write("{}{}{}", highlight.start, len, highlight.end));
+            }
+            p!(write("]"));
+        }
+        (&ty::Slice(ty), &ty::Slice(other)) => {
+            p!(write("["));
+            cx = print_type_diff_side(cx, ty, other, highlight)?;
+            p!(write("]"));
+        }
+        // Fn pointers with late-bound regions are highlighted as a whole,
+        // as printing them takes a binder (one for each side, in general).
+        (&ty::FnPtr(sig), &ty::FnPtr(other_sig))
+            if !sig.skip_binder().has_escaping_bound_vars() &&
+                !other_sig.skip_binder().has_escaping_bound_vars() &&
+                sig.unsafety() == other_sig.unsafety() &&
+                sig.abi() == other_sig.abi() &&
+                sig.c_variadic() == other_sig.c_variadic() &&
+                sig.skip_binder().inputs().len() == other_sig.skip_binder().inputs().len() =>
+        {
+            let (sig, other_sig) = (sig.skip_binder(), other_sig.skip_binder());
+            if sig.unsafety == hir::Unsafety::Unsafe {
+                p!(write("unsafe "));
+            }
+            if sig.abi != Abi::Rust {
+                p!(write("extern {} ", sig.abi));
+            }
+            p!(write("fn("));
+            for (i, (&ty, &other)) in sig.inputs().iter().zip(other_sig.inputs()).enumerate() {
+                if i > 0 {
+                    p!(write(", "));
+                }
+                cx = print_type_diff_side(cx, ty, other, highlight)?;
+            }
+            if sig.c_variadic && !sig.inputs().is_empty() {
+                p!(write(", ..."));
+            }
+            p!(write(")"));
+            // The unit return type is omitted, like `pretty_fn_sig` does, unless
+            // the other side returns something else, so there's something to mark.
+            if !sig.output().is_unit() || !other_sig.output().is_unit() {
+                p!(write(" -> "));
+                cx = print_type_diff_side(cx, sig.output(), other_sig.output(), highlight)?;
+            }
+        }
+        _ => cx = print_highlighted(cx, ty, highlight)?,
+    }
+    Ok(cx)
+}
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns `expected` and `found` side by side, with the parts of each
+    /// that differ from the other marked with `«` and `»` (the default
+    /// `DiffHighlight`), e.g. `Vec<«u32»> vs Vec<«i32»>`, see `print_type_diff`.
+    pub fn type_diff(self, expected: Ty<'tcx>, found: Ty<'tcx>) -> String {
+        let mut s = String::new();
+        let cx = FmtPrinter::new(self, &mut s, Namespace::TypeNS);
+        let _ = print_type_diff(cx, expected, found, DiffHighlight::default());
+        s
+    }
+}
+
+fn print_highlighted<'gcx: 'tcx, 'tcx, P, T>(
+    mut cx: P,
+    value: T,
+    highlight: DiffHighlight,
+) -> Result<P, fmt::Error>
+    where P: PrettyPrinter<'gcx, 'tcx>,
+          T: Print<'gcx, 'tcx, P, Output = P, Error = fmt::Error>
+{
+    write!(cx, "{}", highlight.start)?;
+    cx = value.print(cx)?;
+    write!(cx, "{}", highlight.end)?;
+    Ok(cx)
+}
+
+/// Returns the anonymous `impl Trait` type that `ty` is, if it's the kind
+/// of type an `AssocTy: Bounds` constraint in `impl Trait` desugars to.
+fn assoc_bounds_opaque<'tcx>(
+    tcx: TyCtxt<'_, '_, 'tcx>,
+    ty: Ty<'tcx>,
+) -> Option<(DefId, SubstsRef<'tcx>)> {
+    match ty.sty {
+        ty::Opaque(def_id, substs)
+            if tcx.def_key(def_id).disambiguated_data.data == DefPathData::ImplTrait =>
+        {
+            Some((def_id, substs))
+        }
+        _ => None,
+    }
+}
+
+/// Returns whether the closure `def_id` is an `async` closure, which gets
+/// lowered to a closure returning `from_generator(<generator>)`. A closure
+/// whose body is just an `async` block is lowered (and printed) the same.
+fn is_async_closure(tcx: TyCtxt<'_, '_, '_>, def_id: DefId) -> bool {
+    let hir_id = match tcx.hir().as_local_hir_id(def_id) {
+        Some(hir_id) => hir_id,
+        None => return false,
+    };
+    match tcx.hir().maybe_body_owned_by_by_hir_id(hir_id) {
+        Some(body_id) => match tcx.hir().body(body_id).value.node {
+            hir::ExprKind::Call(ref callee, _) => {
+                callee.span.is_compiler_desugaring(CompilerDesugaringKind::Async)
+            }
+            _ => false,
+        },
+        None => false,
+    }
+}
+
+/// Returns the `Output` type of the future an `async` closure returns,
+/// i.e. the return type of the generator wrapped by `from_generator`.
+fn async_future_output<'tcx>(tcx: TyCtxt<'_, '_, 'tcx>, future: Ty<'tcx>) -> Option<Ty<'tcx>> {
+    match future.sty {
+        ty::Opaque(_, substs) => substs.types().filter_map(|ty| match ty.sty {
+            ty::Generator(def_id, substs, _) => Some(substs.return_ty(def_id, tcx)),
+            _ => None,
+        }).next(),
+        _ => None,
+    }
+}
+
+/// Returns the wrapped type of a newtype index, i.e. a non-generic tuple
+/// struct with exactly one field, of primitive type (`struct NodeId(u32);`).
+fn newtype_index_inner_ty<'tcx>(
+    tcx: TyCtxt<'_, '_, 'tcx>,
+    def: &'tcx ty::AdtDef,
+    substs: SubstsRef<'tcx>,
+) -> Option<Ty<'tcx>> {
+    if !def.is_struct() || !substs.is_empty() {
+        return None;
+    }
+    let variant = def.non_enum_variant();
+    if variant.ctor_kind != CtorKind::Fn || variant.fields.len() != 1 {
+        return None;
+    }
+    let inner = variant.fields[0].ty(tcx, substs);
+    if inner.is_primitive() {
+        Some(inner)
+    } else {
+        None
+    }
+}
+
+/// The results of the trait system lookups done for printing options like
+/// `derive_hints`, which can be costly, so `FmtPrinter` only does each of
+/// them once (see `PrettyPrinter::with_lookup_cache`).
+#[derive(Default)]
+pub struct LookupCache<'tcx> {
+    trait_names: FxHashMap<DefId, Option<ast::Name>>,
+    core_traits: FxHashMap<&'static str, Option<DefId>>,
+    impls: FxHashMap<(Ty<'tcx>, DefId), bool>,
+    iterator_items: FxHashMap<Ty<'tcx>, Option<Ty<'tcx>>>,
+}
+
+/// Returns whether the printing is done from within a query other than
+/// `analysis` (which only runs the passes, so nothing depends on it), in
+/// which case trait selection and const evaluation aren't done for the
+/// printing options that need them, as they could cause query cycles.
+fn in_nested_query(tcx: TyCtxt<'_, '_, '_>) -> bool {
+    ty::tls::with_related_context(tcx, |icx| match icx.query {
+        Some(ref job) => match job.info.query {
+            Query::analysis(_) => false,
+            _ => true,
+        },
+        None => false,
+    })
+}
+
+/// Returns the name of the trait `def_id`, if no other trait (in any crate)
+/// has the same name, i.e. if it can be referred to by that name alone.
+fn unambiguous_trait_name(
+    tcx: TyCtxt<'_, '_, '_>,
+    cache: &mut LookupCache<'_>,
+    def_id: DefId,
+) -> Option<ast::Name> {
+    *cache.trait_names.entry(def_id).or_insert_with(|| {
+        let name = tcx.item_name(def_id);
+        let ambiguous = tcx.all_traits(LOCAL_CRATE).iter().any(|&other| {
+            other != def_id && tcx.item_name(other) == name
+        });
+        if ambiguous { None } else { Some(name) }
+    })
+}
+
+/// Returns the trait named `name` defined in `core`, if any.
+fn core_trait(
+    tcx: TyCtxt<'_, '_, '_>,
+    cache: &mut LookupCache<'_>,
+    name: &'static str,
+) -> Option<DefId> {
+    *cache.core_traits.entry(name).or_insert_with(|| {
+        tcx.all_traits(LOCAL_CRATE).iter().cloned().find(|&def_id| {
+            &*tcx.original_crate_name(def_id.krate).as_str() == "core" &&
+                &*tcx.item_name(def_id).as_str() == name
+        })
+    })
+}
+
+/// Returns whether `ty` is known to implement the trait `trait_def_id`,
+/// which is never the case if it can't be checked (see `in_nested_query`).
+fn implements_trait(
+    tcx: TyCtxt<'_, '_, 'tcx>,
+    cache: &mut LookupCache<'tcx>,
+    ty: Ty<'tcx>,
+    trait_def_id: DefId,
+) -> bool {
+    if in_nested_query(tcx) {
+        return false;
+    }
+    *cache.impls.entry((ty, trait_def_id)).or_insert_with(|| {
+        let ty = match tcx.lift_to_global(&ty) {
+            Some(ty) if !ty.has_escaping_bound_vars() => ty,
+            _ => return false,
+        };
+        tcx.global_tcx().infer_ctxt().enter(|infcx| {
+            traits::type_known_to_meet_bound_modulo_regions(
+                &infcx,
+                ty::ParamEnv::reveal_all(),
+                ty,
+                trait_def_id,
+                DUMMY_SP,
+            )
+        })
+    })
+}
+
+/// Returns the names of the `DERIVABLE_TRAITS` that `ty` is known to implement.
+fn supported_derives(
+    tcx: TyCtxt<'_, '_, 'tcx>,
+    cache: &mut LookupCache<'tcx>,
+    ty: Ty<'tcx>,
+) -> Vec<&'static str> {
+    DERIVABLE_TRAITS.iter().cloned().filter(|&name| {
+        core_trait(tcx, cache, name).map_or(false, |trait_def_id| {
+            implements_trait(tcx, cache, ty, trait_def_id)
+        })
+    }).collect()
+}
+
+/// Returns `<ty as Iterator>::Item` if `ty` is known to implement `Iterator`,
+/// and its item type can be fully resolved (i.e. normalized).
+fn iterator_item_ty(
+    tcx: TyCtxt<'_, '_, 'tcx>,
+    cache: &mut LookupCache<'tcx>,
+    ty: Ty<'tcx>,
+) -> Option<Ty<'tcx>> {
+    if let Some(&item_ty) = cache.iterator_items.get(&ty) {
+        return item_ty;
+    }
+    let iterator_def_id = core_trait(tcx, cache, "Iterator")?;
+    if !implements_trait(tcx, cache, ty, iterator_def_id) {
+        return None;
+    }
+    let global_ty = tcx.lift_to_global(&ty)?;
+    let gcx = tcx.global_tcx();
+    let item_def_id = gcx.associated_items(iterator_def_id).find(|item| {
+        item.kind == ty::AssocKind::Type && &*item.ident.as_str() == "Item"
+    })?.def_id;
+    let item_ty = gcx.infer_ctxt().enter(|infcx| {
+        let item_ty = gcx.mk_projection(item_def_id, gcx.mk_substs_trait(global_ty, &[]));
+        let item_ty = traits::fully_normalize(
+            &infcx,
+            traits::FulfillmentContext::new(),
+            traits::ObligationCause::dummy(),
+            ty::ParamEnv::reveal_all(),
+            &item_ty,
+        ).ok()?;
+        match item_ty.sty {
+            _ if item_ty.needs_infer() => None,
+            ty::Projection(_) => None,
+            _ => Some(item_ty),
+        }
+    });
+    cache.iterator_items.insert(ty, item_ty);
+    item_ty
+}
+
+/// Returns the parameter names of the fn item `def_id`, with `kw::Invalid`
+/// for the parameters that aren't bound by a plain identifier pattern.
+fn fn_param_names(tcx: TyCtxt<'_, '_, '_>, def_id: DefId) -> Vec<ast::Name> {
+    let hir_id = match tcx.hir().as_local_hir_id(def_id) {
+        Some(hir_id) => hir_id,
+        None => return tcx.fn_arg_names(def_id),
+    };
+    if let Some(body_id) = tcx.hir().maybe_body_owned_by_by_hir_id(hir_id) {
+        return tcx.hir().body(body_id).arguments.iter().map(|arg| {
+            match arg.pat.node {
+                hir::PatKind::Binding(_, _, ident, _) => ident.name,
+                _ => kw::Invalid,
+            }
+        }).collect();
+    }
+    let names = match tcx.hir().get_by_hir_id(hir_id) {
+        hir::Node::TraitItem(&hir::TraitItem {
+            node: hir::TraitItemKind::Method(_, hir::TraitMethod::Required(ref names)),
+            ..
+        }) |
+        hir::Node::ForeignItem(&hir::ForeignItem {
+            node: hir::ForeignItemKind::Fn(_, ref names, _),
+            ..
+        }) => names,
+        _ => return vec![],
+    };
+    names.iter().map(|ident| ident.name).collect()
+}
+
+impl<'tcx> ty::TyS<'tcx> {
+    /// Prints this type with a fresh `FmtPrinter`, with `opts` enabled.
+    pub fn print_with_opts(&'tcx self, tcx: TyCtxt<'_, '_, 'tcx>, opts: PrintOptions) -> String {
+        let mut s = String::new();
+        let _ = FmtPrinterBuilder::new(tcx, Namespace::TypeNS)
+            .options(opts)
+            .build(&mut s)
+            .print_type(self);
+        s
+    }
+}
+
+/// A type alias which `FmtPrinter` can print in place of the types that it
+/// expands to (see `FmtPrinterData::type_aliases`), which are ADTs with some
+/// of their generic arguments fixed, e.g. `std::io::Result<T>`, which is
+/// `Result<T, std::io::Error>`, with the argument at index `1` fixed.
+#[derive(Clone, Debug)]
+pub struct TypeAliasPattern<'tcx> {
+    /// The path to print for the alias, e.g. `"std::io::Result"`.
+    pub name: String,
+    pub adt_def_id: DefId,
+    /// The indices and values of the generic arguments the alias fixes.
+    pub fixed_args: Vec<(usize, Kind<'tcx>)>,
+}
+
+impl TypeAliasPattern<'tcx> {
+    /// Returns whether this alias expands to the ADT `def_id` instantiated
+    /// with `substs`. Every fixed argument has to match exactly, so e.g.
+    /// `Result<T, fmt::Error>` is never printed as `io::Result<T>`.
+    fn matches(&self, def_id: DefId, substs: SubstsRef<'tcx>) -> bool {
+        def_id == self.adt_def_id && self.fixed_args.iter().all(|&(index, arg)| {
+            substs.get(index) == Some(&arg)
+        })
+    }
+
+    /// Returns the arguments of `substs` which aren't fixed by the alias,
+    /// without erased lifetimes, which aren't printed anyway.
+    fn remaining_args(&self, substs: SubstsRef<'tcx>) -> Vec<Kind<'tcx>> {
+        substs.iter().enumerate().filter(|&(index, arg)| {
+            let erased = match arg.unpack() {
+                UnpackedKind::Lifetime(r) => *r == ty::ReErased,
+                _ => false,
+            };
+            !erased && !self.fixed_args.iter().any(|&(fixed, _)| fixed == index)
+        }).map(|(_, &arg)| arg).collect()
+    }
+}
+
+/// Where an `impl Trait` type is written, deciding whether those defined by
+/// an alias (i.e. `existential type Alias: Trait;`) are printed by its name,
+/// or by their bounds, like those written in place, e.g. `impl Trait`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TypePosition {
+    /// E.g. the return type of a function, where `impl Trait` is written
+    /// in place, so aliases are printed by their bounds too.
+    Return,
+    /// E.g. the type of a `let` binding, where `impl Trait` can only be
+    /// written through an alias, so aliases are printed by their names.
+    /// This is the default, with `impl Trait`s written in place (i.e. in
+    /// return position) printed by their bounds, as they have no name.
+    Binding,
+}
+
+/// Provides the names to print for defs and crates, e.g. from a tool's own
+/// name database (for renamed or obfuscated builds), in place of rustc's.
+/// Returning `None` falls back to the name rustc would print.
+pub trait NameResolver {
+    /// Returns the name to print for the path segment of `def_id`.
+    fn def_name(&self, _def_id: DefId) -> Option<String> {
+        None
+    }
+
+    /// Returns the name to print for the crate `cnum`, at the start of paths.
+    fn crate_name(&self, _cnum: CrateNum) -> Option<String> {
+        None
+    }
 }
 
 // HACK(eddyb) boxed to avoid moving around a large struct by-value.
@@ -940,13 +2636,140 @@ pub struct FmtPrinterData<'a, 'gcx, 'tcx, F> {
     empty_path: bool,
     in_value: bool,
 
+    /// Whether to print verbosely, by default if `-Z verbose` is set.
+    verbose: bool,
+    /// Whether to print the `crate::` prefix on local paths, overriding
+    /// `with_crate_prefix` if set (see `FmtPrinterBuilder::print_crate_prefix`).
+    crate_prefix: Option<bool>,
+    region_naming: RegionNaming,
+    /// The names of anonymous late-bound regions generated so far, by index.
+    region_names: Vec<InternedString>,
+
     used_region_names: FxHashSet<InternedString>,
     region_index: usize,
     binder_depth: usize,
+    /// Whether any (non-empty) `for<...>` binder has been printed so far.
+    printed_hrtb: bool,
 
     pub region_highlight_mode: RegionHighlightMode,
 
     pub name_resolver: Option<Box<&'a dyn Fn(ty::sty::TyVid) -> Option<String>>>,
+
+    /// Provides the names of defs and crates, when overriding rustc's own.
+    pub def_name_resolver: Option<Box<&'a dyn NameResolver>>,
+    /// The defs whose paths are being printed, innermost last, so that
+    /// `path_append` knows the def of the segment it's appending.
+    def_id_stack: Vec<DefId>,
+
+    /// If set, paths with more segments (after the crate, or qualified path,
+    /// they start at) than this are abbreviated to their last segments, e.g.
+    /// `std::...::map::HashMap` with `2`. Both the crate and the last segment
+    /// are always printed, and so are impl segments (e.g. `<impl Foo>`).
+    pub max_path_segments: Option<usize>,
+    /// How many segments `path_append` is printing after the current one,
+    /// in the path currently being printed (see `max_path_segments`).
+    path_segments_after: usize,
+    /// The segments omitted so far from the path currently being printed,
+    /// for the `legend` entry of the `...` printed in their place.
+    collapsed_segments: Vec<String>,
+
+    /// If set, type parameters given as generic args are followed by the
+    /// (non-`Sized`) bounds the item they're passed to places on them, e.g.
+    /// `HashSet<T: Eq + Hash>`, the first time each of them is printed, with
+    /// at most this many bounds, followed by `+ ...` if there are more.
+    pub inline_param_bounds: Option<usize>,
+    /// The type parameters already printed with their bounds.
+    params_with_bounds: FxHashSet<ty::ParamTy>,
+
+    /// Provides the regions that the late-bound regions of `for<...>` binders
+    /// must outlive, when the caller knows them, which are then printed after
+    /// the bound regions, e.g. `for<'a, 'b where 'a: 'b + 'c>`.
+    pub bound_region_constraints: Option<Box<&'a dyn Fn(ty::BoundRegion) -> Vec<ty::Region<'tcx>>>>,
+
+    /// Provides the hidden types of opaque types, when the caller knows them.
+    pub hidden_ty_resolver: Option<Box<&'a dyn Fn(DefId, SubstsRef<'tcx>) -> Option<Ty<'tcx>>>>,
+
+    /// Where the types printed are written, see `opaque_position`.
+    pub type_position: TypePosition,
+    /// How many `impl Trait` types are being printed, nested in each other.
+    opaque_depth: usize,
+
+    /// Provides the SCCs region inference variables belong to, printed under
+    /// `region_vids`, when the caller knows the region constraint graph.
+    pub region_vid_scc: Option<Box<&'a dyn Fn(ty::RegionVid) -> Option<usize>>>,
+
+    /// The type to print in place of the `Self` parameter, when it's known
+    /// from the context (e.g. the self type of an impl of the trait).
+    /// Other parameters (even if at index `0`) are always printed as-is.
+    pub self_ty: Option<Ty<'tcx>>,
+
+    /// The types to wrap in `ty_highlight` wherever they're printed, e.g.
+    /// `Vec<{u32}>` for `u32`, to point out the part of a type a diagnostic
+    /// is about. Highlighted types nested in each other get nested markers.
+    pub highlighted_tys: FxHashSet<Ty<'tcx>>,
+    /// The markers wrapped around `highlighted_tys`, `{` and `}` by default.
+    pub ty_highlight: DiffHighlight,
+
+    /// The crates whose items are always printed with their full path,
+    /// e.g. to make their origin obvious (see `prints_full_paths_for`).
+    pub full_path_crates: FxHashSet<CrateNum>,
+
+    /// The traits to list after every ADT, among those it implements, e.g.
+    /// `Vec<u32> /* implements: Send, Sync, Clone */` for `Send`, `Sync`,
+    /// `Clone` and `Copy`. As nothing is known about generic parameters,
+    /// implementations that depend on their bounds are omitted, e.g. `Vec<T>`
+    /// gets no marker at all for `Send`.
+    pub impl_markers: Vec<DefId>,
+
+    /// Type aliases to print in place of the types they expand to, checked in order.
+    pub type_aliases: Vec<TypeAliasPattern<'tcx>>,
+
+    /// If set, generic argument lists that wouldn't fit on the current line
+    /// in this many characters are printed one argument per (indented) line.
+    /// Ignored in single-line mode (see `FmtPrinterBuilder::single_line`).
+    pub max_line_width: Option<usize>,
+    /// Whether to escape newlines as `\n`, so the output is a single line.
+    single_line: bool,
+    /// Whether to print paths in full, see `FmtPrinterBuilder::always_fully_qualified`.
+    always_fully_qualified: bool,
+    /// The optional printing modes in effect, see `FmtPrinterBuilder::options`.
+    options: PrintOptions,
+    /// The number of characters written on the current line so far.
+    column: usize,
+    /// The nesting depth of the generic argument lists printed one per line.
+    indent: usize,
+
+    /// How deeply nested the types (and trait object bounds) currently
+    /// being printed are, to avoid overflowing the stack on pathological
+    /// types, by eliding everything nested more than `type_depth_limit` levels
+    /// (see `elided_types`).
+    type_depth: usize,
+    type_depth_limit: usize,
+
+    legend: Vec<(String, String)>,
+
+    /// The number of bytes written so far.
+    written: usize,
+    /// The byte ranges of the output printed by each `print_def_path`
+    /// call, if requested via `record_def_paths`.
+    def_path_ranges: Option<Vec<(Range<usize>, DefId)>>,
+
+    /// The output of the `print_def_path` calls without substs so far, along
+    /// with the `legend` entries they added, which are reused when printing the
+    /// same path again in the same context (i.e. in a value path or not, at the
+    /// same type depth, which decides what gets elided, and after some other
+    /// path or not), as long as nothing (e.g. region highlighting) could make
+    /// it print differently. Like the rest of the output, it assumes that
+    /// `with_forced_impl_filename_line` and `with_crate_prefix` don't change.
+    def_path_cache: FxHashMap<(DefId, bool, usize, bool), (String, Vec<(String, String)>)>,
+    /// All the output since the outermost `print_def_path` call to be cached.
+    def_path_capture: Option<String>,
+    /// All the `legend` entries added since then, even those already present.
+    def_path_legend_capture: Vec<(String, String)>,
+    /// Where the output goes instead, while measuring it (see `measure`).
+    measure_buffer: Option<String>,
+    /// The trait system lookups done so far for the printing options.
+    lookup_cache: LookupCache<'tcx>,
 }
 
 impl<F> Deref for FmtPrinter<'a, 'gcx, 'tcx, F> {
@@ -962,6 +2785,147 @@ impl<F> DerefMut for FmtPrinter<'_, '_, '_, F> {
     }
 }
 
+/// How `FmtPrinter` names the anonymous late-bound regions of `for<...>`
+/// binders (skipping the names already in use), e.g. `for<'r> fn(&'r u8)`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RegionNaming {
+    /// `'r`, `'s`, `'t0`, `'t1`, etc., the default, which stands out
+    /// from the names that are usually written by hand.
+    Letters,
+    /// `'a`, `'b`, ..., `'z`, `'z1`, `'z2`, etc., as they'd usually be written.
+    Alphabetic,
+    /// `'1`, `'2`, etc., like the regions in borrow checker errors.
+    Numbered,
+    /// `'α`, `'β`, ..., `'ω`, then `'α₁`, `'β₁`, etc., as in type theory papers.
+    Greek,
+    /// No names at all, leaving the regions out (e.g. `fn(&u8)`), which is
+    /// ambiguous when there are several, but reads like elided lifetimes.
+    Anonymous,
+}
+
+/// Configures a `FmtPrinter` up front, with every option defaulting to
+/// what `FmtPrinter::new` would use, e.g. to print verbosely into `s`:
+/// `FmtPrinterBuilder::new(tcx, Namespace::TypeNS).verbose(true).build(&mut s)`.
+pub struct FmtPrinterBuilder<'a, 'gcx, 'tcx> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    ns: Namespace,
+    verbose: bool,
+    crate_prefix: Option<bool>,
+    region_naming: RegionNaming,
+    type_depth_limit: usize,
+    single_line: bool,
+    always_fully_qualified: bool,
+    options: PrintOptions,
+}
+
+impl FmtPrinterBuilder<'a, 'gcx, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>, ns: Namespace) -> Self {
+        FmtPrinterBuilder {
+            tcx,
+            ns,
+            verbose: tcx.sess.verbose(),
+            crate_prefix: None,
+            region_naming: RegionNaming::Letters,
+            type_depth_limit: DEFAULT_TYPE_DEPTH_LIMIT,
+            single_line: false,
+            always_fully_qualified: false,
+            options: PrintOptions::default(),
+        }
+    }
+
+    /// Sets whether to print verbosely, regardless of `-Z verbose`.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Sets how the anonymous late-bound regions of binders are named.
+    pub fn region_naming(mut self, region_naming: RegionNaming) -> Self {
+        self.region_naming = region_naming;
+        self
+    }
+
+    /// Sets how deeply nested types can get, see `FmtPrinter::set_type_depth_limit`.
+    pub fn type_depth_limit(mut self, limit: usize) -> Self {
+        self.type_depth_limit = limit;
+        self
+    }
+
+    /// Sets whether to add the `crate::` prefix to local paths (in Rust 2018),
+    /// regardless of `with_crate_prefix`.
+    pub fn print_crate_prefix(mut self, crate_prefix: bool) -> Self {
+        self.crate_prefix = Some(crate_prefix);
+        self
+    }
+
+    /// Sets whether to guarantee the output is a single line, for log sinks
+    /// that break on newlines, by escaping any newline as `\n` (though the
+    /// contents of string and char consts are escaped regardless). Printers
+    /// in this mode never wrap lines, i.e. ignore `max_line_width`.
+    pub fn single_line(mut self, single_line: bool) -> Self {
+        self.single_line = single_line;
+        self
+    }
+
+    /// Sets whether to print every path in full, from the real name of its crate
+    /// (even for the local crate), ignoring re-exports, and not shortening the
+    /// paths of impls (see `Printer::should_fully_qualify_paths`), nor of traits
+    /// (under `short_qualified_traits`), e.g. for reproducible snapshots.
+    pub fn always_fully_qualified(mut self, always_fully_qualified: bool) -> Self {
+        self.always_fully_qualified = always_fully_qualified;
+        self
+    }
+
+    /// Sets the optional printing modes to use, e.g. `elision_counts`.
+    pub fn options(mut self, options: PrintOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn build<F>(self, fmt: F) -> FmtPrinter<'a, 'gcx, 'tcx, F> {
+        let mut cx = FmtPrinter::new(self.tcx, fmt, self.ns);
+        cx.verbose = self.verbose;
+        cx.crate_prefix = self.crate_prefix;
+        cx.region_naming = self.region_naming;
+        cx.set_type_depth_limit(self.type_depth_limit);
+        cx.single_line = self.single_line;
+        cx.always_fully_qualified = self.always_fully_qualified;
+        cx.options = self.options;
+        cx
+    }
+}
+
+/// How `path_append` prints a path segment under `max_path_segments`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum PathSegmentAbbreviation {
+    /// The segment is printed as-is.
+    Keep,
+    /// The segment is printed as `...`, in place of it and those before it.
+    Collapse,
+    /// The segment isn't printed, as a later segment prints `...` for it.
+    Omit,
+}
+
+/// Returns how to print a path segment followed by `segments_after` other
+/// segments, to keep only the last `max_path_segments` (and at least one).
+fn abbreviate_path_segment(
+    segments_after: usize,
+    max_path_segments: usize,
+) -> PathSegmentAbbreviation {
+    let max_path_segments = max_path_segments.max(1);
+    if segments_after < max_path_segments {
+        PathSegmentAbbreviation::Keep
+    } else if segments_after == max_path_segments {
+        PathSegmentAbbreviation::Collapse
+    } else {
+        PathSegmentAbbreviation::Omit
+    }
+}
+
+/// The default `FmtPrinter::set_type_depth_limit`, which is well beyond
+/// the depth of types written by hand, but nowhere near overflowing the stack.
+pub const DEFAULT_TYPE_DEPTH_LIMIT: usize = 512;
+
 impl<F> FmtPrinter<'a, 'gcx, 'tcx, F> {
     pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>, fmt: F, ns: Namespace) -> Self {
         FmtPrinter(Box::new(FmtPrinterData {
@@ -969,16 +2933,114 @@ impl<F> FmtPrinter<'a, 'gcx, 'tcx, F> {
             fmt,
             empty_path: false,
             in_value: ns == Namespace::ValueNS,
+            verbose: tcx.sess.verbose(),
+            crate_prefix: None,
+            region_naming: RegionNaming::Letters,
+            region_names: vec![],
             used_region_names: Default::default(),
             region_index: 0,
             binder_depth: 0,
+            printed_hrtb: false,
             region_highlight_mode: RegionHighlightMode::default(),
             name_resolver: None,
+            def_name_resolver: None,
+            def_id_stack: vec![],
+            max_path_segments: None,
+            path_segments_after: 0,
+            collapsed_segments: vec![],
+            inline_param_bounds: None,
+            params_with_bounds: Default::default(),
+            bound_region_constraints: None,
+            hidden_ty_resolver: None,
+            type_position: TypePosition::Binding,
+            opaque_depth: 0,
+            region_vid_scc: None,
+            self_ty: None,
+            highlighted_tys: Default::default(),
+            ty_highlight: DiffHighlight { start: "{", end: "}" },
+            full_path_crates: Default::default(),
+            impl_markers: vec![],
+            type_aliases: vec![],
+            max_line_width: None,
+            single_line: false,
+            always_fully_qualified: false,
+            options: PrintOptions::default(),
+            column: 0,
+            indent: 0,
+            type_depth: 0,
+            type_depth_limit: DEFAULT_TYPE_DEPTH_LIMIT,
+            legend: vec![],
+            written: 0,
+            def_path_ranges: None,
+            def_path_cache: Default::default(),
+            def_path_capture: None,
+            def_path_legend_capture: vec![],
+            measure_buffer: None,
+            lookup_cache: Default::default(),
         }))
     }
+
+    /// Sets how deeply nested types can get before the remaining subtypes are
+    /// elided (see `elision_marker`), e.g. `Vec<Vec<...>>` with a limit of 2,
+    /// apart from those without any components (e.g. `Vec<Vec<u8>>` is kept).
+    pub fn set_type_depth_limit(&mut self, limit: usize) {
+        self.type_depth_limit = limit;
+    }
+
+    /// Returns the abbreviations made so far, as pairs of the short form
+    /// that was printed and the full form it stands for, in the order
+    /// in which they were first printed, e.g. `("...", "collections::hash")`
+    /// for a path collapsed under `max_path_segments`, or an `elision_marker`
+    /// and the types it elided. Full forms are printed with the default
+    /// options, so they aren't abbreviated themselves, and nested
+    /// abbreviations each get their own entry.
+    pub fn legend(&self) -> &[(String, String)] {
+        &self.legend
+    }
+
+    /// Returns the names generated so far for anonymous late-bound regions, by
+    /// index, each of which was only interned once, no matter how many times
+    /// (or in how many binders) it was printed.
+    pub fn region_names(&self) -> &[InternedString] {
+        &self.region_names
+    }
+
+    /// Returns whether anything printed so far contained higher-ranked regions,
+    /// i.e. whether any `for<...>` binder was printed (no matter how many,
+    /// or how deeply nested), e.g. for diagnostics specific to HRTBs.
+    pub fn printed_hrtb(&self) -> bool {
+        self.printed_hrtb
+    }
+
+    /// Records that `short` was printed in place of `full`, for `legend`.
+    pub fn add_to_legend(&mut self, short: String, full: String) {
+        if self.def_path_capture.is_some() {
+            self.def_path_legend_capture.push((short.clone(), full.clone()));
+        }
+        if !self.legend.iter().any(|(s, f)| *s == short && *f == full) {
+            self.legend.push((short, full));
+        }
+    }
+
+    /// Starts recording the byte range of the output of every `print_def_path`
+    /// call (including those for the parents of the paths being printed).
+    pub fn record_def_paths(&mut self) {
+        self.def_path_ranges.get_or_insert_with(Vec::new);
+    }
+
+    /// Returns the byte ranges recorded so far (see `record_def_paths`),
+    /// in the order their printing finished, so inner paths come first.
+    pub fn def_path_ranges(&self) -> &[(Range<usize>, DefId)] {
+        self.def_path_ranges.as_ref().map_or(&[], |ranges| &ranges[..])
+    }
+
+    /// Returns the underlying output, consuming the printer.
+    pub fn into_fmt(self) -> F {
+        self.0.fmt
+    }
 }
 
-impl TyCtxt<'_, '_, '_> {
+impl TyCtxt<'_, '_, 'tcx> {
     // HACK(eddyb) get rid of `def_path_str` and/or pass `Namespace` explicitly always
     // (but also some things just print a `DefId` generally so maybe we need this?)
     fn guess_def_namespace(self, def_id: DefId) -> Namespace {
@@ -1008,35 +3070,180 @@ impl TyCtxt<'_, '_, '_> {
             .print_def_path(def_id, &[]);
         s
     }
+
+    /// Returns the fully qualified paths of the candidate methods of an
+    /// ambiguous method call, deduplicated, for the user to pick from.
+    /// Inherent methods are printed as `Type::method` and listed first, followed
+    /// by trait methods, printed as `<Type as Trait>::method`, each sorted by path.
+    pub fn method_candidate_paths(
+        self,
+        candidates: &[(DefId, SubstsRef<'tcx>)],
+    ) -> Vec<String> {
+        let mut paths: Vec<_> = candidates.iter().map(|&(def_id, substs)| {
+            let is_inherent = self.impl_of_method(def_id).map_or(false, |impl_def_id| {
+                self.trait_id_of_impl(impl_def_id).is_none()
+            });
+            let mut s = String::new();
+            let _ = FmtPrinter::new(self, &mut s, Namespace::ValueNS)
+                .print_def_path(def_id, substs);
+            (!is_inherent, s)
+        }).collect();
+        paths.sort();
+        paths.dedup();
+        paths.into_iter().map(|(_, path)| path).collect()
+    }
+}
+
+/// Returns `ty` printed by a default `FmtPrinter`, the same as its `{}`
+/// output, but without needing `ty` to be lifted to the global `tcx`.
+pub fn ty_to_string<'tcx>(tcx: TyCtxt<'_, '_, 'tcx>, ty: Ty<'tcx>) -> String {
+    let mut s = String::new();
+    let _ = FmtPrinter::new(tcx, &mut s, Namespace::TypeNS).print_type(ty);
+    s
+}
+
+/// Returns the path of `def_id`, instantiated with `substs`, printed by a
+/// default `FmtPrinter`, like `def_path_str` (which has no `substs`).
+pub fn def_path_to_string<'tcx>(
+    tcx: TyCtxt<'_, '_, 'tcx>,
+    def_id: DefId,
+    substs: &'tcx [Kind<'tcx>],
+) -> String {
+    let ns = tcx.guess_def_namespace(def_id);
+    let mut s = String::new();
+    let _ = FmtPrinter::new(tcx, &mut s, ns).print_def_path(def_id, substs);
+    s
 }
 
 impl<F: fmt::Write> fmt::Write for FmtPrinter<'_, '_, '_, F> {
     fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.single_line && s.contains('\n') {
+            return self.write_str(&s.replace('\n', "\\n"));
+        }
+        if let Some(ref mut buffer) = self.measure_buffer {
+            buffer.push_str(s);
+            return Ok(());
+        }
+        self.written += s.len();
+        if let Some(ref mut capture) = self.def_path_capture {
+            capture.push_str(s);
+        }
+        match s.rfind('\n') {
+            Some(i) => self.column = s[i + 1..].chars().count(),
+            None => self.column += s.chars().count(),
+        }
         self.fmt.write_str(s)
     }
 }
 
-impl<F: fmt::Write> Printer<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F> {
-    type Error = fmt::Error;
+impl<F: fmt::Write> FmtPrinter<'_, 'gcx, 'tcx, F> {
+    /// Returns `max_line_width`, unless in single-line mode, which never wraps lines.
+    fn line_width_limit(&self) -> Option<usize> {
+        if self.single_line {
+            return None;
+        }
+        self.max_line_width
+    }
 
-    type Path = Self;
-    type Region = Self;
-    type Type = Self;
-    type DynExistential = Self;
-    type Const = Self;
+    /// Returns how wide what `f` prints is, on a single line, measured by printing
+    /// it with this printer (so with the same settings) into a separate buffer,
+    /// then undoing the changes printing it made to the state of the printer.
+    fn measure(
+        mut self,
+        f: impl FnOnce(Self) -> Result<Self, fmt::Error>,
+    ) -> Result<(Self, usize), fmt::Error> {
+        let max_line_width = self.max_line_width.take();
+        let empty_path = self.empty_path;
+        let region_index = self.region_index;
+        let printed_hrtb = self.printed_hrtb;
+        let used_region_names = self.used_region_names.clone();
+        let params_with_bounds = self.params_with_bounds.clone();
+        let legend_len = self.legend.len();
+        let def_path_ranges_len = self.def_path_ranges.as_ref().map(|ranges| ranges.len());
+        let outer_buffer = self.measure_buffer.replace(String::new());
+
+        let mut cx = f(self)?;
+        let width = cx.measure_buffer.take().map_or(0, |buffer| buffer.chars().count());
+        cx.measure_buffer = outer_buffer;
+        cx.max_line_width = max_line_width;
+        cx.empty_path = empty_path;
+        cx.region_index = region_index;
+        cx.printed_hrtb = printed_hrtb;
+        cx.used_region_names = used_region_names;
+        cx.params_with_bounds = params_with_bounds;
+        cx.legend.truncate(legend_len);
+        if let (Some(ranges), Some(len)) = (cx.def_path_ranges.as_mut(), def_path_ranges_len) {
+            ranges.truncate(len);
+        }
+        Ok((cx, width))
+    }
 
-    fn tcx(&'a self) -> TyCtxt<'a, 'gcx, 'tcx> {
-        self.tcx
+    /// Prints the trait object type `ty`, with bounds `predicates` and `r`,
+    /// putting every bound after the principal trait on its own line (with
+    /// the bounds aligned), if it wouldn't otherwise fit in `max_line_width`.
+    fn pretty_print_dyn_wrapped(
+        mut self,
+        ty: Ty<'tcx>,
+        predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
+        r: ty::Region<'tcx>,
+        max_line_width: usize,
+    ) -> Result<Self, fmt::Error> {
+        let (cx, width) = self.measure(|cx| cx.pretty_print_type(ty))?;
+        self = cx;
+        if self.column + width <= max_line_width {
+            return self.pretty_print_type(ty);
+        }
+
+        let print_r = self.region_should_not_be_omitted(r);
+        if print_r {
+            write!(self, "(")?;
+        }
+        write!(self, "dyn ")?;
+        let continuation = " ".repeat(self.column - "+ ".len());
+
+        // Print the principal trait (along with its projections) first, then
+        // the auto traits (sorted like `pretty_print_dyn_existential` does).
+        let (principal, auto_traits): (Vec<_>, Vec<_>) = predicates.iter().partition(|p| {
+            match p {
+                ty::ExistentialPredicate::AutoTrait(_) => false,
+                _ => true,
+            }
+        });
+        let mut auto_traits: Vec<_> = auto_traits.into_iter().map(|p| match p {
+            ty::ExistentialPredicate::AutoTrait(did) => (self.tcx.def_path_str(*did), *did),
+            _ => bug!(),
+        }).collect();
+        auto_traits.sort();
+
+        let mut first = true;
+        if !principal.is_empty() {
+            let principal = self.tcx.mk_existential_predicates(principal.into_iter().cloned());
+            self = self.pretty_print_dyn_existential(principal)?;
+            first = false;
+        }
+        for (_, def_id) in auto_traits {
+            if !first {
+                write!(self, "\n{}+ ", continuation)?;
+            }
+            first = false;
+            self = self.print_def_path(def_id, &[])?;
+        }
+        if print_r {
+            write!(self, "\n{}+ ", continuation)?;
+            self = self.print_region(r)?;
+            write!(self, ")")?;
+        }
+        Ok(self)
     }
 
-    fn print_def_path(
+    fn print_def_path_unrecorded(
         mut self,
         def_id: DefId,
         substs: &'tcx [Kind<'tcx>],
-    ) -> Result<Self::Path, Self::Error> {
+    ) -> Result<Self, fmt::Error> {
         define_scoped_cx!(self);
 
-        if substs.is_empty() {
+        if substs.is_empty() && !self.always_fully_qualified {
             match self.try_print_visible_def_path(def_id)? {
                 (cx, true) => return Ok(cx),
                 (cx, false) => self = cx,
@@ -1061,21 +3268,128 @@ impl<F: fmt::Write> Printer<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F> {
                 let parent_def_id = DefId { index: key.parent.unwrap(), ..def_id };
                 let span = self.tcx.def_span(def_id);
 
-                self = self.print_def_path(parent_def_id, &[])?;
+                self = self.print_def_path(parent_def_id, &[])?;
+
+                // HACK(eddyb) copy of `path_append` to avoid
+                // constructing a `DisambiguatedDefPathData`.
+                if !self.empty_path {
+                    write!(self, "::")?;
+                }
+                write!(self, "<impl at {:?}>", span)?;
+                self.empty_path = false;
+
+                return Ok(self);
+            }
+        }
+
+        self.default_print_def_path(def_id, substs)
+    }
+
+}
+
+impl<F: fmt::Write> Printer<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F> {
+    type Error = fmt::Error;
+
+    type Path = Self;
+    type Region = Self;
+    type Type = Self;
+    type DynExistential = Self;
+    type Const = Self;
+
+    fn tcx(&'a self) -> TyCtxt<'a, 'gcx, 'tcx> {
+        self.tcx
+    }
+
+    fn should_print_verbose(&self) -> bool {
+        self.verbose
+    }
+
+    fn should_fully_qualify_paths(&self) -> bool {
+        self.always_fully_qualified
+    }
+
+    fn in_value_path(&self) -> bool {
+        self.in_value
+    }
 
-                // HACK(eddyb) copy of `path_append` to avoid
-                // constructing a `DisambiguatedDefPathData`.
-                if !self.empty_path {
-                    write!(self, "::")?;
-                }
-                write!(self, "<impl at {:?}>", span)?;
-                self.empty_path = false;
+    fn print_options(&self) -> PrintOptions {
+        self.options
+    }
+
+    fn set_print_options(&mut self, options: PrintOptions) {
+        self.options = options;
+    }
 
+    fn print_def_path(
+        mut self,
+        def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        let cache_key = (def_id, self.in_value, self.type_depth, self.empty_path);
+        let cacheable = substs.is_empty() && !self.verbose &&
+            self.region_highlight_mode.is_empty() && self.def_path_ranges.is_none() &&
+            self.max_path_segments.is_none() && self.measure_buffer.is_none();
+        if cacheable {
+            if let Some((path, legend)) = self.def_path_cache.get(&cache_key).cloned() {
+                self.write_str(&path)?;
+                for (short, full) in legend {
+                    self.add_to_legend(short, full);
+                }
+                self.empty_path = path.is_empty();
+                self.write_object_safety_marker(def_id)?;
                 return Ok(self);
             }
         }
+        let outermost_capture = cacheable && self.def_path_capture.is_none();
+        if outermost_capture {
+            self.def_path_capture = Some(String::new());
+        }
+        let capture_start = self.def_path_capture.as_ref().map_or(0, |capture| capture.len());
+        let legend_capture_start = self.def_path_legend_capture.len();
+
+        let start = self.written;
+        self.def_id_stack.push(def_id);
+        let mut cx = self.print_def_path_unrecorded(def_id, substs)?;
+        cx.def_id_stack.pop();
+
+        if cx.options.variant_discriminants {
+            if let Some(DefKind::Variant) = cx.tcx.def_kind(def_id) {
+                let adt_def = cx.tcx.adt_def(cx.tcx.parent(def_id).unwrap());
+                let discr = adt_def.discriminant_for_variant(
+                    cx.tcx,
+                    adt_def.variant_index_with_id(def_id),
+                );
+                write!(cx, " = {}", discr)?;
+            }
+        }
 
-        self.default_print_def_path(def_id, substs)
+        let end = cx.written;
+        if let Some(ref mut ranges) = cx.def_path_ranges {
+            ranges.push((start..end, def_id));
+        }
+
+        if cacheable {
+            let path = cx.def_path_capture.as_ref().unwrap()[capture_start..].to_string();
+            let legend = cx.def_path_legend_capture[legend_capture_start..].to_vec();
+            cx.def_path_cache.insert(cache_key, (path, legend));
+            if outermost_capture {
+                cx.def_path_capture = None;
+                cx.def_path_legend_capture.clear();
+            }
+        }
+        cx.write_object_safety_marker(def_id)?;
+        Ok(cx)
+    }
+    fn print_value_path(
+        mut self,
+        def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        let was_in_value = std::mem::replace(&mut self.in_value, true);
+        self = self.print_def_path(def_id, substs)?;
+        self.in_value = was_in_value;
+
+        Ok(self)
     }
 
     fn print_region(
@@ -1086,17 +3400,98 @@ impl<F: fmt::Write> Printer<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F> {
     }
 
     fn print_type(
-        self,
+        mut self,
         ty: Ty<'tcx>,
     ) -> Result<Self::Type, Self::Error> {
-        self.pretty_print_type(ty)
+        if let Some(count) = self.elided_types(ty) {
+            let marker = self.options.elision_marker(count, "type");
+            write!(self, "{}", marker)?;
+            self.note_elided_types(&marker, &[ty]);
+            return Ok(self);
+        }
+        self.type_depth += 1;
+
+        let is_opaque = match ty.sty {
+            ty::Opaque(..) => true,
+            _ => false,
+        };
+        if is_opaque {
+            self.opaque_depth += 1;
+        }
+
+        let markers = self.ty_highlight;
+        let highlighted = self.highlighted_tys.contains(&ty);
+        if highlighted {
+            write!(self, "{}", markers.start)?;
+        }
+
+        let mut cx = match (self.self_ty, &ty.sty, self.line_width_limit()) {
+            (Some(self_ty), ..) if ty.is_self() => self.pretty_print_type(self_ty)?,
+            (_, &ty::Dynamic(data, r), Some(max_line_width)) => {
+                self.pretty_print_dyn_wrapped(ty, *data.skip_binder(), r, max_line_width)?
+            }
+            _ => self.pretty_print_type(ty)?,
+        };
+
+        if cx.options.unsized_markers &&
+            !ty.needs_subst() && !ty.has_escaping_bound_vars() {
+            if let Some(ty) = cx.tcx.lift_to_global(&ty) {
+                let gcx = cx.tcx.global_tcx();
+                if !ty.is_sized(gcx.at(DUMMY_SP), ty::ParamEnv::reveal_all()) {
+                    write!(cx, " /* unsized */")?;
+                }
+            }
+        }
+
+        if let ty::Adt(..) = ty.sty {
+            if !cx.impl_markers.is_empty() {
+                let tcx = cx.tcx;
+                let cache = &mut cx.0.lookup_cache;
+                let implemented: Vec<_> = cx.0.impl_markers.iter().filter(|&&trait_def_id| {
+                    implements_trait(tcx, cache, ty, trait_def_id)
+                }).map(|&trait_def_id| tcx.item_name(trait_def_id).to_string()).collect();
+                if !implemented.is_empty() {
+                    write!(cx, " /* implements: {} */", implemented.join(", "))?;
+                }
+            }
+        }
+
+        if highlighted {
+            write!(cx, "{}", markers.end)?;
+        }
+        if is_opaque {
+            cx.opaque_depth -= 1;
+        }
+        cx.type_depth -= 1;
+        Ok(cx)
+    }
+
+    fn print_fn_sig(
+        self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        self.pretty_print_fn_sig(sig)
     }
 
     fn print_dyn_existential(
-        self,
+        mut self,
         predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
     ) -> Result<Self::DynExistential, Self::Error> {
-        self.pretty_print_dyn_existential(predicates)
+        if self.type_depth >= self.type_depth_limit {
+            let marker = self.options.elision_marker(predicates.len(), "bound");
+            write!(self, "{}", marker)?;
+            let mut full = String::new();
+            let _ = FmtPrinterBuilder::new(self.tcx, Namespace::TypeNS)
+                .options(self.options)
+                .build(&mut full)
+                .print_dyn_existential(predicates);
+            self.add_to_legend(marker, full);
+            return Ok(self);
+        }
+        self.type_depth += 1;
+        let mut cx = self.pretty_print_dyn_existential(predicates)?;
+        cx.type_depth -= 1;
+        Ok(cx)
     }
 
     fn print_const(
@@ -1111,16 +3506,22 @@ impl<F: fmt::Write> Printer<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F> {
         cnum: CrateNum,
     ) -> Result<Self::Path, Self::Error> {
         self.empty_path = true;
-        if cnum == LOCAL_CRATE {
+        if cnum == LOCAL_CRATE && !self.always_fully_qualified {
             if self.tcx.sess.rust_2018() {
                 // We add the `crate::` keyword on Rust 2018, only when desired.
-                if SHOULD_PREFIX_WITH_CRATE.with(|flag| flag.get()) {
+                let crate_prefix = self.crate_prefix.unwrap_or_else(|| {
+                    SHOULD_PREFIX_WITH_CRATE.with(|flag| flag.get())
+                });
+                if crate_prefix {
                     write!(self, "{}", kw::Crate)?;
                     self.empty_path = false;
                 }
             }
         } else {
-            write!(self, "{}", self.tcx.crate_name(cnum))?;
+            let resolved = self.def_name_resolver.as_ref().and_then(|r| r.crate_name(cnum));
+            let name = resolved.unwrap_or_else(|| self.tcx.crate_name(cnum).to_string());
+            let sanitize = self.options.sanitized_idents;
+            write_ident(&mut self, &name, sanitize)?;
             self.empty_path = false;
         }
         Ok(self)
@@ -1138,11 +3539,13 @@ impl<F: fmt::Write> Printer<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F> {
     fn path_append_impl(
         mut self,
         print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
-        _disambiguated_data: &DisambiguatedDefPathData,
+        disambiguated_data: &DisambiguatedDefPathData,
         self_ty: Ty<'tcx>,
         trait_ref: Option<ty::TraitRef<'tcx>>,
     ) -> Result<Self::Path, Self::Error> {
+        let segments_after = self.path_segments_after;
         self = self.pretty_path_append_impl(|mut cx| {
+            cx.path_segments_after = segments_after + 1;
             cx = print_prefix(cx)?;
             if !cx.empty_path {
                 write!(cx, "::")?;
@@ -1150,6 +3553,8 @@ impl<F: fmt::Write> Printer<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F> {
 
             Ok(cx)
         }, self_ty, trait_ref)?;
+        self.path_segments_after = segments_after;
+        self.write_def_id_suffix(disambiguated_data)?;
         self.empty_path = false;
         Ok(self)
     }
@@ -1158,7 +3563,12 @@ impl<F: fmt::Write> Printer<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F> {
         print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
         disambiguated_data: &DisambiguatedDefPathData,
     ) -> Result<Self::Path, Self::Error> {
+        let segments_after = self.path_segments_after;
+        if disambiguated_data.data != DefPathData::Ctor {
+            self.path_segments_after += 1;
+        }
         self = print_prefix(self)?;
+        self.path_segments_after = segments_after;
 
         // Skip `::{{constructor}}` on tuple/unit structs.
         match disambiguated_data.data {
@@ -1170,20 +3580,41 @@ impl<F: fmt::Write> Printer<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F> {
         // currently is for `extern { ... }` "foreign modules".
         let name = disambiguated_data.data.as_interned_str().as_str();
         if !name.is_empty() {
+            let abbreviation = self.max_path_segments.map_or(PathSegmentAbbreviation::Keep, |max| {
+                abbreviate_path_segment(segments_after, max)
+            });
+            if abbreviation == PathSegmentAbbreviation::Omit {
+                self.collapsed_segments.push(name.to_string());
+                return Ok(self);
+            }
             if !self.empty_path {
                 write!(self, "::")?;
             }
-            write!(self, "{}", name)?;
+            if abbreviation == PathSegmentAbbreviation::Collapse {
+                write!(self, "...")?;
+                self.empty_path = false;
+                self.collapsed_segments.push(name.to_string());
+                let full = std::mem::replace(&mut self.collapsed_segments, vec![]).join("::");
+                self.add_to_legend("...".to_string(), full);
+                return Ok(self);
+            }
+            let resolved = match (&self.def_name_resolver, self.def_id_stack.last()) {
+                (Some(resolver), Some(&def_id)) => resolver.def_name(def_id),
+                _ => None,
+            };
+            let sanitize = self.options.sanitized_idents;
+            write_ident(&mut self, resolved.as_ref().map_or(&*name, |s| &s[..]), sanitize)?;
 
             // FIXME(eddyb) this will print e.g. `{{closure}}#3`, but it
             // might be nicer to use something else, e.g. `{closure#3}`.
             let dis = disambiguated_data.disambiguator;
             let print_dis =
                 disambiguated_data.data.get_opt_name().is_none() ||
-                dis != 0 && self.tcx.sess.verbose();
+                dis != 0 && self.verbose;
             if print_dis {
                 write!(self, "#{}", dis)?;
             }
+            self.write_def_id_suffix(disambiguated_data)?;
 
             self.empty_path = false;
         }
@@ -1197,13 +3628,21 @@ impl<F: fmt::Write> Printer<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F> {
     ) -> Result<Self::Path, Self::Error> {
         self = print_prefix(self)?;
 
-        // Don't print `'_` if there's no unerased regions.
+        // Don't print `'_` if there's no unerased regions, unless the
+        // args are all lifetimes, and those are to be shown regardless.
+        let lifetimes_only = !args.is_empty() && args.iter().all(|arg| {
+            match arg.unpack() {
+                UnpackedKind::Lifetime(_) => true,
+                _ => false,
+            }
+        });
         let print_regions = args.iter().any(|arg| {
             match arg.unpack() {
                 UnpackedKind::Lifetime(r) => *r != ty::ReErased,
                 _ => false,
             }
-        });
+        }) || lifetimes_only && self.options.lifetime_only_args;
+        let bounds = self.inline_bounds(args);
         let args = args.iter().cloned().filter(|arg| {
             match arg.unpack() {
                 UnpackedKind::Lifetime(_) => print_regions,
@@ -1215,7 +3654,46 @@ impl<F: fmt::Write> Printer<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F> {
             if self.in_value {
                 write!(self, "::")?;
             }
-            self.generic_delimiters(|cx| cx.comma_sep(args))
+
+            let print_single_line = |mut cx: Self| {
+                if bounds.is_empty() {
+                    return cx.comma_sep_eliding(args.clone(), |_| String::new());
+                }
+                for (i, arg) in args.clone().enumerate() {
+                    if i > 0 {
+                        write!(cx, ", ")?;
+                    }
+                    cx = cx.print_arg_with_inline_bounds(arg, &bounds)?;
+                }
+                Ok(cx)
+            };
+
+            if let Some(max_line_width) = self.line_width_limit() {
+                // Print the arguments on their own first, to measure them.
+                let (cx, args_width) = self.measure(&print_single_line)?;
+                self = cx;
+                let width = self.column + "<>".len() + args_width;
+                if width > max_line_width {
+                    return self.generic_delimiters(|mut cx| {
+                        cx.indent += 1;
+                        for arg in args {
+                            write!(cx, "\n{}", "    ".repeat(cx.indent))?;
+                            cx = cx.print_arg_with_inline_bounds(arg, &bounds)?;
+                            write!(cx, ",")?;
+                        }
+                        cx.indent -= 1;
+                        write!(cx, "\n{}", "    ".repeat(cx.indent))?;
+                        Ok(cx)
+                    });
+                }
+            }
+
+            self.generic_delimiters(print_single_line)
+        } else if self.options.empty_generic_brackets {
+            if self.in_value {
+                write!(self, "::")?;
+            }
+            self.generic_delimiters(|cx| Ok(cx))
         } else {
             Ok(self)
         }
@@ -1227,16 +3705,76 @@ impl<F: fmt::Write> PrettyPrinter<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F>
         self.0.name_resolver.as_ref().and_then(|func| func(id))
     }
 
-    fn print_value_path(
+    fn elided_types(&self, ty: Ty<'tcx>) -> Option<usize> {
+        // Types without any components (e.g. `u8`) don't nest any deeper,
+        // and a marker wouldn't be any shorter, so they're always printed.
+        let count = ty.walk().count();
+        if self.type_depth >= self.type_depth_limit && count > 1 {
+            Some(count)
+        } else {
+            None
+        }
+    }
+
+    fn note_elided_types(&mut self, marker: &str, tys: &[Ty<'tcx>]) {
+        let (tcx, options) = (self.tcx, self.options);
+        let full: Vec<_> = tys.iter().map(|&ty| {
+            let mut s = String::new();
+            let _ = FmtPrinterBuilder::new(tcx, Namespace::TypeNS)
+                .options(options)
+                .build(&mut s)
+                .print_type(ty);
+            s
+        }).collect();
+        self.add_to_legend(marker.to_string(), full.join(", "));
+    }
+
+    fn with_lookup_cache<R>(
+        &mut self,
+        f: impl FnOnce(TyCtxt<'_, 'gcx, 'tcx>, &mut LookupCache<'tcx>) -> R,
+    ) -> R {
+        f(self.tcx, &mut self.lookup_cache)
+    }
+
+    fn prints_full_paths_for(&self, cnum: CrateNum) -> bool {
+        self.full_path_crates.contains(&cnum)
+    }
+
+    fn path_append_visible(
         mut self,
         def_id: DefId,
-        substs: &'tcx [Kind<'tcx>],
+        disambiguated_data: &DisambiguatedDefPathData,
     ) -> Result<Self::Path, Self::Error> {
-        let was_in_value = std::mem::replace(&mut self.in_value, true);
-        self = self.print_def_path(def_id, substs)?;
-        self.in_value = was_in_value;
+        // Make `path_append` see the def of the segment (e.g. for the
+        // `def_name_resolver`), not the one whose visible path this is.
+        self.def_id_stack.push(def_id);
+        let mut cx = self.path_append(Ok, disambiguated_data)?;
+        cx.def_id_stack.pop();
+        Ok(cx)
+    }
 
-        Ok(self)
+    fn opaque_hidden_ty(&self, def_id: DefId, substs: SubstsRef<'tcx>) -> Option<Ty<'tcx>> {
+        self.0.hidden_ty_resolver.as_ref().and_then(|func| func(def_id, substs))
+    }
+
+    fn opaque_position(&self) -> TypePosition {
+        // Only the outermost `impl Trait` is in `type_position`, those nested
+        // in it (e.g. in its bounds) are written in place, like the bounds.
+        if self.opaque_depth > 1 {
+            TypePosition::Return
+        } else {
+            self.type_position
+        }
+    }
+
+    fn collapse_type_alias(
+        &self,
+        def_id: DefId,
+        substs: SubstsRef<'tcx>,
+    ) -> Option<(String, Vec<Kind<'tcx>>)> {
+        self.type_aliases.iter()
+            .find(|alias| alias.matches(def_id, substs))
+            .map(|alias| (alias.name.clone(), alias.remaining_args(substs)))
     }
 
     fn in_binder<T>(
@@ -1255,8 +3793,13 @@ impl<F: fmt::Write> PrettyPrinter<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F>
         write!(self, "<")?;
 
         let was_in_value = std::mem::replace(&mut self.in_value, false);
+        // The paths in `<...>` are separate from the one they're part of.
+        let segments_after = std::mem::replace(&mut self.path_segments_after, 0);
+        let collapsed_segments = std::mem::replace(&mut self.collapsed_segments, vec![]);
         let mut inner = f(self)?;
         inner.in_value = was_in_value;
+        inner.path_segments_after = segments_after;
+        inner.collapsed_segments = collapsed_segments;
 
         write!(inner, ">")?;
         Ok(inner)
@@ -1271,7 +3814,12 @@ impl<F: fmt::Write> PrettyPrinter<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F>
             return true;
         }
 
-        if self.tcx.sess.verbose() {
+        if self.verbose {
+            return true;
+        }
+
+        if self.options.descriptive_regions &&
+            region_description(region).is_some() {
             return true;
         }
 
@@ -1302,6 +3850,8 @@ impl<F: fmt::Write> PrettyPrinter<'gcx, 'tcx> for FmtPrinter<'_, 'gcx, 'tcx, F>
                 false
             }
 
+            ty::ReVar(_) if self.options.region_vids => true,
+
             ty::ReScope(_) |
             ty::ReVar(_) if identify_regions => true,
 
@@ -1331,7 +3881,7 @@ impl<F: fmt::Write> FmtPrinter<'_, '_, '_, F> {
             return Ok(self);
         }
 
-        if self.tcx.sess.verbose() {
+        if self.verbose {
             p!(write("{:?}", region));
             return Ok(self);
         }
@@ -1345,7 +3895,10 @@ impl<F: fmt::Write> FmtPrinter<'_, '_, '_, F> {
         match *region {
             ty::ReEarlyBound(ref data) => {
                 if data.name.as_symbol() != kw::Invalid {
-                    p!(write("{}", data.name));
+                    p!(print_ident(&data.name.as_str()));
+                    if self.options.param_indices {
+                        p!(write("#{}", data.index));
+                    }
                     return Ok(self);
                 }
             }
@@ -1355,7 +3908,7 @@ impl<F: fmt::Write> FmtPrinter<'_, '_, '_, F> {
                 if let ty::BrNamed(_, name) = br {
                     if name.as_symbol() != kw::Invalid &&
                        name.as_symbol() != kw::UnderscoreLifetime {
-                        p!(write("{}", name));
+                        p!(print_ident(&name.as_str()));
                         return Ok(self);
                     }
                 }
@@ -1385,6 +3938,13 @@ impl<F: fmt::Write> FmtPrinter<'_, '_, '_, F> {
                 }
                 return Ok(self);
             }
+            ty::ReVar(region_vid) if self.options.region_vids => {
+                p!(write("'?{}", region_vid.index()));
+                if let Some(scc) = self.region_vid_scc.as_ref().and_then(|func| func(region_vid)) {
+                    p!(write("(scc{})", scc));
+                }
+                return Ok(self);
+            }
             ty::ReVar(region_vid) if identify_regions => {
                 p!(write("{:?}", region_vid));
                 return Ok(self);
@@ -1408,12 +3968,68 @@ impl<F: fmt::Write> FmtPrinter<'_, '_, '_, F> {
             }
         }
 
+        if self.options.descriptive_regions {
+            if let Some(description) = region_description(region) {
+                p!(write("'<{}>", description));
+                return Ok(self);
+            }
+        }
+
         p!(write("'_"));
 
         Ok(self)
     }
 }
 
+/// Returns a short description of where the anonymous `region` comes from,
+/// for `descriptive_regions`, if there's anything to say about it.
+fn region_description(region: ty::Region<'_>) -> Option<&'static str> {
+    match *region {
+        ty::ReScope(scope) => Some(match scope.data {
+            region::ScopeData::CallSite |
+            region::ScopeData::Arguments => "function body",
+            region::ScopeData::Destruction => "destruction scope",
+            region::ScopeData::Node => "expression",
+            region::ScopeData::Remainder(_) => "rest of block",
+        }),
+        ty::ReFree(ty::FreeRegion { bound_region: ty::BrAnon(_), .. }) => {
+            Some("anonymous lifetime of the function")
+        }
+        ty::ReFree(ty::FreeRegion { bound_region: ty::BrEnv, .. }) => {
+            Some("closure environment")
+        }
+        _ => None,
+    }
+}
+
+fn name_by_region_index(naming: RegionNaming, index: usize) -> InternedString {
+    match (naming, index) {
+        (RegionNaming::Letters, 0) => InternedString::intern("'r"),
+        (RegionNaming::Letters, 1) => InternedString::intern("'s"),
+        (RegionNaming::Letters, i) => InternedString::intern(&format!("'t{}", i-2)),
+        (RegionNaming::Alphabetic, i) if i < 26 => {
+            InternedString::intern(&format!("'{}", (b'a' + i as u8) as char))
+        }
+        (RegionNaming::Alphabetic, i) => InternedString::intern(&format!("'z{}", i-25)),
+        (RegionNaming::Numbered, i) => InternedString::intern(&format!("'{}", i + 1)),
+        (RegionNaming::Greek, i) => {
+            // The lowercase Greek letters, without the final sigma (`ς`).
+            const LETTERS: &str = "αβγδεζηθικλμνξοπρστυφχψω";
+            const SUBSCRIPTS: &str = "₀₁₂₃₄₅₆₇₈₉";
+            let letter_count = LETTERS.chars().count();
+            let mut name = format!("'{}", LETTERS.chars().nth(i % letter_count).unwrap());
+            let round = i / letter_count;
+            if round > 0 {
+                name.extend(round.to_string().bytes().map(|digit| {
+                    SUBSCRIPTS.chars().nth((digit - b'0') as usize).unwrap()
+                }));
+            }
+            InternedString::intern(&name)
+        }
+        (RegionNaming::Anonymous, _) => bug!("anonymous regions have no names"),
+    }
+}
+
 // HACK(eddyb) limited to `FmtPrinter` because of `binder_depth`,
 // `region_index` and `used_region_names`.
 impl<F: fmt::Write> FmtPrinter<'_, 'gcx, 'tcx, F> {
@@ -1423,14 +4039,6 @@ impl<F: fmt::Write> FmtPrinter<'_, 'gcx, 'tcx, F> {
     ) -> Result<Self, fmt::Error>
         where T: Print<'gcx, 'tcx, Self, Output = Self, Error = fmt::Error> + TypeFoldable<'tcx>
     {
-        fn name_by_region_index(index: usize) -> InternedString {
-            match index {
-                0 => InternedString::intern("'r"),
-                1 => InternedString::intern("'s"),
-                i => InternedString::intern(&format!("'t{}", i-2)),
-            }
-        }
-
         // Replace any anonymous late-bound regions with named
         // variants, using gensym'd identifiers, so that we can
         // clearly differentiate between named and unnamed regions in
@@ -1454,17 +4062,26 @@ impl<F: fmt::Write> FmtPrinter<'_, 'gcx, 'tcx, F> {
 
         let old_region_index = self.region_index;
         let mut region_index = old_region_index;
+        let mut constrained = vec![];
         let new_value = self.tcx.replace_late_bound_regions(value, |br| {
+            if let (ty::BrAnon(_), RegionNaming::Anonymous) |
+                   (ty::BrEnv, RegionNaming::Anonymous) = (br, self.region_naming) {
+                // Left unnamed, so they're omitted, even from the binder.
+                return self.tcx.mk_region(ty::ReLateBound(ty::INNERMOST, br));
+            }
             let _ = start_or_continue(&mut self, "for<", ", ");
+            self.printed_hrtb = true;
+            let bounds = self.bound_region_constraints.as_ref().map_or(vec![], |func| func(br));
             let br = match br {
                 ty::BrNamed(_, name) => {
-                    let _ = write!(self, "{}", name);
+                    let sanitize = self.options.sanitized_idents;
+                    let _ = write_ident(&mut self, &name.as_str(), sanitize);
                     br
                 }
                 ty::BrAnon(_) |
                 ty::BrEnv => {
                     let name = loop {
-                        let name = name_by_region_index(region_index);
+                        let name = self.region_name_by_index(region_index);
                         region_index += 1;
                         if !self.used_region_names.contains(&name) {
                             break name;
@@ -1474,8 +4091,24 @@ impl<F: fmt::Write> FmtPrinter<'_, 'gcx, 'tcx, F> {
                     ty::BrNamed(DefId::local(CRATE_DEF_INDEX), name)
                 }
             };
+            if !bounds.is_empty() {
+                constrained.push((br, bounds));
+            }
             self.tcx.mk_region(ty::ReLateBound(ty::INNERMOST, br))
         }).0;
+        for (i, (br, bounds)) in constrained.into_iter().enumerate() {
+            write!(self, "{}", if i == 0 { " where " } else { ", " })?;
+            if let ty::BrNamed(_, name) = br {
+                self = self.print_ident(&name.as_str())?;
+                write!(self, ": ")?;
+            }
+            for (j, bound) in bounds.into_iter().enumerate() {
+                if j > 0 {
+                    write!(self, " + ")?;
+                }
+                self = self.print_region(bound)?;
+            }
+        }
         start_or_continue(&mut self, "", "> ")?;
 
         self.binder_depth += 1;
@@ -1486,6 +4119,123 @@ impl<F: fmt::Write> FmtPrinter<'_, 'gcx, 'tcx, F> {
         Ok(inner)
     }
 
+    /// Writes the `DefId` of the def `disambiguated_data` is the last path
+    /// segment of, under `def_id_suffixes`, if that's the def whose
+    /// path is being printed.
+    /// Marks the trait `def_id` under `object_safety_markers`, if it's not
+    /// object-safe, but only when its path was the outermost being printed,
+    /// i.e. not when it's the prefix of another path (e.g. of a method).
+    fn write_object_safety_marker(&mut self, def_id: DefId) -> fmt::Result {
+        if !self.options.object_safety_markers || !self.def_id_stack.is_empty() ||
+            in_nested_query(self.tcx) {
+            return Ok(());
+        }
+        if let Some(DefKind::Trait) = self.tcx.def_kind(def_id) {
+            let violations = self.tcx.global_tcx().object_safety_violations(def_id);
+            if let Some(violation) = violations.first() {
+                write!(self, " /* not object-safe: {} */", violation.error_msg())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_def_id_suffix(
+        &mut self,
+        disambiguated_data: &DisambiguatedDefPathData,
+    ) -> fmt::Result {
+        if !self.options.def_id_suffixes {
+            return Ok(());
+        }
+        if let Some(&def_id) = self.def_id_stack.last() {
+            if self.tcx.def_key(def_id).disambiguated_data == *disambiguated_data {
+                write!(self, "{{{}:{}}}", def_id.krate.as_u32(), def_id.index.as_u32())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the name of the anonymous late-bound region numbered `index`,
+    /// only generating (and interning) it the first time it's needed.
+    fn region_name_by_index(&mut self, index: usize) -> InternedString {
+        while self.region_names.len() <= index {
+            let name = name_by_region_index(self.region_naming, self.region_names.len());
+            self.region_names.push(name);
+        }
+        self.region_names[index]
+    }
+
+    /// Returns the bounds that `inline_param_bounds` prints after the type
+    /// parameters in `args`, the generic args of the innermost def being printed.
+    fn inline_bounds(&self, args: &[Kind<'tcx>]) -> Vec<(Ty<'tcx>, Vec<ty::TraitRef<'tcx>>)> {
+        let def_id = match (self.inline_param_bounds, self.def_id_stack.last()) {
+            (Some(_), Some(&def_id)) => def_id,
+            _ => return vec![],
+        };
+        let params: Vec<_> = args.iter().filter_map(|arg| match arg.unpack() {
+            UnpackedKind::Type(ty) => match ty.sty {
+                ty::Param(param) if !self.params_with_bounds.contains(&param) => Some(ty),
+                _ => None,
+            },
+            _ => None,
+        }).collect();
+        if params.is_empty() {
+            return vec![];
+        }
+
+        // The bounds are written in terms of the item's own parameters,
+        // which get replaced with `args`, while the rest are left as-is.
+        let tcx = self.tcx;
+        let generics = tcx.generics_of(def_id);
+        let substs = InternalSubsts::for_item(tcx, def_id, |param, _| {
+            let own_index = (param.index as usize).checked_sub(generics.parent_count);
+            match own_index.and_then(|i| args.get(i)) {
+                Some(&arg) => arg,
+                None => tcx.mk_param_from_def(param),
+            }
+        });
+        let predicates = tcx.predicates_of(def_id).instantiate(tcx, substs).predicates;
+        let sized_trait = tcx.lang_items().sized_trait();
+        params.into_iter().map(|param| {
+            let bounds = predicates.iter().filter_map(|predicate| {
+                predicate.to_opt_poly_trait_ref()
+            }).map(|trait_ref| *trait_ref.skip_binder()).filter(|trait_ref| {
+                trait_ref.self_ty() == param && Some(trait_ref.def_id) != sized_trait
+            }).collect();
+            (param, bounds)
+        }).collect()
+    }
+
+    /// Prints `arg`, followed by its `bounds` (see `inline_bounds`), if it's a
+    /// type parameter printed for the first time, e.g. `T: Eq + Hash`.
+    fn print_arg_with_inline_bounds(
+        mut self,
+        arg: Kind<'tcx>,
+        bounds: &[(Ty<'tcx>, Vec<ty::TraitRef<'tcx>>)],
+    ) -> Result<Self, fmt::Error> {
+        self = arg.print(self)?;
+        let (param, bounds) = match bounds.iter().find(|(param, _)| Kind::from(*param) == arg) {
+            Some((param, bounds)) if !bounds.is_empty() => (param, bounds),
+            _ => return Ok(self),
+        };
+        if let ty::Param(param) = param.sty {
+            if !self.params_with_bounds.insert(param) {
+                return Ok(self);
+            }
+        }
+        let max_bounds = self.inline_param_bounds.unwrap_or(bounds.len());
+        write!(self, ": ")?;
+        for (i, &trait_ref) in bounds.iter().take(max_bounds).enumerate() {
+            if i > 0 {
+                write!(self, " + ")?;
+            }
+            self = trait_ref.print(self)?;
+        }
+        if bounds.len() > max_bounds {
+            write!(self, "{}...", if max_bounds > 0 { " + " } else { "" })?;
+        }
+        Ok(self)
+    }
+
     fn prepare_late_bound_region_info<T>(&mut self, value: &ty::Binder<T>)
         where T: TypeFoldable<'tcx>
     {
@@ -1510,6 +4260,8 @@ impl<F: fmt::Write> FmtPrinter<'_, 'gcx, 'tcx, F> {
     }
 }
 
+/// Prints the `for<...>` prefix naming the regions bound by the binder, if
+/// there are any (see `in_binder`), followed by the value inside it.
 impl<'gcx: 'tcx, 'tcx, T, P: PrettyPrinter<'gcx, 'tcx>> Print<'gcx, 'tcx, P>
     for ty::Binder<T>
     where T: Print<'gcx, 'tcx, P, Output = P, Error = P::Error> + TypeFoldable<'tcx>
@@ -1583,6 +4335,9 @@ forward_display_to_print! {
     Ty<'tcx>,
     &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
     &'tcx ty::Const<'tcx>,
+    ty::TraitRef<'tcx>,
+    ty::FnSig<'tcx>,
+    Kind<'tcx>,
 
     // HACK(eddyb) these are exhaustive instead of generic,
     // because `for<'gcx: 'tcx, 'tcx>` isn't possible yet.
@@ -1628,7 +4383,7 @@ define_print_and_forward_display! {
 
     ty::ExistentialProjection<'tcx> {
         let name = cx.tcx().associated_item(self.item_def_id).ident;
-        p!(write("{} = ", name), print(self.ty))
+        p!(print_ident(&name.as_str()), write(" = "), print(self.ty))
     }
 
     ty::ExistentialPredicate<'tcx> {
@@ -1641,20 +4396,8 @@ define_print_and_forward_display! {
         }
     }
 
-    ty::FnSig<'tcx> {
-        if self.unsafety == hir::Unsafety::Unsafe {
-            p!(write("unsafe "));
-        }
-
-        if self.abi != Abi::Rust {
-            p!(write("extern {} ", self.abi));
-        }
-
-        p!(write("fn"), pretty_fn_sig(self.inputs(), self.c_variadic, self.output()));
-    }
-
     ty::InferTy {
-        if cx.tcx().sess.verbose() {
+        if cx.should_print_verbose() {
             p!(write("{:?}", self));
             return Ok(cx);
         }
@@ -1668,16 +4411,12 @@ define_print_and_forward_display! {
         }
     }
 
-    ty::TraitRef<'tcx> {
-        p!(print_def_path(self.def_id, self.substs));
-    }
-
     ty::ParamTy {
-        p!(write("{}", self.name))
+        p!(print_ident(&self.name.as_str()))
     }
 
     ty::ParamConst {
-        p!(write("{}", self.name))
+        p!(print_ident(&self.name.as_str()))
     }
 
     ty::SubtypePredicate<'tcx> {
@@ -1705,36 +4444,104 @@ define_print_and_forward_display! {
     }
 
     ty::Predicate<'tcx> {
-        match *self {
-            ty::Predicate::Trait(ref data) => p!(print(data)),
-            ty::Predicate::Subtype(ref predicate) => p!(print(predicate)),
-            ty::Predicate::RegionOutlives(ref predicate) => p!(print(predicate)),
-            ty::Predicate::TypeOutlives(ref predicate) => p!(print(predicate)),
-            ty::Predicate::Projection(ref predicate) => p!(print(predicate)),
-            ty::Predicate::WellFormed(ty) => p!(print(ty), write(" well-formed")),
-            ty::Predicate::ObjectSafe(trait_def_id) => {
-                p!(write("the trait `"),
-                   print_def_path(trait_def_id, &[]),
-                   write("` is object-safe"))
-            }
-            ty::Predicate::ClosureKind(closure_def_id, _closure_substs, kind) => {
-                p!(write("the closure `"),
-                   print_value_path(closure_def_id, &[]),
-                   write("` implements the trait `{}`", kind))
-            }
-            ty::Predicate::ConstEvaluatable(def_id, substs) => {
-                p!(write("the constant `"),
-                   print_value_path(def_id, substs),
-                   write("` can be evaluated"))
+        p!(print_predicate(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{abbreviate_path_segment, PrintOptions};
+    use super::{name_by_region_index, PathSegmentAbbreviation, RegionNaming};
+
+    /// Abbreviates the path `segments` (which start with the crate) like
+    /// `FmtPrinter::path_append` does under `max_path_segments`.
+    fn abbreviate_path(segments: &[&str], max_path_segments: usize) -> String {
+        let mut path = segments[0].to_string();
+        for (i, segment) in segments.iter().enumerate().skip(1) {
+            match abbreviate_path_segment(segments.len() - 1 - i, max_path_segments) {
+                PathSegmentAbbreviation::Keep => path = path + "::" + segment,
+                PathSegmentAbbreviation::Collapse => path += "::...",
+                PathSegmentAbbreviation::Omit => {}
             }
         }
+        path
     }
 
-    Kind<'tcx> {
-        match self.unpack() {
-            UnpackedKind::Lifetime(lt) => p!(print(lt)),
-            UnpackedKind::Type(ty) => p!(print(ty)),
-            UnpackedKind::Const(ct) => p!(print(ct)),
-        }
+    const PATH: &[&str] = &["std", "collections", "hash", "map", "inner", "HashMap"];
+
+    #[test]
+    fn test_max_path_segments() {
+        assert_eq!(abbreviate_path(PATH, 2), "std::...::inner::HashMap");
+        assert_eq!(abbreviate_path(PATH, 4), "std::...::hash::map::inner::HashMap");
+    }
+
+    #[test]
+    fn test_max_path_segments_keeps_crate_and_leaf() {
+        assert_eq!(abbreviate_path(PATH, 1), "std::...::HashMap");
+        assert_eq!(abbreviate_path(PATH, 0), "std::...::HashMap");
+        assert_eq!(abbreviate_path(&["std", "HashMap"], 0), "std::HashMap");
+    }
+
+    #[test]
+    fn test_max_path_segments_short_paths() {
+        assert_eq!(abbreviate_path(PATH, 5), "std::collections::hash::map::inner::HashMap");
+        assert_eq!(abbreviate_path(PATH, 6), "std::collections::hash::map::inner::HashMap");
+    }
+
+    /// Returns the names `naming` gives to the regions at `indices`.
+    fn region_names(naming: RegionNaming, indices: &[usize]) -> Vec<String> {
+        syntax::with_default_globals(|| {
+            indices.iter().map(|&i| name_by_region_index(naming, i).to_string()).collect()
+        })
+    }
+
+    #[test]
+    fn test_region_naming() {
+        assert_eq!(region_names(RegionNaming::Letters, &[0, 1, 2, 5]), ["'r", "'s", "'t0", "'t3"]);
+        assert_eq!(region_names(RegionNaming::Alphabetic, &[0, 1, 25, 26, 27]),
+                   ["'a", "'b", "'z", "'z1", "'z2"]);
+        assert_eq!(region_names(RegionNaming::Numbered, &[0, 1, 9]), ["'1", "'2", "'10"]);
+    }
+
+    #[test]
+    fn test_region_naming_greek() {
+        assert_eq!(region_names(RegionNaming::Greek, &[0, 1, 16, 17, 23]),
+                   ["'α", "'β", "'ρ", "'σ", "'ω"]);
+        assert_eq!(region_names(RegionNaming::Greek, &[24, 25, 47, 48, 240, 263]),
+                   ["'α₁", "'β₁", "'ω₁", "'α₂", "'α₁₀", "'ω₁₀"]);
+    }
+
+    #[test]
+    fn test_sanitized_ident() {
+        let options = PrintOptions::default();
+        assert_eq!(options.sanitized_ident("a\u{200d}b"), "a\u{200d}b");
+        let options = PrintOptions { sanitized_idents: true, ..options };
+        assert_eq!(options.sanitized_ident("a\u{200d}b"), "a\\u{200d}b");
+        assert_eq!(options.sanitized_ident("abc\u{202e}cba"), "abc\\u{202e}cba");
+        assert_eq!(options.sanitized_ident("a b"), "a\\u{20}b");
+    }
+
+    #[test]
+    fn test_sanitized_ident_allows_non_ascii() {
+        let options = PrintOptions { sanitized_idents: true, ..PrintOptions::default() };
+        assert_eq!(options.sanitized_ident("naïve_größe"), "naïve_größe");
+        assert_eq!(options.sanitized_ident("Москва"), "Москва");
+        assert_eq!(options.sanitized_ident("{{closure}}"), "{{closure}}");
+    }
+
+    #[test]
+    fn test_sanitized_ident_normalizes() {
+        let options = PrintOptions { sanitized_idents: true, ..PrintOptions::default() };
+        // `e` followed by a combining acute accent, composed into `é`.
+        assert_eq!(options.sanitized_ident("cafe\u{301}"), "caf\u{e9}");
+        assert_eq!(options.sanitized_ident("caf\u{e9}"), "caf\u{e9}");
+    }
+
+    #[test]
+    fn test_sanitized_ident_escapes_mixed_scripts() {
+        let options = PrintOptions { sanitized_idents: true, ..PrintOptions::default() };
+        // The Cyrillic `а` (U+0430), among Latin letters.
+        assert_eq!(options.sanitized_ident("p\u{430}ypal"), "p\\u{430}ypal");
+        assert_eq!(options.sanitized_ident("\u{430}\u{431}_1"), "\u{430}\u{431}_1");
     }
 }
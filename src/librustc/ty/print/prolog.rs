@@ -0,0 +1,416 @@
+//! A `Printer` emitting "type-system entities" as flat Prolog facts, for
+//! logic-based (e.g. Datalog) analyses. Every printed subterm is assigned a
+//! fresh identifier, and described by one fact relating that identifier to
+//! the identifiers of its own subterms. For example, `Option<u32>` becomes:
+//!
+//! ```text
+//! crate(t0, 'core').
+//! segment(t1, t0, 'option', 0).
+//! segment(t2, t1, 'Option', 0).
+//! primitive(t3, 'u32').
+//! generic_args(t4, t2, [t3]).
+//! adt(t5, t4).
+//! ```
+
+use crate::hir;
+use crate::hir::def::Namespace;
+use crate::hir::def_id::CrateNum;
+use crate::hir::map::{DefPathData, DisambiguatedDefPathData};
+use crate::ty::{self, Ty, TyCtxt};
+use crate::ty::print::{FmtPrinterBuilder, Print, PrintOptions, Printer};
+use crate::ty::subst::Kind;
+
+use std::fmt::{self, Write};
+
+/// The identifier of a printed subterm, an atom of the form `tN`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TermId(usize);
+
+impl fmt::Display for TermId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "t{}", self.0)
+    }
+}
+
+/// Quotes `s` as a Prolog atom.
+fn atom(s: &str) -> String {
+    let mut atom = String::with_capacity(s.len() + 2);
+    atom.push('\'');
+    for c in s.chars() {
+        if c == '\'' || c == '\\' {
+            atom.push('\\');
+        }
+        atom.push(c);
+    }
+    atom.push('\'');
+    atom
+}
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns the Prolog facts describing `ty`, see `PrologPrinter`.
+    pub fn type_prolog(self, ty: Ty<'tcx>) -> Vec<String> {
+        PrologPrinter::new(self).print_type(ty).unwrap().into_facts()
+    }
+}
+
+pub struct PrologPrinter<'a, 'gcx, 'tcx> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    options: PrintOptions,
+    facts: Vec<String>,
+
+    /// The identifier of the last subterm printed.
+    last: Option<TermId>,
+}
+
+impl PrologPrinter<'a, 'gcx, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>) -> Self {
+        PrologPrinter {
+            tcx,
+            options: PrintOptions::default(),
+            facts: vec![],
+            last: None,
+        }
+    }
+
+    /// Returns the identifier of the last term printed, which after
+    /// e.g. a `print_type` call is the identifier of the whole type.
+    pub fn root(&self) -> Option<TermId> {
+        self.last
+    }
+
+    /// Returns all the facts emitted so far, one per line.
+    pub fn into_facts(self) -> Vec<String> {
+        self.facts
+    }
+
+    fn last(&self) -> TermId {
+        self.last.expect("PrologPrinter: no term was printed")
+    }
+
+    /// Emits `relation(tN, args...).`, for a fresh `tN`, which becomes the last term.
+    /// Identifiers are unique because every term is described by exactly one fact.
+    fn fact(mut self, relation: &str, args: &[&dyn fmt::Display]) -> Self {
+        let id = TermId(self.facts.len());
+        let mut fact = format!("{}({}", relation, id);
+        for arg in args {
+            let _ = write!(fact, ", {}", arg);
+        }
+        fact.push_str(").");
+        self.facts.push(fact);
+        self.last = Some(id);
+        self
+    }
+
+    /// Prints each of `elems` with `print`, returning a Prolog list of their identifiers.
+    fn print_list<T>(
+        mut self,
+        elems: impl Iterator<Item = T>,
+        mut print: impl FnMut(Self, T) -> Result<Self, !>,
+    ) -> Result<(Self, String), !> {
+        let mut ids = vec![];
+        for elem in elems {
+            self = print(self, elem)?;
+            ids.push(self.last().to_string());
+        }
+        Ok((self, format!("[{}]", ids.join(", "))))
+    }
+
+    fn print_kind(self, arg: Kind<'tcx>) -> Result<Self, !> {
+        arg.print(self)
+    }
+
+    fn print_opt_trait_ref(
+        self,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<(Self, String), !> {
+        match trait_ref {
+            Some(trait_ref) => {
+                let cx = self.print_def_path(trait_ref.def_id, trait_ref.substs)?;
+                let id = cx.last().to_string();
+                Ok((cx, id))
+            }
+            None => Ok((self, "none".to_string())),
+        }
+    }
+}
+
+fn mutability(mutbl: hir::Mutability) -> &'static str {
+    match mutbl {
+        hir::MutMutable => "mut",
+        hir::MutImmutable => "not_mut",
+    }
+}
+
+impl Printer<'gcx, 'tcx> for PrologPrinter<'_, 'gcx, 'tcx> {
+    type Error = !;
+
+    type Path = Self;
+    type Region = Self;
+    type Type = Self;
+    type DynExistential = Self;
+    type Const = Self;
+
+    fn tcx(&'a self) -> TyCtxt<'a, 'gcx, 'tcx> {
+        self.tcx
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.options
+    }
+
+    fn set_print_options(&mut self, options: PrintOptions) {
+        self.options = options;
+    }
+
+    fn print_region(
+        self,
+        region: ty::Region<'_>,
+    ) -> Result<Self::Region, Self::Error> {
+        let mut name = String::new();
+        let _ = FmtPrinterBuilder::new(self.tcx, Namespace::TypeNS)
+            .options(self.options)
+            .build(&mut name)
+            .print_region(region);
+        Ok(self.fact("region", &[&atom(&name)]))
+    }
+
+    fn print_type(
+        mut self,
+        ty: Ty<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        let primitive = match ty.sty {
+            ty::Bool => Some("bool"),
+            ty::Char => Some("char"),
+            ty::Int(t) => Some(t.ty_to_string()),
+            ty::Uint(t) => Some(t.ty_to_string()),
+            ty::Float(t) => Some(t.ty_to_string()),
+            ty::Str => Some("str"),
+            ty::Never => Some("!"),
+            _ => None,
+        };
+        if let Some(name) = primitive {
+            return Ok(self.fact("primitive", &[&atom(name)]));
+        }
+
+        match ty.sty {
+            ty::Adt(def, substs) => {
+                self = self.print_def_path(def.did, substs)?;
+                let path = self.last();
+                Ok(self.fact("adt", &[&path]))
+            }
+            ty::Foreign(def_id) => {
+                self = self.print_def_path(def_id, &[])?;
+                let path = self.last();
+                Ok(self.fact("foreign", &[&path]))
+            }
+            ty::RawPtr(mt) => {
+                self = self.print_type(mt.ty)?;
+                let pointee = self.last();
+                Ok(self.fact("ptr", &[&mutability(mt.mutbl), &pointee]))
+            }
+            ty::Ref(r, ty, mutbl) => {
+                self = self.print_region(r)?;
+                let region = self.last();
+                self = self.print_type(ty)?;
+                let pointee = self.last();
+                Ok(self.fact("ref", &[&region, &mutability(mutbl), &pointee]))
+            }
+            ty::Tuple(tys) => {
+                let (cx, elems) = self.print_list(tys.iter(), |cx, k| cx.print_kind(*k))?;
+                Ok(cx.fact("tuple", &[&elems]))
+            }
+            ty::Array(ty, len) => {
+                self = self.print_type(ty)?;
+                let elem = self.last();
+                self = self.print_const(len)?;
+                let len = self.last();
+                Ok(self.fact("array", &[&elem, &len]))
+            }
+            ty::Slice(ty) => {
+                self = self.print_type(ty)?;
+                let elem = self.last();
+                Ok(self.fact("slice", &[&elem]))
+            }
+            ty::FnPtr(sig) => {
+                let sig = sig.skip_binder();
+                let (mut cx, inputs) = self.print_list(
+                    sig.inputs().iter(),
+                    |cx, &ty| cx.print_type(ty),
+                )?;
+                cx = cx.print_type(sig.output())?;
+                let output = cx.last();
+                Ok(cx.fact("fn_ptr", &[&inputs, &output]))
+            }
+            ty::FnDef(def_id, substs) => {
+                self = self.print_def_path(def_id, substs)?;
+                let path = self.last();
+                Ok(self.fact("fn_def", &[&path]))
+            }
+            ty::Closure(def_id, substs) => {
+                self = self.print_def_path(def_id, substs.substs)?;
+                let path = self.last();
+                Ok(self.fact("closure", &[&path]))
+            }
+            ty::Generator(def_id, substs, _) => {
+                self = self.print_def_path(def_id, substs.substs)?;
+                let path = self.last();
+                Ok(self.fact("generator", &[&path]))
+            }
+            ty::Opaque(def_id, substs) => {
+                self = self.print_def_path(def_id, substs)?;
+                let path = self.last();
+                Ok(self.fact("opaque", &[&path]))
+            }
+            ty::Projection(ref data) => {
+                self = self.print_def_path(data.item_def_id, data.substs)?;
+                let path = self.last();
+                Ok(self.fact("projection", &[&path]))
+            }
+            ty::Dynamic(data, r) => {
+                self = self.print_dyn_existential(*data.skip_binder())?;
+                let predicates = self.last();
+                self = self.print_region(r)?;
+                let region = self.last();
+                Ok(self.fact("dynamic", &[&predicates, &region]))
+            }
+            ty::Param(ref param_ty) => {
+                let name = param_ty.name.as_str();
+                let name = atom(&self.options.sanitized_ident(&name));
+                Ok(self.fact("param", &[&name, &param_ty.index]))
+            }
+
+            // These don't have a stable structure worth exposing to analyses.
+            ty::Infer(_) |
+            ty::Bound(..) |
+            ty::Placeholder(_) |
+            ty::UnnormalizedProjection(_) |
+            ty::GeneratorWitness(_) |
+            ty::Error => Ok(self.fact("other", &[&atom(&format!("{:?}", ty))])),
+
+            ty::Bool | ty::Char | ty::Int(_) | ty::Uint(_) |
+            ty::Float(_) | ty::Str | ty::Never => bug!("primitive `{:?}` not handled", ty),
+        }
+    }
+
+    fn print_fn_sig(
+        self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        let fn_ptr = self.tcx().mk_fn_ptr(ty::Binder::bind(sig));
+        self.print_type(fn_ptr)
+    }
+
+    fn print_dyn_existential(
+        self,
+        predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
+    ) -> Result<Self::DynExistential, Self::Error> {
+        let (cx, predicates) = self.print_list(predicates.iter(), |mut cx, predicate| {
+            match *predicate {
+                ty::ExistentialPredicate::Trait(trait_ref) => {
+                    // Use a type that can't appear in defaults of type parameters.
+                    let dummy_self = cx.tcx.mk_ty_infer(ty::FreshTy(0));
+                    let trait_ref = trait_ref.with_self_ty(cx.tcx, dummy_self);
+                    cx = cx.print_def_path(trait_ref.def_id, trait_ref.substs)?;
+                    let path = cx.last();
+                    Ok(cx.fact("existential_trait", &[&path]))
+                }
+                ty::ExistentialPredicate::Projection(projection) => {
+                    let name = cx.tcx.associated_item(projection.item_def_id).ident;
+                    cx = cx.print_type(projection.ty)?;
+                    let ty = cx.last();
+                    let name = atom(&cx.options.sanitized_ident(&name.as_str()));
+                    Ok(cx.fact("existential_projection", &[&name, &ty]))
+                }
+                ty::ExistentialPredicate::AutoTrait(def_id) => {
+                    cx = cx.print_def_path(def_id, &[])?;
+                    let path = cx.last();
+                    Ok(cx.fact("auto_trait", &[&path]))
+                }
+            }
+        })?;
+        Ok(cx.fact("existential", &[&predicates]))
+    }
+
+    fn print_const(
+        mut self,
+        ct: &'tcx ty::Const<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        let mut value = String::new();
+        let _ = FmtPrinterBuilder::new(self.tcx, Namespace::ValueNS)
+            .options(self.options)
+            .build(&mut value)
+            .print_const(ct);
+        self = self.print_type(ct.ty)?;
+        let ty = self.last();
+        Ok(self.fact("const", &[&ty, &atom(&value)]))
+    }
+
+    fn path_crate(
+        self,
+        cnum: CrateNum,
+    ) -> Result<Self::Path, Self::Error> {
+        let name = self.tcx.original_crate_name(cnum).as_str();
+        let name = self.options.sanitized_ident(&name);
+        Ok(self.fact("crate", &[&atom(&name)]))
+    }
+    fn path_qualified(
+        mut self,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self = self.print_type(self_ty)?;
+        let self_ty = self.last();
+        let (cx, trait_ref) = self.print_opt_trait_ref(trait_ref)?;
+        Ok(cx.fact("qualified", &[&self_ty, &trait_ref]))
+    }
+
+    fn path_append_impl(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        _disambiguated_data: &DisambiguatedDefPathData,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?;
+        let prefix = self.last();
+        self = self.print_type(self_ty)?;
+        let self_ty = self.last();
+        let (cx, trait_ref) = self.print_opt_trait_ref(trait_ref)?;
+        Ok(cx.fact("impl_segment", &[&prefix, &self_ty, &trait_ref]))
+    }
+    fn path_append(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        disambiguated_data: &DisambiguatedDefPathData,
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?;
+
+        // Skip `::{{constructor}}` on tuple/unit structs.
+        if let DefPathData::Ctor = disambiguated_data.data {
+            return Ok(self);
+        }
+
+        let prefix = self.last();
+        let name = disambiguated_data.data.as_interned_str().as_str();
+        let name = self.options.sanitized_ident(&name);
+        Ok(self.fact("segment", &[
+            &prefix,
+            &atom(&name),
+            &disambiguated_data.disambiguator,
+        ]))
+    }
+    fn path_generic_args(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        args: &[Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?;
+        if args.is_empty() {
+            return Ok(self);
+        }
+
+        let prefix = self.last();
+        let (cx, args) = self.print_list(args.iter(), |cx, &arg| cx.print_kind(arg))?;
+        Ok(cx.fact("generic_args", &[&prefix, &args]))
+    }
+}
@@ -0,0 +1,440 @@
+//! A `Printer` producing a minimal, self-contained reproduction of a type,
+//! e.g. for bug reports about type-related ICEs, which can be shared without
+//! the crate(s) the type came from. Items from the standard library crates
+//! are kept as-is, while all the other ADTs and traits the type refers to are
+//! replaced by anonymous stubs (`S0`, `T1`, etc.) with the same generics:
+//!
+//! ```text
+//! struct S0<'a0, A0>(std::marker::PhantomData<(&'a0 (), A0,)>);
+//! trait T1 { type Item; }
+//! fn repro<T>() {
+//!     let _: S0<'static, std::vec::Vec<std::boxed::Box<dyn T1<Item = T> + 'static>>>;
+//! }
+//! ```
+//!
+//! Stubs don't refer to each other, so cyclic types need no forward
+//! declarations. Only the structure of the type is reproduced, not e.g. the
+//! impls that projections would need to be normalized, nor const generics:
+//! const args are dropped from stubs, and printed as `_` elsewhere. Types
+//! that can't be written out (closures, `impl Trait`, etc.) are replaced by
+//! stubs without generics. Free regions print as `'static`, bound ones as `'_`.
+
+use crate::hir;
+use crate::hir::def_id::{CrateNum, DefId};
+use crate::hir::map::DisambiguatedDefPathData;
+use crate::ty::{self, Ty, TyCtxt};
+use crate::ty::print::{Print, Printer};
+use crate::ty::subst::{Kind, UnpackedKind};
+use rustc_data_structures::fx::FxHashMap;
+use syntax::symbol::InternedString;
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+enum StubKind {
+    Struct,
+    Trait,
+}
+
+struct Stub {
+    kind: StubKind,
+    lifetimes: usize,
+    types: usize,
+    assoc_types: Vec<InternedString>,
+}
+
+pub struct ReproPrinter<'a, 'gcx, 'tcx> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    out: String,
+
+    stubs: Vec<Stub>,
+    stub_indices: FxHashMap<DefId, usize>,
+
+    /// The type parameters referred to, by index, which become the
+    /// generics of the `repro` function.
+    params: BTreeMap<u32, InternedString>,
+}
+
+impl ReproPrinter<'a, 'gcx, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>) -> Self {
+        ReproPrinter {
+            tcx,
+            out: String::new(),
+            stubs: vec![],
+            stub_indices: Default::default(),
+            params: Default::default(),
+        }
+    }
+
+    /// Returns the reproduction of the type printed so far, i.e. the stubs
+    /// followed by a `repro` function with a `let` of that type.
+    pub fn finish(self) -> String {
+        let mut s = String::new();
+        for (i, stub) in self.stubs.iter().enumerate() {
+            let params = (0..stub.lifetimes).map(|j| format!("'a{}", j))
+                .chain((0..stub.types).map(|j| format!("A{}", j)))
+                .collect::<Vec<_>>();
+            let generics = if params.is_empty() {
+                String::new()
+            } else {
+                format!("<{}>", params.join(", "))
+            };
+            let _ = match stub.kind {
+                StubKind::Struct if params.is_empty() => writeln!(s, "struct S{};", i),
+                StubKind::Struct => {
+                    let phantom = (0..stub.lifetimes).map(|j| format!("&'a{} ()", j))
+                        .chain((0..stub.types).map(|j| format!("A{}", j)))
+                        .collect::<Vec<_>>();
+                    writeln!(s, "struct S{}{}(std::marker::PhantomData<({},)>);",
+                             i, generics, phantom.join(", "))
+                }
+                StubKind::Trait => {
+                    let assoc_types = stub.assoc_types.iter()
+                        .map(|name| format!(" type {};", name))
+                        .collect::<String>();
+                    writeln!(s, "trait T{}{} {{{} }}", i, generics, assoc_types)
+                }
+            };
+        }
+        let params = self.params.values().map(|name| name.to_string()).collect::<Vec<_>>();
+        let _ = writeln!(s, "fn repro<{}>() {{", params.join(", "));
+        let _ = writeln!(s, "    let _: {};", self.out);
+        let _ = writeln!(s, "}}");
+        s
+    }
+
+    fn push(mut self, s: &str) -> Self {
+        self.out.push_str(s);
+        self
+    }
+
+    /// Returns whether `def_id` is from one of the standard library
+    /// crates, which the reproduction can refer to directly.
+    fn is_std(&self, def_id: DefId) -> bool {
+        match &*self.tcx.crate_name(def_id.krate).as_str() {
+            "std" | "core" | "alloc" => true,
+            _ => false,
+        }
+    }
+
+    fn stub(&mut self, def_id: DefId, kind: StubKind, args: &[Kind<'tcx>]) -> usize {
+        if let Some(&i) = self.stub_indices.get(&def_id) {
+            return i;
+        }
+        let mut stub = Stub {
+            kind,
+            lifetimes: 0,
+            types: 0,
+            assoc_types: vec![],
+        };
+        for arg in args {
+            match arg.unpack() {
+                UnpackedKind::Lifetime(_) => stub.lifetimes += 1,
+                UnpackedKind::Type(_) => stub.types += 1,
+                UnpackedKind::Const(_) => {}
+            }
+        }
+        self.stubs.push(stub);
+        self.stub_indices.insert(def_id, self.stubs.len() - 1);
+        self.stubs.len() - 1
+    }
+
+    /// Prints the path of the ADT, foreign type or trait `def_id`, with
+    /// `args` (which exclude the `Self` of traits), stubbing it if needed.
+    fn print_item(self, def_id: DefId, kind: StubKind, args: &[Kind<'tcx>]) -> Result<Self, !> {
+        self.print_item_with_bindings(def_id, kind, args, &[])
+    }
+
+    /// Like `print_item`, followed by associated type `bindings`, e.g.
+    /// `Iterator<Item = u8>`, for the principal trait of trait objects.
+    fn print_item_with_bindings(
+        mut self,
+        def_id: DefId,
+        kind: StubKind,
+        args: &[Kind<'tcx>],
+        bindings: &[ty::ExistentialProjection<'tcx>],
+    ) -> Result<Self, !> {
+        let stubbed = !self.is_std(def_id);
+        if stubbed {
+            let prefix = match kind {
+                StubKind::Struct => "S",
+                StubKind::Trait => "T",
+            };
+            let i = self.stub(def_id, kind, args);
+            for binding in bindings {
+                let name = self.tcx.item_name(binding.item_def_id).as_interned_str();
+                if !self.stubs[i].assoc_types.contains(&name) {
+                    self.stubs[i].assoc_types.push(name);
+                }
+            }
+            self = self.push(&format!("{}{}", prefix, i));
+        } else {
+            let path = self.tcx.def_path_str(def_id);
+            self = self.push(&path);
+        }
+
+        // Stubs don't have const params (see the module docs).
+        let args = args.iter().filter(|arg| match arg.unpack() {
+            UnpackedKind::Const(_) => !stubbed,
+            _ => true,
+        }).collect::<Vec<_>>();
+        if args.is_empty() && bindings.is_empty() {
+            return Ok(self);
+        }
+        self = self.push("<");
+        for (i, arg) in args.iter().enumerate() {
+            if i > 0 {
+                self = self.push(", ");
+            }
+            self = self.print_arg(**arg)?;
+        }
+        for (i, binding) in bindings.iter().enumerate() {
+            if i > 0 || !args.is_empty() {
+                self = self.push(", ");
+            }
+            let name = self.tcx.item_name(binding.item_def_id);
+            self = self.push(&format!("{} = ", name)).print_type(binding.ty)?;
+        }
+        Ok(self.push(">"))
+    }
+
+    fn print_arg(self, arg: Kind<'tcx>) -> Result<Self, !> {
+        arg.print(self)
+    }
+}
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns a self-contained reproduction of `ty`, see `ReproPrinter`.
+    pub fn type_repro(self, ty: Ty<'tcx>) -> String {
+        ReproPrinter::new(self).print_type(ty).unwrap().finish()
+    }
+}
+
+impl Printer<'gcx, 'tcx> for ReproPrinter<'_, 'gcx, 'tcx> {
+    type Error = !;
+
+    type Path = Self;
+    type Region = Self;
+    type Type = Self;
+    type DynExistential = Self;
+    type Const = Self;
+
+    fn tcx(&'a self) -> TyCtxt<'a, 'gcx, 'tcx> {
+        self.tcx
+    }
+
+    fn print_def_path(
+        mut self,
+        def_id: DefId,
+        _substs: &'tcx [Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        // Anything other than ADTs, foreign types and traits (which are
+        // printed by `print_item` instead) can't be named in the reproduction.
+        let i = self.stub(def_id, StubKind::Struct, &[]);
+        Ok(self.push(&format!("S{}", i)))
+    }
+
+    fn print_region(
+        self,
+        region: ty::Region<'_>,
+    ) -> Result<Self::Region, Self::Error> {
+        Ok(self.push(match *region {
+            ty::ReLateBound(..) => "'_",
+            _ => "'static",
+        }))
+    }
+
+    fn print_type(
+        mut self,
+        ty: Ty<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        match ty.sty {
+            ty::Bool => Ok(self.push("bool")),
+            ty::Char => Ok(self.push("char")),
+            ty::Int(t) => Ok(self.push(t.ty_to_string())),
+            ty::Uint(t) => Ok(self.push(t.ty_to_string())),
+            ty::Float(t) => Ok(self.push(t.ty_to_string())),
+            ty::Str => Ok(self.push("str")),
+            ty::Never => Ok(self.push("!")),
+
+            ty::Adt(def, substs) => self.print_item(def.did, StubKind::Struct, substs),
+            ty::Foreign(def_id) => self.print_item(def_id, StubKind::Struct, &[]),
+            ty::Projection(ref data) => {
+                let trait_ref = data.trait_ref(self.tcx);
+                let name = self.tcx.item_name(data.item_def_id).as_interned_str();
+                self = self.path_qualified(trait_ref.self_ty(), Some(trait_ref))?;
+                if let Some(&i) = self.stub_indices.get(&trait_ref.def_id) {
+                    if !self.stubs[i].assoc_types.contains(&name) {
+                        self.stubs[i].assoc_types.push(name);
+                    }
+                }
+                Ok(self.push(&format!("::{}", name)))
+            }
+            ty::RawPtr(mt) => {
+                self.push(match mt.mutbl {
+                    hir::MutMutable => "*mut ",
+                    hir::MutImmutable => "*const ",
+                }).print_type(mt.ty)
+            }
+            ty::Ref(r, ty, mutbl) => {
+                self = self.push("&").print_region(r)?;
+                self.push(match mutbl {
+                    hir::MutMutable => " mut ",
+                    hir::MutImmutable => " ",
+                }).print_type(ty)
+            }
+            ty::Tuple(tys) => {
+                self = self.push("(");
+                for (i, ty) in tys.types().enumerate() {
+                    if i > 0 {
+                        self = self.push(", ");
+                    }
+                    self = self.print_type(ty)?;
+                }
+                Ok(self.push(if tys.len() == 1 { ",)" } else { ")" }))
+            }
+            ty::Array(ty, len) => {
+                Ok(self.push("[").print_type(ty)?.push("; ").print_const(len)?.push("]"))
+            }
+            ty::Slice(ty) => Ok(self.push("[").print_type(ty)?.push("]")),
+            ty::FnPtr(sig) => {
+                let sig = sig.skip_binder();
+                if sig.unsafety == hir::Unsafety::Unsafe {
+                    self = self.push("unsafe ");
+                }
+                self = self.push(&format!("extern {} fn(", sig.abi));
+                for (i, &ty) in sig.inputs().iter().enumerate() {
+                    if i > 0 {
+                        self = self.push(", ");
+                    }
+                    self = self.print_type(ty)?;
+                }
+                if sig.c_variadic {
+                    self = self.push(", ...");
+                }
+                self.push(") -> ").print_type(sig.output())
+            }
+            ty::Dynamic(data, r) => {
+                self = self.push("dyn ").print_dyn_existential(*data.skip_binder())?;
+                self.push(" + ").print_region(r)
+            }
+            ty::Param(ref param_ty) => {
+                self.params.insert(param_ty.index, param_ty.name);
+                Ok(self.push(&param_ty.name.as_str()))
+            }
+
+            ty::FnDef(def_id, substs) |
+            ty::Opaque(def_id, substs) => self.print_def_path(def_id, substs),
+            ty::Closure(def_id, ty::ClosureSubsts { substs }) |
+            ty::Generator(def_id, ty::GeneratorSubsts { substs }, _) => {
+                self.print_def_path(def_id, substs)
+            }
+
+            // Not expected in well-formed types, so they're left as-is.
+            ty::Infer(_) |
+            ty::Bound(..) |
+            ty::Placeholder(_) |
+            ty::UnnormalizedProjection(_) |
+            ty::GeneratorWitness(_) |
+            ty::Error => Ok(self.push(&format!("{:?}", ty))),
+        }
+    }
+
+    fn print_fn_sig(
+        self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        let fn_ptr = self.tcx().mk_fn_ptr(ty::Binder::bind(sig));
+        self.print_type(fn_ptr)
+    }
+
+    fn print_dyn_existential(
+        mut self,
+        predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
+    ) -> Result<Self::DynExistential, Self::Error> {
+        // Associated type bindings go into the generic args of their trait,
+        // which is always the principal (the only non-auto trait).
+        let bindings = predicates.projection_bounds().map(|p| *p.skip_binder())
+            .collect::<Vec<_>>();
+        let mut first = true;
+        for predicate in predicates {
+            let (def_id, args, bindings) = match *predicate {
+                ty::ExistentialPredicate::Trait(trait_ref) => {
+                    (trait_ref.def_id, trait_ref.substs, &bindings[..])
+                }
+                ty::ExistentialPredicate::AutoTrait(def_id) => {
+                    (def_id, ty::List::empty(), &[][..])
+                }
+                ty::ExistentialPredicate::Projection(_) => continue,
+            };
+            if !first {
+                self = self.push(" + ");
+            }
+            first = false;
+            self = self.print_item_with_bindings(def_id, StubKind::Trait, args, bindings)?;
+        }
+        Ok(self)
+    }
+
+    fn print_const(
+        self,
+        _ct: &'tcx ty::Const<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        Ok(self.push("_"))
+    }
+
+    // Paths are printed by `print_def_path` and `print_item` as a whole,
+    // so these are only used by callers printing partial paths themselves.
+
+    fn path_crate(
+        self,
+        cnum: CrateNum,
+    ) -> Result<Self::Path, Self::Error> {
+        let name = self.tcx.crate_name(cnum);
+        Ok(self.push(&name.as_str()))
+    }
+    fn path_qualified(
+        mut self,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self = self.push("<").print_type(self_ty)?;
+        if let Some(trait_ref) = trait_ref {
+            self = self.push(" as ")
+                .print_item(trait_ref.def_id, StubKind::Trait, &trait_ref.substs[1..])?;
+        }
+        Ok(self.push(">"))
+    }
+
+    fn path_append_impl(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        _disambiguated_data: &DisambiguatedDefPathData,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        print_prefix(self)?.push("::").path_qualified(self_ty, trait_ref)
+    }
+    fn path_append(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        disambiguated_data: &DisambiguatedDefPathData,
+    ) -> Result<Self::Path, Self::Error> {
+        let name = disambiguated_data.data.as_interned_str();
+        Ok(print_prefix(self)?.push("::").push(&name.as_str()))
+    }
+    fn path_generic_args(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        args: &[Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        let mut cx = print_prefix(self)?.push("<");
+        for (i, &arg) in args.iter().enumerate() {
+            if i > 0 {
+                cx = cx.push(", ");
+            }
+            cx = cx.print_arg(arg)?;
+        }
+        Ok(cx.push(">"))
+    }
+}
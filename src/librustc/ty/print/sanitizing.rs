@@ -0,0 +1,190 @@
+//! A `Printer` wrapping any other printer, to sanitize the identifiers it
+//! prints, for tools displaying the types of untrusted crates, so that their
+//! names can't spoof others in a terminal, e.g. through zero-width joiners.
+
+use crate::hir::def_id::{CrateNum, DefId};
+use crate::hir::map::DisambiguatedDefPathData;
+use crate::ty::{self, Ty, TyCtxt};
+use crate::ty::print::{PrintOptions, PrintedGenericArg, Printer};
+use crate::ty::subst::Kind;
+
+/// Makes the printer `P` normalize the identifiers it prints (path segments,
+/// as well as the names of e.g. parameters, regions, fields and associated
+/// types), and escape (as `\u{...}`) their invisible characters and their
+/// mixed-script confusables, while still allowing non-ASCII identifiers (see
+/// `sanitized_idents`), e.g. to print `ty` into `s`:
+/// `ty.print(SanitizingPrinter::new(FmtPrinter::new(tcx, &mut s, ns)))`.
+///
+/// Everything is printed by `P` itself, with its `sanitized_idents` option
+/// enabled, so e.g. the paths of generic args are sanitized too. This relies
+/// on `P` storing its `print_options`, and writing identifiers with
+/// `PrintOptions::sanitized_ident`, as all the printers in this module do, apart
+/// from those whose output isn't meant to be displayed (e.g. `TypeHashPrinter`)
+/// or must stay valid Rust (e.g. `ReproPrinter`).
+/// Like `P`, this returns whatever `P` does, e.g. `P` itself, not the wrapper.
+pub struct SanitizingPrinter<P>(P);
+
+impl<'gcx: 'tcx, 'tcx, P: Printer<'gcx, 'tcx>> SanitizingPrinter<P> {
+    pub fn new(mut printer: P) -> Self {
+        let options = printer.print_options();
+        printer.set_print_options(PrintOptions { sanitized_idents: true, ..options });
+        SanitizingPrinter(printer)
+    }
+
+    /// Returns the wrapped printer.
+    pub fn into_inner(self) -> P {
+        self.0
+    }
+}
+
+impl<'gcx: 'tcx, 'tcx, P: Printer<'gcx, 'tcx>> Printer<'gcx, 'tcx> for SanitizingPrinter<P> {
+    type Error = P::Error;
+
+    type Path = P::Path;
+    type Region = P::Region;
+    type Type = P::Type;
+    type DynExistential = P::DynExistential;
+    type Const = P::Const;
+
+    fn tcx<'a>(&'a self) -> TyCtxt<'a, 'gcx, 'tcx> {
+        self.0.tcx()
+    }
+
+    fn should_print_verbose(&self) -> bool {
+        self.0.should_print_verbose()
+    }
+
+    fn should_fully_qualify_paths(&self) -> bool {
+        self.0.should_fully_qualify_paths()
+    }
+
+    fn in_value_path(&self) -> bool {
+        self.0.in_value_path()
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.0.print_options()
+    }
+
+    fn set_print_options(&mut self, options: PrintOptions) {
+        self.0.set_print_options(PrintOptions { sanitized_idents: true, ..options });
+    }
+
+    fn print_def_path(
+        self,
+        def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        self.0.print_def_path(def_id, substs)
+    }
+
+    fn print_value_path(
+        self,
+        def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        self.0.print_value_path(def_id, substs)
+    }
+
+    fn print_impl_path(
+        self,
+        impl_def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self.0.print_impl_path(impl_def_id, substs, self_ty, trait_ref)
+    }
+
+    fn print_trait_ref(
+        self,
+        trait_ref: ty::TraitRef<'tcx>,
+    ) -> Result<Self::Path, Self::Error> {
+        self.0.print_trait_ref(trait_ref)
+    }
+
+    fn print_region(
+        self,
+        region: ty::Region<'_>,
+    ) -> Result<Self::Region, Self::Error> {
+        self.0.print_region(region)
+    }
+
+    fn print_type(
+        self,
+        ty: Ty<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        self.0.print_type(ty)
+    }
+
+    fn print_fn_sig(
+        self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        self.0.print_fn_sig(sig)
+    }
+
+    fn print_dyn_existential(
+        self,
+        predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
+    ) -> Result<Self::DynExistential, Self::Error> {
+        self.0.print_dyn_existential(predicates)
+    }
+
+    fn print_const(
+        self,
+        ct: &'tcx ty::Const<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        self.0.print_const(ct)
+    }
+
+    fn print_generic_arg(
+        self,
+        arg: Kind<'tcx>,
+    ) -> Result<PrintedGenericArg<Self::Region, Self::Type, Self::Const>, Self::Error> {
+        self.0.print_generic_arg(arg)
+    }
+
+    fn path_crate(
+        self,
+        cnum: CrateNum,
+    ) -> Result<Self::Path, Self::Error> {
+        self.0.path_crate(cnum)
+    }
+    fn path_qualified(
+        self,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self.0.path_qualified(self_ty, trait_ref)
+    }
+
+    fn path_append_impl(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        disambiguated_data: &DisambiguatedDefPathData,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self.0.path_append_impl(
+            |cx| print_prefix(SanitizingPrinter(cx)),
+            disambiguated_data,
+            self_ty,
+            trait_ref,
+        )
+    }
+    fn path_append(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        disambiguated_data: &DisambiguatedDefPathData,
+    ) -> Result<Self::Path, Self::Error> {
+        self.0.path_append(|cx| print_prefix(SanitizingPrinter(cx)), disambiguated_data)
+    }
+    fn path_generic_args(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        args: &[Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        self.0.path_generic_args(|cx| print_prefix(SanitizingPrinter(cx)), args)
+    }
+}
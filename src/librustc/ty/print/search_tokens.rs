@@ -0,0 +1,262 @@
+//! A `Printer` producing the tokens of a type for full-text search indexing,
+//! e.g. `vec`, `hashmap`, `string`, `u32` for `Vec<HashMap<String, u32>>`.
+//!
+//! Only names show up as tokens: syntax (e.g. `&`, `<...>` or `fn`), lifetimes
+//! and paths leading up to the (last) name of a def are all ignored. Tokens are
+//! lowercased, stripped of everything but alphanumerics and `_`, and kept only
+//! the first time they appear in the document (i.e. all the printed types).
+
+use crate::hir::def_id::{CrateNum, DefId};
+use crate::hir::map::{DefPathData, DisambiguatedDefPathData};
+use crate::mir::interpret::ConstValue;
+use crate::ty::{self, Ty, TyCtxt};
+use crate::ty::print::{Print, PrintOptions, Printer};
+use crate::ty::subst::Kind;
+use rustc_data_structures::fx::FxHashSet;
+
+pub struct SearchTokenPrinter<'a, 'gcx, 'tcx> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    options: PrintOptions,
+    tokens: Vec<String>,
+
+    /// The tokens already in `tokens`, to skip repeated ones.
+    seen: FxHashSet<String>,
+}
+
+impl SearchTokenPrinter<'a, 'gcx, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>) -> Self {
+        SearchTokenPrinter {
+            tcx,
+            options: PrintOptions::default(),
+            tokens: vec![],
+            seen: Default::default(),
+        }
+    }
+
+    /// Returns the tokens of everything printed so far, in order of appearance.
+    pub fn into_tokens(self) -> Vec<String> {
+        self.tokens
+    }
+
+    fn push(mut self, name: &str) -> Self {
+        let token: String = name.chars()
+            .filter(|&c| c.is_alphanumeric() || c == '_')
+            .flat_map(char::to_lowercase)
+            .collect();
+        if !token.is_empty() && self.seen.insert(token.clone()) {
+            self.tokens.push(token);
+        }
+        self
+    }
+
+    fn print_kinds(mut self, args: &[Kind<'tcx>]) -> Result<Self, !> {
+        for arg in args {
+            self = arg.print(self)?;
+        }
+        Ok(self)
+    }
+}
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns the search tokens of `ty`, see `SearchTokenPrinter`.
+    pub fn search_tokens(self, ty: Ty<'tcx>) -> Vec<String> {
+        SearchTokenPrinter::new(self).print_type(ty).unwrap().into_tokens()
+    }
+}
+
+impl Printer<'gcx, 'tcx> for SearchTokenPrinter<'_, 'gcx, 'tcx> {
+    type Error = !;
+
+    type Path = Self;
+    type Region = Self;
+    type Type = Self;
+    type DynExistential = Self;
+    type Const = Self;
+
+    fn tcx(&'a self) -> TyCtxt<'a, 'gcx, 'tcx> {
+        self.tcx
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.options
+    }
+
+    fn set_print_options(&mut self, options: PrintOptions) {
+        self.options = options;
+    }
+
+    fn print_def_path(
+        self,
+        mut def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        // Tuple/unit struct constructors are named after their struct.
+        if let DefPathData::Ctor = self.tcx.def_key(def_id).disambiguated_data.data {
+            def_id = self.tcx.parent(def_id).unwrap();
+        }
+        let name = self.tcx.def_key(def_id).disambiguated_data.data.as_interned_str().as_str();
+        let name = self.options.sanitized_ident(&name);
+        self.push(&name).print_kinds(substs)
+    }
+
+    fn print_region(
+        self,
+        _region: ty::Region<'_>,
+    ) -> Result<Self::Region, Self::Error> {
+        Ok(self)
+    }
+
+    fn print_type(
+        self,
+        ty: Ty<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        match ty.sty {
+            ty::Bool => Ok(self.push("bool")),
+            ty::Char => Ok(self.push("char")),
+            ty::Int(t) => Ok(self.push(t.ty_to_string())),
+            ty::Uint(t) => Ok(self.push(t.ty_to_string())),
+            ty::Float(t) => Ok(self.push(t.ty_to_string())),
+            ty::Str => Ok(self.push("str")),
+            ty::Never => Ok(self),
+
+            ty::Adt(def, substs) => self.print_def_path(def.did, substs),
+            ty::Foreign(def_id) => self.print_def_path(def_id, &[]),
+            ty::FnDef(def_id, substs) |
+            ty::Opaque(def_id, substs) => self.print_def_path(def_id, substs),
+            ty::Closure(def_id, ty::ClosureSubsts { substs }) |
+            ty::Generator(def_id, ty::GeneratorSubsts { substs }, _) => {
+                self.print_def_path(def_id, substs)
+            }
+            ty::Projection(ref data) => {
+                let trait_ref = data.trait_ref(self.tcx);
+                let name = self.tcx.associated_item(data.item_def_id).ident.as_str();
+                let name = self.options.sanitized_ident(&name);
+                Ok(self.print_def_path(trait_ref.def_id, trait_ref.substs)?.push(&name))
+            }
+            ty::RawPtr(ty::TypeAndMut { ty, .. }) |
+            ty::Ref(_, ty, _) |
+            ty::Slice(ty) => self.print_type(ty),
+            ty::Tuple(tys) => self.print_kinds(tys),
+            ty::Array(ty, len) => self.print_type(ty)?.print_const(len),
+            ty::FnPtr(sig) => {
+                let sig = sig.skip_binder();
+                self.print_kinds(sig.inputs_and_output)
+            }
+            ty::Dynamic(data, _) => self.print_dyn_existential(*data.skip_binder()),
+            ty::Param(ref param_ty) => {
+                let name = param_ty.name.as_str();
+                let name = self.options.sanitized_ident(&name);
+                Ok(self.push(&name))
+            }
+
+            // These have no names worth searching for.
+            ty::Infer(_) |
+            ty::Bound(..) |
+            ty::Placeholder(_) |
+            ty::UnnormalizedProjection(_) |
+            ty::GeneratorWitness(_) |
+            ty::Error => Ok(self),
+        }
+    }
+
+    fn print_fn_sig(
+        self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        let fn_ptr = self.tcx().mk_fn_ptr(ty::Binder::bind(sig));
+        self.print_type(fn_ptr)
+    }
+
+    fn print_dyn_existential(
+        mut self,
+        predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
+    ) -> Result<Self::DynExistential, Self::Error> {
+        for predicate in predicates.iter() {
+            self = match *predicate {
+                ty::ExistentialPredicate::Trait(trait_ref) => {
+                    self.print_def_path(trait_ref.def_id, trait_ref.substs)?
+                }
+                ty::ExistentialPredicate::Projection(projection) => {
+                    let name = self.tcx.associated_item(projection.item_def_id).ident.as_str();
+                    let name = self.options.sanitized_ident(&name);
+                    self.push(&name).print_type(projection.ty)?
+                }
+                ty::ExistentialPredicate::AutoTrait(def_id) => {
+                    self.print_def_path(def_id, &[])?
+                }
+            };
+        }
+        Ok(self)
+    }
+
+    fn print_const(
+        self,
+        ct: &'tcx ty::Const<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        // Only const params are named, values (e.g. array lengths) aren't.
+        match ct.val {
+            ConstValue::Param(param) => {
+                let name = param.name.as_str();
+                let name = self.options.sanitized_ident(&name);
+                Ok(self.push(&name))
+            }
+            _ => Ok(self),
+        }
+    }
+
+    // Paths are printed by `print_def_path` as their last name, so these are
+    // only used by callers printing partial paths themselves.
+
+    fn path_crate(
+        self,
+        cnum: CrateNum,
+    ) -> Result<Self::Path, Self::Error> {
+        let name = self.tcx.original_crate_name(cnum).as_str();
+        let name = self.options.sanitized_ident(&name);
+        Ok(self.push(&name))
+    }
+    fn path_qualified(
+        self,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        let cx = self.print_type(self_ty)?;
+        match trait_ref {
+            Some(trait_ref) => cx.print_def_path(trait_ref.def_id, trait_ref.substs),
+            None => Ok(cx),
+        }
+    }
+
+    fn path_append_impl(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        _disambiguated_data: &DisambiguatedDefPathData,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        print_prefix(self)?.path_qualified(self_ty, trait_ref)
+    }
+    fn path_append(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        disambiguated_data: &DisambiguatedDefPathData,
+    ) -> Result<Self::Path, Self::Error> {
+        let cx = print_prefix(self)?;
+
+        // Skip `::{{constructor}}` on tuple/unit structs, like other printers.
+        if let DefPathData::Ctor = disambiguated_data.data {
+            return Ok(cx);
+        }
+
+        let name = disambiguated_data.data.as_interned_str().as_str();
+        let name = cx.options.sanitized_ident(&name);
+        Ok(cx.push(&name))
+    }
+    fn path_generic_args(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        args: &[Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        print_prefix(self)?.print_kinds(args)
+    }
+}
@@ -0,0 +1,258 @@
+//! A `Printer` that collects the segments of a path, instead of printing it,
+//! for tools (e.g. search indexers) working with paths segment by segment,
+//! e.g. `std`, `vec`, `Vec` for `std::vec::Vec<u32>`. Segments are collected
+//! up front, then handed out by an iterator, as `path_append` builds a path
+//! prefix-first through nested calls, which doesn't lend itself to streaming.
+
+use crate::hir::def::Namespace;
+use crate::hir::def_id::{CrateNum, DefId};
+use crate::hir::map::{DefPathData, DisambiguatedDefPathData};
+use crate::ty::{self, Ty, TyCtxt};
+use crate::ty::print::{FmtPrinterBuilder, PrettyPrinter, PrintOptions, Printer};
+use crate::ty::subst::Kind;
+use syntax::symbol::InternedString;
+
+use std::vec;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PathSegmentKind {
+    /// The crate a path starts at, e.g. `std`.
+    Crate,
+    /// A qualified path a path starts at, e.g. `<T as Trait>`.
+    Qualified,
+    /// An impl, e.g. `<impl Trait for T>`.
+    Impl,
+    /// An item, or some other named (or anonymous, e.g. `{{closure}}`) def.
+    Item,
+}
+
+#[derive(Clone, Debug)]
+pub struct PathSegment<'tcx> {
+    pub name: InternedString,
+    pub kind: PathSegmentKind,
+    /// The def this segment is the path of, if any (qualified paths have none).
+    pub def_id: Option<DefId>,
+    /// The generic args printed after this segment (unless not requested).
+    pub args: Vec<Kind<'tcx>>,
+}
+
+pub struct PathSegmentCollector<'a, 'gcx, 'tcx> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    options: PrintOptions,
+    segments: Vec<PathSegment<'tcx>>,
+
+    /// The defs whose paths are being printed, innermost last.
+    def_ids: Vec<DefId>,
+
+    /// Whether the generic args of segments should be collected.
+    include_args: bool,
+}
+
+impl PathSegmentCollector<'a, 'gcx, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>, include_args: bool) -> Self {
+        PathSegmentCollector {
+            tcx,
+            options: PrintOptions::default(),
+            segments: vec![],
+            def_ids: vec![],
+            include_args,
+        }
+    }
+
+    /// Returns the segments collected so far, outermost first.
+    pub fn into_segments(self) -> vec::IntoIter<PathSegment<'tcx>> {
+        self.segments.into_iter()
+    }
+
+    fn push(mut self, name: InternedString, kind: PathSegmentKind) -> Self {
+        let def_id = match kind {
+            PathSegmentKind::Qualified => None,
+            _ => self.def_ids.last().cloned(),
+        };
+        self.segments.push(PathSegment { name, kind, def_id, args: vec![] });
+        self
+    }
+
+    fn push_qualified(
+        self,
+        kind: PathSegmentKind,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Self {
+        let mut name = String::new();
+        let cx = FmtPrinterBuilder::new(self.tcx, Namespace::TypeNS)
+            .options(self.options)
+            .build(&mut name);
+        let _ = match kind {
+            PathSegmentKind::Impl => cx.pretty_path_append_impl(|cx| Ok(cx), self_ty, trait_ref),
+            _ => cx.path_qualified(self_ty, trait_ref),
+        };
+        self.push(InternedString::intern(&name), kind)
+    }
+}
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns the segments of the path of `def_id`, instantiated with `substs`,
+    /// including the generic args after each segment, if `include_args` is set.
+    pub fn path_segments(
+        self,
+        def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+        include_args: bool,
+    ) -> vec::IntoIter<PathSegment<'tcx>> {
+        PathSegmentCollector::new(self, include_args)
+            .print_def_path(def_id, substs)
+            .unwrap()
+            .into_segments()
+    }
+
+    /// Returns the segments of the path of `ty`, which are none for types
+    /// without paths (e.g. references), see `path_segments`.
+    pub fn type_path_segments(
+        self,
+        ty: Ty<'tcx>,
+        include_args: bool,
+    ) -> vec::IntoIter<PathSegment<'tcx>> {
+        match ty.sty {
+            ty::Adt(def, substs) => self.path_segments(def.did, substs, include_args),
+            ty::Foreign(def_id) => self.path_segments(def_id, &[], include_args),
+            ty::FnDef(def_id, substs) |
+            ty::Opaque(def_id, substs) => self.path_segments(def_id, substs, include_args),
+            ty::Closure(def_id, ty::ClosureSubsts { substs }) |
+            ty::Generator(def_id, ty::GeneratorSubsts { substs }, _) => {
+                self.path_segments(def_id, substs, include_args)
+            }
+            ty::Projection(ref data) => {
+                self.path_segments(data.item_def_id, data.substs, include_args)
+            }
+            _ => vec![].into_iter(),
+        }
+    }
+}
+
+impl Printer<'gcx, 'tcx> for PathSegmentCollector<'_, 'gcx, 'tcx> {
+    type Error = !;
+
+    type Path = Self;
+    type Region = Self;
+    type Type = Self;
+    type DynExistential = Self;
+    type Const = Self;
+
+    fn tcx(&'a self) -> TyCtxt<'a, 'gcx, 'tcx> {
+        self.tcx
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.options
+    }
+
+    fn set_print_options(&mut self, options: PrintOptions) {
+        self.options = options;
+    }
+
+    fn print_def_path(
+        mut self,
+        def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        self.def_ids.push(def_id);
+        let mut cx = self.default_print_def_path(def_id, substs)?;
+        cx.def_ids.pop();
+        Ok(cx)
+    }
+
+    // Types, regions and consts only show up in qualified paths and generic
+    // args, which are kept as a whole (see `push_qualified` and `args`).
+
+    fn print_region(
+        self,
+        _region: ty::Region<'_>,
+    ) -> Result<Self::Region, Self::Error> {
+        Ok(self)
+    }
+
+    fn print_type(
+        self,
+        _ty: Ty<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        Ok(self)
+    }
+
+    fn print_fn_sig(
+        self,
+        _sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        Ok(self)
+    }
+
+    fn print_dyn_existential(
+        self,
+        _predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
+    ) -> Result<Self::DynExistential, Self::Error> {
+        Ok(self)
+    }
+
+    fn print_const(
+        self,
+        _ct: &'tcx ty::Const<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        Ok(self)
+    }
+
+    fn path_crate(
+        self,
+        cnum: CrateNum,
+    ) -> Result<Self::Path, Self::Error> {
+        let name = self.tcx.original_crate_name(cnum).as_str();
+        let name = InternedString::intern(&self.options.sanitized_ident(&name));
+        Ok(self.push(name, PathSegmentKind::Crate))
+    }
+    fn path_qualified(
+        self,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        Ok(self.push_qualified(PathSegmentKind::Qualified, self_ty, trait_ref))
+    }
+
+    fn path_append_impl(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        _disambiguated_data: &DisambiguatedDefPathData,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        let cx = print_prefix(self)?;
+        Ok(cx.push_qualified(PathSegmentKind::Impl, self_ty, trait_ref))
+    }
+    fn path_append(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        disambiguated_data: &DisambiguatedDefPathData,
+    ) -> Result<Self::Path, Self::Error> {
+        let cx = print_prefix(self)?;
+
+        // Skip `::{{constructor}}` on tuple/unit structs, like other printers.
+        if let DefPathData::Ctor = disambiguated_data.data {
+            return Ok(cx);
+        }
+
+        let name = disambiguated_data.data.as_interned_str().as_str();
+        let name = InternedString::intern(&cx.options.sanitized_ident(&name));
+        Ok(cx.push(name, PathSegmentKind::Item))
+    }
+    fn path_generic_args(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        args: &[Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        let mut cx = print_prefix(self)?;
+        if cx.include_args {
+            if let Some(segment) = cx.segments.last_mut() {
+                segment.args.extend_from_slice(args);
+            }
+        }
+        Ok(cx)
+    }
+}
@@ -0,0 +1,439 @@
+//! A `Printer` rendering types as S-expressions, for tools (e.g. fuzzers, or
+//! differential testing) needing an unambiguous, easily reparsed type dump,
+//! e.g. `(adt core::option::Option (ty u32))` for `Option<u32>`.
+//!
+//! Every generic arg is tagged with its kind, i.e. `(lt ...)`, `(ty ...)` or
+//! `(ct ...)`, and all of them get printed, even those matching defaults.
+//! Paths are printed as a single atom, e.g. `core::option::Option`, unless
+//! they contain impls, qualified (e.g. `<T as Trait>`) segments, or generic
+//! args (e.g. of the enum a variant belongs to), which get printed as a
+//! `(path ...)` of their segments, e.g. `(path m (impl (adt m::Foo)) new)`.
+
+use crate::hir;
+use crate::hir::def::Namespace;
+use crate::hir::def_id::{CrateNum, DefId};
+use crate::hir::map::{DefPathData, DisambiguatedDefPathData};
+use crate::mir::interpret::ConstValue;
+use crate::ty::{self, Ty, TyCtxt};
+use crate::ty::print::{FmtPrinterBuilder, PrintOptions, PrintedGenericArg, Printer};
+use crate::ty::subst::Kind;
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Sexp {
+    Atom(String),
+    List(Vec<Sexp>),
+}
+
+impl fmt::Display for Sexp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sexp::Atom(atom) => write!(f, "{}", atom),
+            Sexp::List(items) => {
+                write!(f, "(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl Sexp {
+    /// Parses `s`, as printed by `Display`, returning `None` if it's malformed,
+    /// e.g. to check that printed S-expressions round-trip.
+    pub fn parse(s: &str) -> Option<Sexp> {
+        let mut pos = 0;
+        let sexp = parse_at(s, &mut pos)?;
+        if pos == s.len() { Some(sexp) } else { None }
+    }
+}
+
+/// Parses the S-expression starting at `*pos` in `s`, moving `*pos` past it.
+fn parse_at(s: &str, pos: &mut usize) -> Option<Sexp> {
+    let rest = &s[*pos..];
+    if rest.starts_with('(') {
+        *pos += 1;
+        let mut items = vec![];
+        loop {
+            match s[*pos..].chars().next()? {
+                ')' => {
+                    *pos += 1;
+                    return Some(Sexp::List(items));
+                }
+                ' ' if !items.is_empty() => *pos += 1,
+                _ if !items.is_empty() => return None,
+                _ => {}
+            }
+            items.push(parse_at(s, pos)?);
+        }
+    }
+
+    // String literals (see `string`) are atoms, which can contain anything.
+    let len = if rest.starts_with('"') {
+        let mut escaped = false;
+        let end = rest.char_indices().skip(1).find(|&(_, c)| {
+            let is_end = c == '"' && !escaped;
+            escaped = c == '\\' && !escaped;
+            is_end
+        })?.0;
+        end + 1
+    } else {
+        rest.find(|c| c == ' ' || c == '(' || c == ')').unwrap_or(rest.len())
+    };
+    if len == 0 {
+        return None;
+    }
+    *pos += len;
+    Some(Sexp::Atom(rest[..len].to_string()))
+}
+
+fn atom(s: impl ToString) -> Sexp {
+    Sexp::Atom(s.to_string())
+}
+
+/// Builds an atom holding `s` as a (quoted and escaped) string literal.
+fn string(s: &str) -> Sexp {
+    Sexp::Atom(format!("{:?}", s))
+}
+
+/// Builds a list starting with the atom `head`, followed by `items`.
+fn list(head: &str, items: impl IntoIterator<Item = Sexp>) -> Sexp {
+    Sexp::List(Some(atom(head)).into_iter().chain(items).collect())
+}
+
+/// Builds the S-expression of a path from its segments, see the module docs.
+fn path(segments: Vec<Sexp>) -> Sexp {
+    if segments.iter().all(|segment| if let Sexp::Atom(_) = segment { true } else { false }) {
+        let segments: Vec<_> = segments.iter().map(|segment| segment.to_string()).collect();
+        atom(segments.join("::"))
+    } else {
+        list("path", segments)
+    }
+}
+
+fn mutability(mutbl: hir::Mutability) -> Sexp {
+    match mutbl {
+        hir::MutMutable => atom("mut"),
+        hir::MutImmutable => atom("imm"),
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct SexpPrinter<'a, 'gcx, 'tcx> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    options: PrintOptions,
+}
+
+impl SexpPrinter<'a, 'gcx, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>) -> Self {
+        SexpPrinter { tcx, options: PrintOptions::default() }
+    }
+
+    fn print_kinds(self, args: &[Kind<'tcx>]) -> Result<Vec<Sexp>, !> {
+        args.iter().map(|&arg| {
+            Ok(match self.print_generic_arg(arg)? {
+                PrintedGenericArg::Lifetime(r) => list("lt", Some(r)),
+                PrintedGenericArg::Type(ty) => list("ty", Some(ty)),
+                PrintedGenericArg::Const(ct) => list("ct", Some(ct)),
+            })
+        }).collect()
+    }
+
+    /// Prints the path of `def_id` (without generic args), followed by all of
+    /// `substs`, as a list starting with `head`, e.g. `(adt PATH ARGS...)`.
+    fn print_path_of(
+        self,
+        head: &str,
+        def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+    ) -> Result<Sexp, !> {
+        let path = path(self.print_def_path(def_id, &[])?);
+        Ok(list(head, Some(path).into_iter().chain(self.print_kinds(substs)?)))
+    }
+
+    /// Prints `trait_ref` as `(trait PATH ARGS...)`, without its `Self` arg.
+    fn print_trait(self, trait_ref: ty::TraitRef<'tcx>) -> Result<Sexp, !> {
+        self.print_path_of("trait", trait_ref.def_id, &trait_ref.substs[1..])
+    }
+
+    fn print_self_and_trait(
+        self,
+        head: &str,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Sexp, !> {
+        let mut items = vec![self.print_type(self_ty)?];
+        if let Some(trait_ref) = trait_ref {
+            items.push(self.print_trait(trait_ref)?);
+        }
+        Ok(list(head, items))
+    }
+}
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns the S-expression describing `ty`, see `SexpPrinter`.
+    pub fn type_sexp(self, ty: Ty<'tcx>) -> String {
+        SexpPrinter::new(self).print_type(ty).unwrap().to_string()
+    }
+}
+
+impl Printer<'gcx, 'tcx> for SexpPrinter<'_, 'gcx, 'tcx> {
+    type Error = !;
+
+    /// The segments of the path, see `path`.
+    type Path = Vec<Sexp>;
+    type Region = Sexp;
+    type Type = Sexp;
+    type DynExistential = Sexp;
+    type Const = Sexp;
+
+    fn tcx(&'a self) -> TyCtxt<'a, 'gcx, 'tcx> {
+        self.tcx
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.options
+    }
+
+    fn set_print_options(&mut self, options: PrintOptions) {
+        self.options = options;
+    }
+
+    // All generic args get printed, see the module docs.
+    fn should_print_verbose(&self) -> bool {
+        true
+    }
+
+    fn print_region(
+        self,
+        region: ty::Region<'_>,
+    ) -> Result<Self::Region, Self::Error> {
+        let mut name = String::new();
+        let _ = FmtPrinterBuilder::new(self.tcx, Namespace::TypeNS)
+            .options(self.options)
+            .build(&mut name)
+            .print_region(region);
+        if name.is_empty() {
+            name = "'_".to_string();
+        }
+        Ok(atom(name))
+    }
+
+    fn print_type(
+        self,
+        ty: Ty<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        let sexp = match ty.sty {
+            ty::Bool => atom("bool"),
+            ty::Char => atom("char"),
+            ty::Int(t) => atom(t.ty_to_string()),
+            ty::Uint(t) => atom(t.ty_to_string()),
+            ty::Float(t) => atom(t.ty_to_string()),
+            ty::Str => atom("str"),
+            ty::Never => atom("!"),
+
+            ty::Adt(def, substs) => self.print_path_of("adt", def.did, substs)?,
+            ty::Foreign(def_id) => self.print_path_of("foreign", def_id, &[])?,
+            ty::FnDef(def_id, substs) => self.print_path_of("fndef", def_id, substs)?,
+            ty::Closure(def_id, substs) => {
+                self.print_path_of("closure", def_id, substs.substs)?
+            }
+            ty::Generator(def_id, substs, movability) => {
+                let head = match movability {
+                    hir::GeneratorMovability::Static => "static-generator",
+                    hir::GeneratorMovability::Movable => "generator",
+                };
+                self.print_path_of(head, def_id, substs.substs)?
+            }
+            ty::Opaque(def_id, substs) => self.print_path_of("opaque", def_id, substs)?,
+            ty::Projection(ref data) => {
+                self.print_path_of("projection", data.item_def_id, data.substs)?
+            }
+            ty::RawPtr(mt) => list("ptr", vec![mutability(mt.mutbl), self.print_type(mt.ty)?]),
+            ty::Ref(r, ty, mutbl) => list("ref", vec![
+                self.print_region(r)?,
+                mutability(mutbl),
+                self.print_type(ty)?,
+            ]),
+            ty::Tuple(tys) => {
+                let tys = tys.iter().map(|ty| self.print_type(ty.expect_ty()));
+                list("tuple", tys.collect::<Result<Vec<_>, _>>()?)
+            }
+            ty::Array(ty, len) => list("array", vec![self.print_type(ty)?, self.print_const(len)?]),
+            ty::Slice(ty) => list("slice", Some(self.print_type(ty)?)),
+            ty::FnPtr(sig) => {
+                let sig = sig.skip_binder();
+                let mut inputs = sig.inputs().iter()
+                    .map(|&ty| self.print_type(ty))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if sig.c_variadic {
+                    inputs.push(atom("..."));
+                }
+                list("fnptr", vec![
+                    atom(match sig.unsafety {
+                        hir::Unsafety::Unsafe => "unsafe",
+                        hir::Unsafety::Normal => "safe",
+                    }),
+                    string(sig.abi.name()),
+                    list("inputs", inputs),
+                    self.print_type(sig.output())?,
+                ])
+            }
+            ty::Dynamic(data, r) => list("dyn", vec![
+                self.print_dyn_existential(*data.skip_binder())?,
+                self.print_region(r)?,
+            ]),
+            ty::Param(ref param_ty) => {
+                let name = param_ty.name.as_str();
+                let name = atom(self.options.sanitized_ident(&name));
+                list("param", vec![name, atom(param_ty.index)])
+            }
+
+            // These don't have a stable structure worth exposing to tools.
+            ty::Infer(_) |
+            ty::Bound(..) |
+            ty::Placeholder(_) |
+            ty::UnnormalizedProjection(_) |
+            ty::GeneratorWitness(_) |
+            ty::Error => list("other", Some(string(&format!("{:?}", ty)))),
+        };
+        Ok(sexp)
+    }
+
+    fn print_fn_sig(
+        self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        let fn_ptr = self.tcx().mk_fn_ptr(ty::Binder::bind(sig));
+        self.print_type(fn_ptr)
+    }
+
+    fn print_dyn_existential(
+        self,
+        predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
+    ) -> Result<Self::DynExistential, Self::Error> {
+        let predicates = predicates.iter().map(|predicate| match *predicate {
+            // The args of existential trait refs already exclude `Self`.
+            ty::ExistentialPredicate::Trait(trait_ref) => {
+                self.print_path_of("trait", trait_ref.def_id, trait_ref.substs)
+            }
+            ty::ExistentialPredicate::Projection(projection) => {
+                let name = self.tcx.associated_item(projection.item_def_id).ident.as_str();
+                let name = atom(self.options.sanitized_ident(&name));
+                Ok(list("assoc", vec![name, self.print_type(projection.ty)?]))
+            }
+            ty::ExistentialPredicate::AutoTrait(def_id) => {
+                self.print_path_of("auto", def_id, &[])
+            }
+        });
+        Ok(list("bounds", predicates.collect::<Result<Vec<_>, _>>()?))
+    }
+
+    fn print_const(
+        self,
+        ct: &'tcx ty::Const<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        if let ConstValue::Param(param) = ct.val {
+            let name = param.name.as_str();
+            let name = atom(self.options.sanitized_ident(&name));
+            return Ok(list("param", vec![name, atom(param.index)]));
+        }
+        let mut value = String::new();
+        let _ = FmtPrinterBuilder::new(self.tcx, Namespace::ValueNS)
+            .options(self.options)
+            .build(&mut value)
+            .print_const(ct);
+        Ok(list("const", vec![self.print_type(ct.ty)?, string(&value)]))
+    }
+
+    fn path_crate(
+        self,
+        cnum: CrateNum,
+    ) -> Result<Self::Path, Self::Error> {
+        let name = self.tcx.original_crate_name(cnum).as_str();
+        Ok(vec![atom(self.options.sanitized_ident(&name))])
+    }
+    fn path_qualified(
+        self,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        Ok(vec![self.print_self_and_trait("qualified", self_ty, trait_ref)?])
+    }
+
+    fn path_append_impl(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        _disambiguated_data: &DisambiguatedDefPathData,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        let mut segments = print_prefix(self)?;
+        segments.push(self.print_self_and_trait("impl", self_ty, trait_ref)?);
+        Ok(segments)
+    }
+    fn path_append(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        disambiguated_data: &DisambiguatedDefPathData,
+    ) -> Result<Self::Path, Self::Error> {
+        let mut segments = print_prefix(self)?;
+
+        // Skip `::{{constructor}}` on tuple/unit structs.
+        if let DefPathData::Ctor = disambiguated_data.data {
+            return Ok(segments);
+        }
+
+        // Keep e.g. the closures of a function apart, as `{{closure}}#1`.
+        let name = disambiguated_data.data.as_interned_str();
+        let mut name = self.options.sanitized_ident(&name.as_str()).into_owned();
+        if disambiguated_data.disambiguator != 0 {
+            name = format!("{}#{}", name, disambiguated_data.disambiguator);
+        }
+        segments.push(atom(name));
+        Ok(segments)
+    }
+    fn path_generic_args(
+        self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        args: &[Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        let mut segments = print_prefix(self)?;
+        if !args.is_empty() {
+            segments.push(list("args", self.print_kinds(args)?));
+        }
+        Ok(segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sexp;
+
+    #[test]
+    fn test_parse_round_trip() {
+        for &s in &[
+            "(adt core::option::Option (ty u32))",
+            "(ref 'a imm (adt m::S (lt 'a) (ty (param T 0)) (ct (const usize \"3usize\"))))",
+            "(dyn (bounds (trait core::fmt::Debug) (auto core::marker::Send)) 'static)",
+            "(fnptr safe \"Rust\" (inputs u8 ...) !)",
+            "(other \"a \\\"quoted\\\" (string)\")",
+            "(tuple)",
+        ] {
+            assert_eq!(Sexp::parse(s).map(|sexp| sexp.to_string()), Some(s.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        for &s in &["", "(", "(a", "(a))", "a b", "(a )", "( a)", "(a(b))", "\"a"] {
+            assert_eq!(Sexp::parse(s), None);
+        }
+    }
+}
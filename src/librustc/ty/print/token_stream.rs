@@ -0,0 +1,127 @@
+//! Printing types as flat streams of tokens annotated with their nesting
+//! depth, for clients doing their own layout (e.g. wrapping at their width),
+//! e.g. `Vec`@0, `<`@0, `u8`@1, `>`@0 for `Vec<u8>`.
+//!
+//! Tokens are split out of the output of a `FmtPrinter`, so they print just
+//! like it does (and follow the same options), with each token including the
+//! whitespace preceding it, i.e. joining all tokens reproduces that output.
+//! Brackets (`<>`, `()`, `[]` and `{}`) are at the depth they're written at,
+//! which is one less than the depth of the tokens between them.
+
+use crate::hir::def::Namespace;
+use crate::ty::{Ty, TyCtxt};
+use crate::ty::print::{FmtPrinter, Printer};
+
+use std::fmt;
+use std::mem;
+
+/// The punctuation printed as single tokens, e.g. `::` or `->`.
+const MULTI_CHAR_PUNCT: &[&str] = &["::", "->", "..", "...", "/*"];
+
+pub type TokenStreamPrinter<'a, 'gcx, 'tcx> = FmtPrinter<'a, 'gcx, 'tcx, TokenStream>;
+
+/// The `fmt::Write` sink of a `TokenStreamPrinter`, splitting everything
+/// written to it into tokens.
+#[derive(Default)]
+pub struct TokenStream {
+    tokens: Vec<(String, usize)>,
+    depth: usize,
+
+    /// The whitespace written since the last token.
+    space: String,
+    /// The token being written, which may continue in the next write.
+    current: String,
+    /// Whether `current` is a comment, i.e. runs until the next `*/`.
+    in_comment: bool,
+}
+
+impl TokenStream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the tokens of everything written so far, with their depth.
+    pub fn into_tokens(mut self) -> Vec<(String, usize)> {
+        self.flush();
+        self.tokens
+    }
+
+    fn flush(&mut self) {
+        if !self.current.is_empty() {
+            let token = mem::replace(&mut self.space, String::new()) + &self.current;
+            self.tokens.push((token, self.depth));
+            self.current.clear();
+        }
+        self.in_comment = false;
+    }
+
+    fn is_word(&self) -> bool {
+        self.current.chars().last().map_or(false, is_word_char)
+    }
+
+    fn push_char(&mut self, c: char) {
+        if self.in_comment {
+            self.current.push(c);
+            if self.current.ends_with("*/") {
+                self.flush();
+            }
+            return;
+        }
+
+        let mut punct = self.current.clone();
+        punct.push(c);
+        if !self.is_word() && MULTI_CHAR_PUNCT.contains(&&punct[..]) {
+            self.current = punct;
+            self.in_comment = self.current == "/*";
+            return;
+        }
+
+        match c {
+            _ if c.is_whitespace() => {
+                self.flush();
+                self.space.push(c);
+            }
+            '<' | '(' | '[' | '{' => {
+                self.flush();
+                self.current.push(c);
+                self.flush();
+                self.depth += 1;
+            }
+            '>' | ')' | ']' | '}' => {
+                self.flush();
+                // Unmatched closing brackets (e.g. the `>` of a comparison
+                // in a const expression) stay at the outermost depth.
+                self.depth = self.depth.saturating_sub(1);
+                self.current.push(c);
+                self.flush();
+            }
+            _ if is_word_char(c) && self.is_word() => self.current.push(c),
+            _ => {
+                self.flush();
+                self.current.push(c);
+            }
+        }
+    }
+}
+
+/// Whether `c` can be part of a name, lifetime (e.g. `'a`) or literal.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '\''
+}
+
+impl fmt::Write for TokenStream {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            self.push_char(c);
+        }
+        Ok(())
+    }
+}
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns the tokens of `ty`, with their depth, see `TokenStreamPrinter`.
+    pub fn type_token_stream(self, ty: Ty<'tcx>) -> Vec<(String, usize)> {
+        let cx = TokenStreamPrinter::new(self, TokenStream::new(), Namespace::TypeNS);
+        cx.print_type(ty).unwrap().into_fmt().into_tokens()
+    }
+}
@@ -0,0 +1,253 @@
+//! A `Printer` that hashes the structure of a type, instead of printing it,
+//! to produce a short digest identifying it, e.g. for use in file names.
+//! Paths are hashed through their `DefPathHash`, and lifetimes are ignored,
+//! so the digest is the same across compilation sessions (of the same
+//! compiler), and for types only differing in their lifetimes.
+
+use crate::hir;
+use crate::hir::def::Namespace;
+use crate::hir::def_id::{CrateNum, DefId};
+use crate::hir::map::DisambiguatedDefPathData;
+use crate::ty::{self, Ty, TyCtxt};
+use crate::ty::print::{FmtPrinter, Print, Printer};
+use crate::ty::subst::Kind;
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::stable_hasher::StableHasher;
+
+use std::hash::Hash;
+
+pub struct TypeHashPrinter<'a, 'gcx, 'tcx> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    hasher: StableHasher<Fingerprint>,
+}
+
+impl TypeHashPrinter<'a, 'gcx, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>) -> Self {
+        TypeHashPrinter {
+            tcx,
+            hasher: StableHasher::new(),
+        }
+    }
+
+    /// Returns the digest of everything printed so far, as 32 hex digits.
+    pub fn finish(self) -> String {
+        let (hi, lo) = self.hasher.finish().as_value();
+        format!("{:016x}{:016x}", hi, lo)
+    }
+
+    fn hash(mut self, value: impl Hash) -> Self {
+        value.hash(&mut self.hasher);
+        self
+    }
+
+    fn print_kind(self, arg: Kind<'tcx>) -> Result<Self, !> {
+        arg.print(self)
+    }
+
+    fn print_kinds(mut self, args: &[Kind<'tcx>]) -> Result<Self, !> {
+        self = self.hash(args.len());
+        for &arg in args {
+            self = self.print_kind(arg)?;
+        }
+        Ok(self)
+    }
+}
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns a digest identifying `ty` up to its lifetimes, which stays the
+    /// same across compilation sessions, e.g. for naming cached artifacts.
+    pub fn type_hash_str(self, ty: Ty<'tcx>) -> String {
+        let ty = self.erase_regions(&ty);
+        TypeHashPrinter::new(self).print_type(ty).unwrap().finish()
+    }
+}
+
+impl Printer<'gcx, 'tcx> for TypeHashPrinter<'_, 'gcx, 'tcx> {
+    type Error = !;
+
+    type Path = Self;
+    type Region = Self;
+    type Type = Self;
+    type DynExistential = Self;
+    type Const = Self;
+
+    fn tcx(&'a self) -> TyCtxt<'a, 'gcx, 'tcx> {
+        self.tcx
+    }
+
+    fn print_def_path(
+        self,
+        def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        // The `DefPathHash` already identifies the whole path.
+        let def_path_hash = self.tcx.def_path_hash(def_id);
+        self.hash("def").hash(def_path_hash).print_kinds(substs)
+    }
+
+    fn print_region(
+        self,
+        _region: ty::Region<'_>,
+    ) -> Result<Self::Region, Self::Error> {
+        Ok(self.hash("region"))
+    }
+
+    fn print_type(
+        mut self,
+        ty: Ty<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        match ty.sty {
+            ty::Bool => Ok(self.hash("bool")),
+            ty::Char => Ok(self.hash("char")),
+            ty::Int(t) => Ok(self.hash(t.ty_to_string())),
+            ty::Uint(t) => Ok(self.hash(t.ty_to_string())),
+            ty::Float(t) => Ok(self.hash(t.ty_to_string())),
+            ty::Str => Ok(self.hash("str")),
+            ty::Never => Ok(self.hash("!")),
+
+            ty::Adt(def, substs) => self.hash("adt").print_def_path(def.did, substs),
+            ty::Foreign(def_id) => self.hash("foreign").print_def_path(def_id, &[]),
+            ty::FnDef(def_id, substs) => self.hash("fn_def").print_def_path(def_id, substs),
+            ty::Closure(def_id, substs) => {
+                self.hash("closure").print_def_path(def_id, substs.substs)
+            }
+            ty::Generator(def_id, substs, movability) => {
+                self = self.hash("generator").hash(movability == hir::GeneratorMovability::Static);
+                self.print_def_path(def_id, substs.substs)
+            }
+            ty::Opaque(def_id, substs) => self.hash("opaque").print_def_path(def_id, substs),
+            ty::Projection(ref data) => {
+                self.hash("projection").print_def_path(data.item_def_id, data.substs)
+            }
+            ty::RawPtr(mt) => {
+                self.hash("ptr").hash(mt.mutbl == hir::MutMutable).print_type(mt.ty)
+            }
+            ty::Ref(r, ty, mutbl) => {
+                self = self.hash("ref").print_region(r)?;
+                self.hash(mutbl == hir::MutMutable).print_type(ty)
+            }
+            ty::Tuple(tys) => self.hash("tuple").print_kinds(tys),
+            ty::Array(ty, len) => self.hash("array").print_type(ty)?.print_const(len),
+            ty::Slice(ty) => self.hash("slice").print_type(ty),
+            ty::FnPtr(sig) => {
+                let sig = sig.skip_binder();
+                self = self.hash("fn_ptr")
+                    .hash(sig.unsafety == hir::Unsafety::Unsafe)
+                    .hash(sig.abi.name())
+                    .hash(sig.c_variadic)
+                    .hash(sig.inputs_and_output.len());
+                for &ty in sig.inputs_and_output {
+                    self = self.print_type(ty)?;
+                }
+                Ok(self)
+            }
+            ty::Dynamic(data, r) => {
+                self = self.hash("dyn").print_dyn_existential(*data.skip_binder())?;
+                self.print_region(r)
+            }
+            ty::Param(ref param_ty) => {
+                Ok(self.hash("param").hash(param_ty.index).hash(&*param_ty.name.as_str()))
+            }
+
+            // These can't be given a digest that is meaningful across
+            // compilation sessions, but they are distinguished at least.
+            ty::Infer(_) |
+            ty::Bound(..) |
+            ty::Placeholder(_) |
+            ty::UnnormalizedProjection(_) |
+            ty::GeneratorWitness(_) |
+            ty::Error => Ok(self.hash("other").hash(format!("{:?}", ty))),
+        }
+    }
+
+    fn print_fn_sig(
+        self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        let fn_ptr = self.tcx().mk_fn_ptr(ty::Binder::bind(sig));
+        self.print_type(fn_ptr)
+    }
+
+    fn print_dyn_existential(
+        mut self,
+        predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
+    ) -> Result<Self::DynExistential, Self::Error> {
+        self = self.hash(predicates.len());
+        for predicate in predicates {
+            self = match *predicate {
+                ty::ExistentialPredicate::Trait(trait_ref) => {
+                    self.hash("trait").print_def_path(trait_ref.def_id, trait_ref.substs)?
+                }
+                ty::ExistentialPredicate::Projection(projection) => {
+                    self = self.hash("projection")
+                        .print_def_path(projection.item_def_id, projection.substs)?;
+                    self.print_type(projection.ty)?
+                }
+                ty::ExistentialPredicate::AutoTrait(def_id) => {
+                    self.hash("auto_trait").print_def_path(def_id, &[])?
+                }
+            };
+        }
+        Ok(self)
+    }
+
+    fn print_const(
+        mut self,
+        ct: &'tcx ty::Const<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        let mut value = String::new();
+        let _ = FmtPrinter::new(self.tcx, &mut value, Namespace::ValueNS).print_const(ct);
+        self = self.hash("const").print_type(ct.ty)?;
+        Ok(self.hash(value))
+    }
+
+    // Paths are hashed by `print_def_path` as a whole, so these are
+    // only used by callers printing partial paths themselves.
+
+    fn path_crate(
+        self,
+        cnum: CrateNum,
+    ) -> Result<Self::Path, Self::Error> {
+        let disambiguator = self.tcx.crate_disambiguator(cnum);
+        Ok(self.hash("crate").hash(disambiguator.to_fingerprint()))
+    }
+    fn path_qualified(
+        mut self,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self = self.hash("qualified").print_type(self_ty)?;
+        match trait_ref {
+            Some(trait_ref) => self.print_def_path(trait_ref.def_id, trait_ref.substs),
+            None => Ok(self.hash("inherent")),
+        }
+    }
+
+    fn path_append_impl(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        _disambiguated_data: &DisambiguatedDefPathData,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?;
+        self.path_qualified(self_ty, trait_ref)
+    }
+    fn path_append(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        disambiguated_data: &DisambiguatedDefPathData,
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?;
+        let name = disambiguated_data.data.as_interned_str();
+        Ok(self.hash(&*name.as_str()).hash(disambiguated_data.disambiguator))
+    }
+    fn path_generic_args(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        args: &[Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?;
+        self.print_kinds(args)
+    }
+}
@@ -0,0 +1,503 @@
+//! A `Printer` producing YAML documents describing "type-system entities",
+//! meant to be read (and commented on) by humans, e.g. `Option<&'a u32>`:
+//!
+//! ```text
+//! kind: adt
+//! path:
+//!   - core
+//!   - option
+//!   - name: Option
+//!     args:
+//!       - kind: ref
+//!         region: "'a"
+//!         mutable: false
+//!         pointee:
+//!           kind: primitive
+//!           name: u32
+//! ```
+//!
+//! The document is built as a tree of `Node`s first, and only rendered
+//! at the very end, so that nesting never has to be tracked while printing.
+
+use crate::hir;
+use crate::hir::def::Namespace;
+use crate::hir::def_id::{CrateNum, DefId};
+use crate::hir::map::{DefPathData, DisambiguatedDefPathData};
+use crate::ty::{self, Ty, TyCtxt};
+use crate::ty::print::{FmtPrinterBuilder, Print, PrintOptions, Printer};
+use crate::ty::subst::Kind;
+
+use std::fmt::{self, Write};
+
+/// A YAML value, with scalars already quoted (if necessary).
+#[derive(Clone, Debug)]
+pub enum Node {
+    Scalar(String),
+    Seq(Vec<Node>),
+    Map(Vec<(&'static str, Node)>),
+}
+
+impl Node {
+    /// Returns a string scalar, quoted unless it's unambiguously a plain string.
+    fn string(s: &str) -> Node {
+        let plain = !s.is_empty() &&
+            s.chars().all(|c| c.is_alphanumeric() || c == '_') &&
+            !s.starts_with(|c: char| c.is_ascii_digit()) &&
+            !["true", "false", "null", "y", "n", "yes", "no", "on", "off"]
+                .contains(&&*s.to_lowercase());
+        if plain {
+            return Node::Scalar(s.to_string());
+        }
+
+        let mut quoted = String::with_capacity(s.len() + 2);
+        quoted.push('"');
+        for c in s.chars() {
+            match c {
+                '"' | '\\' => {
+                    quoted.push('\\');
+                    quoted.push(c);
+                }
+                '\n' => quoted.push_str("\\n"),
+                c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+                c => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+        Node::Scalar(quoted)
+    }
+
+    fn display(value: impl fmt::Display) -> Node {
+        Node::Scalar(value.to_string())
+    }
+
+    fn is_block(&self) -> bool {
+        match *self {
+            Node::Scalar(_) => false,
+            Node::Seq(ref items) => !items.is_empty(),
+            Node::Map(ref entries) => !entries.is_empty(),
+        }
+    }
+
+    /// Renders this node as a block at the given indentation, with each line
+    /// (including the last) ending in a newline. Must only be called on nodes
+    /// for which `is_block` returns `true`.
+    fn render_block(&self, out: &mut String, indent: usize) {
+        match *self {
+            Node::Scalar(_) => bug!("YamlPrinter: rendering a scalar as a block"),
+            Node::Seq(ref items) => {
+                for item in items {
+                    if item.is_block() {
+                        // Render the item one level deeper, then put the
+                        // `- ` in the indentation of its first line.
+                        let start = out.len();
+                        item.render_block(out, indent + 2);
+                        out.replace_range(start..start + indent + 2,
+                                          &format!("{:1$}- ", "", indent));
+                    } else {
+                        let _ = writeln!(out, "{:1$}- {2}", "", indent, item.render_flow());
+                    }
+                }
+            }
+            Node::Map(ref entries) => {
+                for (key, value) in entries {
+                    if value.is_block() {
+                        let _ = writeln!(out, "{:1$}{2}:", "", indent, key);
+                        value.render_block(out, indent + 2);
+                    } else {
+                        let _ = writeln!(out, "{:1$}{2}: {3}", "", indent, key,
+                                         value.render_flow());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Renders a non-block node (a scalar, `[]` or `{}`) inline.
+    fn render_flow(&self) -> &str {
+        match *self {
+            Node::Scalar(ref s) => s,
+            Node::Seq(_) => "[]",
+            Node::Map(_) => "{}",
+        }
+    }
+
+    /// Renders this node as a whole YAML document.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        if self.is_block() {
+            self.render_block(&mut out, 0);
+        } else {
+            out.push_str(self.render_flow());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl TyCtxt<'_, '_, 'tcx> {
+    /// Returns the YAML document describing `ty`, see `YamlPrinter`.
+    pub fn type_yaml(self, ty: Ty<'tcx>) -> String {
+        YamlPrinter::new(self).print_type(ty).unwrap().into_node().render()
+    }
+}
+
+pub struct YamlPrinter<'a, 'gcx, 'tcx> {
+    tcx: TyCtxt<'a, 'gcx, 'tcx>,
+    options: PrintOptions,
+
+    /// The node for the last thing printed.
+    last: Option<Node>,
+}
+
+impl YamlPrinter<'a, 'gcx, 'tcx> {
+    pub fn new(tcx: TyCtxt<'a, 'gcx, 'tcx>) -> Self {
+        YamlPrinter {
+            tcx,
+            options: PrintOptions::default(),
+            last: None,
+        }
+    }
+
+    /// Returns the node for the last thing printed, which after e.g.
+    /// a `print_type` call describes the whole type.
+    pub fn into_node(self) -> Node {
+        self.last.expect("YamlPrinter: nothing was printed")
+    }
+
+    fn take(&mut self) -> Node {
+        self.last.take().expect("YamlPrinter: no node was printed")
+    }
+
+    fn finish(mut self, kind: &str, mut entries: Vec<(&'static str, Node)>) -> Self {
+        entries.insert(0, ("kind", Node::string(kind)));
+        self.last = Some(Node::Map(entries));
+        self
+    }
+
+    fn print_seq<T>(
+        mut self,
+        elems: impl Iterator<Item = T>,
+        mut print: impl FnMut(Self, T) -> Result<Self, !>,
+    ) -> Result<(Self, Node), !> {
+        let mut nodes = vec![];
+        for elem in elems {
+            self = print(self, elem)?;
+            nodes.push(self.take());
+        }
+        Ok((self, Node::Seq(nodes)))
+    }
+
+    fn print_kind(self, arg: Kind<'tcx>) -> Result<Self, !> {
+        arg.print(self)
+    }
+
+    fn print_opt_trait_ref(
+        mut self,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<(Self, Option<Node>), !> {
+        match trait_ref {
+            Some(trait_ref) => {
+                self = self.print_def_path(trait_ref.def_id, trait_ref.substs)?;
+                let trait_ref = self.take();
+                Ok((self, Some(trait_ref)))
+            }
+            None => Ok((self, None)),
+        }
+    }
+
+    /// Returns the segments of the path printed last, to append to them.
+    fn take_segments(&mut self) -> Vec<Node> {
+        match self.take() {
+            Node::Seq(segments) => segments,
+            node => bug!("YamlPrinter: expected a path, found {:?}", node),
+        }
+    }
+
+    fn print_path_of(
+        mut self,
+        kind: &str,
+        def_id: DefId,
+        substs: &'tcx [Kind<'tcx>],
+    ) -> Result<Self, !> {
+        self = self.print_def_path(def_id, substs)?;
+        let path = self.take();
+        Ok(self.finish(kind, vec![("path", path)]))
+    }
+}
+
+impl Printer<'gcx, 'tcx> for YamlPrinter<'_, 'gcx, 'tcx> {
+    type Error = !;
+
+    type Path = Self;
+    type Region = Self;
+    type Type = Self;
+    type DynExistential = Self;
+    type Const = Self;
+
+    fn tcx(&'a self) -> TyCtxt<'a, 'gcx, 'tcx> {
+        self.tcx
+    }
+
+    fn print_options(&self) -> PrintOptions {
+        self.options
+    }
+
+    fn set_print_options(&mut self, options: PrintOptions) {
+        self.options = options;
+    }
+
+    fn print_region(
+        mut self,
+        region: ty::Region<'_>,
+    ) -> Result<Self::Region, Self::Error> {
+        let mut name = String::new();
+        let _ = FmtPrinterBuilder::new(self.tcx, Namespace::TypeNS)
+            .options(self.options)
+            .build(&mut name)
+            .print_region(region);
+        self.last = Some(Node::string(&name));
+        Ok(self)
+    }
+
+    fn print_type(
+        mut self,
+        ty: Ty<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        let primitive = match ty.sty {
+            ty::Bool => Some("bool"),
+            ty::Char => Some("char"),
+            ty::Int(t) => Some(t.ty_to_string()),
+            ty::Uint(t) => Some(t.ty_to_string()),
+            ty::Float(t) => Some(t.ty_to_string()),
+            ty::Str => Some("str"),
+            ty::Never => Some("!"),
+            _ => None,
+        };
+        if let Some(name) = primitive {
+            return Ok(self.finish("primitive", vec![("name", Node::string(name))]));
+        }
+
+        match ty.sty {
+            ty::Adt(def, substs) => self.print_path_of("adt", def.did, substs),
+            ty::Foreign(def_id) => self.print_path_of("foreign", def_id, &[]),
+            ty::FnDef(def_id, substs) => self.print_path_of("fn_def", def_id, substs),
+            ty::Closure(def_id, substs) => self.print_path_of("closure", def_id, substs.substs),
+            ty::Generator(def_id, substs, _) => {
+                self.print_path_of("generator", def_id, substs.substs)
+            }
+            ty::Opaque(def_id, substs) => self.print_path_of("opaque", def_id, substs),
+            ty::Projection(ref data) => {
+                self.print_path_of("projection", data.item_def_id, data.substs)
+            }
+            ty::RawPtr(mt) => {
+                self = self.print_type(mt.ty)?;
+                let pointee = self.take();
+                Ok(self.finish("ptr", vec![
+                    ("mutable", Node::display(mt.mutbl == hir::MutMutable)),
+                    ("pointee", pointee),
+                ]))
+            }
+            ty::Ref(r, ty, mutbl) => {
+                self = self.print_region(r)?;
+                let region = self.take();
+                self = self.print_type(ty)?;
+                let pointee = self.take();
+                Ok(self.finish("ref", vec![
+                    ("region", region),
+                    ("mutable", Node::display(mutbl == hir::MutMutable)),
+                    ("pointee", pointee),
+                ]))
+            }
+            ty::Tuple(tys) => {
+                let (cx, elems) = self.print_seq(tys.iter(), |cx, k| cx.print_kind(*k))?;
+                Ok(cx.finish("tuple", vec![("elements", elems)]))
+            }
+            ty::Array(ty, len) => {
+                self = self.print_type(ty)?;
+                let elem = self.take();
+                self = self.print_const(len)?;
+                let len = self.take();
+                Ok(self.finish("array", vec![("element", elem), ("len", len)]))
+            }
+            ty::Slice(ty) => {
+                self = self.print_type(ty)?;
+                let elem = self.take();
+                Ok(self.finish("slice", vec![("element", elem)]))
+            }
+            ty::FnPtr(sig) => {
+                let sig = sig.skip_binder();
+                let (mut cx, inputs) = self.print_seq(
+                    sig.inputs().iter(),
+                    |cx, &ty| cx.print_type(ty),
+                )?;
+                cx = cx.print_type(sig.output())?;
+                let output = cx.take();
+                Ok(cx.finish("fn_ptr", vec![("inputs", inputs), ("output", output)]))
+            }
+            ty::Dynamic(data, r) => {
+                self = self.print_dyn_existential(*data.skip_binder())?;
+                let bounds = self.take();
+                self = self.print_region(r)?;
+                let region = self.take();
+                Ok(self.finish("dyn", vec![("bounds", bounds), ("region", region)]))
+            }
+            ty::Param(ref param_ty) => {
+                let name = Node::string(&self.options.sanitized_ident(&param_ty.name.as_str()));
+                Ok(self.finish("param", vec![
+                    ("name", name),
+                    ("index", Node::display(param_ty.index)),
+                ]))
+            }
+
+            // These don't have a stable structure worth exposing to reviewers.
+            ty::Infer(_) |
+            ty::Bound(..) |
+            ty::Placeholder(_) |
+            ty::UnnormalizedProjection(_) |
+            ty::GeneratorWitness(_) |
+            ty::Error => Ok(self.finish("other", vec![
+                ("debug", Node::string(&format!("{:?}", ty))),
+            ])),
+
+            ty::Bool | ty::Char | ty::Int(_) | ty::Uint(_) |
+            ty::Float(_) | ty::Str | ty::Never => bug!("primitive `{:?}` not handled", ty),
+        }
+    }
+
+    fn print_fn_sig(
+        self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        let fn_ptr = self.tcx().mk_fn_ptr(ty::Binder::bind(sig));
+        self.print_type(fn_ptr)
+    }
+
+    fn print_dyn_existential(
+        self,
+        predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
+    ) -> Result<Self::DynExistential, Self::Error> {
+        let (mut cx, predicates) = self.print_seq(predicates.iter(), |mut cx, predicate| {
+            match *predicate {
+                ty::ExistentialPredicate::Trait(trait_ref) => {
+                    // Use a type that can't appear in defaults of type parameters.
+                    let dummy_self = cx.tcx.mk_ty_infer(ty::FreshTy(0));
+                    let trait_ref = trait_ref.with_self_ty(cx.tcx, dummy_self);
+                    cx.print_path_of("trait", trait_ref.def_id, trait_ref.substs)
+                }
+                ty::ExistentialPredicate::Projection(projection) => {
+                    let name = cx.tcx.associated_item(projection.item_def_id).ident;
+                    cx = cx.print_type(projection.ty)?;
+                    let ty = cx.take();
+                    let name = Node::string(&cx.options.sanitized_ident(&name.as_str()));
+                    Ok(cx.finish("projection", vec![
+                        ("name", name),
+                        ("ty", ty),
+                    ]))
+                }
+                ty::ExistentialPredicate::AutoTrait(def_id) => {
+                    cx.print_path_of("auto_trait", def_id, &[])
+                }
+            }
+        })?;
+        cx.last = Some(predicates);
+        Ok(cx)
+    }
+
+    fn print_const(
+        mut self,
+        ct: &'tcx ty::Const<'tcx>,
+    ) -> Result<Self::Const, Self::Error> {
+        let mut value = String::new();
+        let _ = FmtPrinterBuilder::new(self.tcx, Namespace::ValueNS)
+            .options(self.options)
+            .build(&mut value)
+            .print_const(ct);
+        self = self.print_type(ct.ty)?;
+        let ty = self.take();
+        Ok(self.finish("const", vec![("ty", ty), ("value", Node::string(&value))]))
+    }
+
+    fn path_crate(
+        mut self,
+        cnum: CrateNum,
+    ) -> Result<Self::Path, Self::Error> {
+        let name = self.tcx.original_crate_name(cnum).as_str();
+        let name = self.options.sanitized_ident(&name);
+        self.last = Some(Node::Seq(vec![Node::string(&name)]));
+        Ok(self)
+    }
+    fn path_qualified(
+        mut self,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self = self.print_type(self_ty)?;
+        let mut entries = vec![("self", self.take())];
+        let (mut cx, trait_ref) = self.print_opt_trait_ref(trait_ref)?;
+        entries.extend(trait_ref.map(|trait_ref| ("trait", trait_ref)));
+        cx.last = Some(Node::Seq(vec![Node::Map(entries)]));
+        Ok(cx)
+    }
+
+    fn path_append_impl(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        _disambiguated_data: &DisambiguatedDefPathData,
+        self_ty: Ty<'tcx>,
+        trait_ref: Option<ty::TraitRef<'tcx>>,
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?;
+        let mut segments = self.take_segments();
+        self = self.print_type(self_ty)?;
+        let mut entries = vec![("impl_for", self.take())];
+        let (mut cx, trait_ref) = self.print_opt_trait_ref(trait_ref)?;
+        entries.extend(trait_ref.map(|trait_ref| ("trait", trait_ref)));
+        segments.push(Node::Map(entries));
+        cx.last = Some(Node::Seq(segments));
+        Ok(cx)
+    }
+    fn path_append(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        disambiguated_data: &DisambiguatedDefPathData,
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?;
+
+        // Skip `::{{constructor}}` on tuple/unit structs.
+        if let DefPathData::Ctor = disambiguated_data.data {
+            return Ok(self);
+        }
+
+        let mut segments = self.take_segments();
+        let name = disambiguated_data.data.as_interned_str();
+        segments.push(Node::string(&self.options.sanitized_ident(&name.as_str())));
+        self.last = Some(Node::Seq(segments));
+        Ok(self)
+    }
+    fn path_generic_args(
+        mut self,
+        print_prefix: impl FnOnce(Self) -> Result<Self::Path, Self::Error>,
+        args: &[Kind<'tcx>],
+    ) -> Result<Self::Path, Self::Error> {
+        self = print_prefix(self)?;
+        if args.is_empty() {
+            return Ok(self);
+        }
+
+        let mut segments = self.take_segments();
+        let (mut cx, args) = self.print_seq(args.iter(), |cx, &arg| cx.print_kind(arg))?;
+
+        // Attach the arguments to the last segment, as `name` and `args`.
+        let segment = match segments.pop() {
+            Some(Node::Map(mut entries)) => {
+                entries.push(("args", args));
+                Node::Map(entries)
+            }
+            Some(name) => Node::Map(vec![("name", name), ("args", args)]),
+            None => Node::Map(vec![("args", args)]),
+        };
+        segments.push(segment);
+        cx.last = Some(Node::Seq(segments));
+        Ok(cx)
+    }
+}
@@ -87,12 +87,12 @@
 //! virtually impossible. Thus, symbol hash generation exclusively relies on
 //! DefPaths which are much more robust in the face of changes to the code base.
 
-use rustc::hir::def_id::LOCAL_CRATE;
+use rustc::hir::def_id::{DefId, LOCAL_CRATE};
 use rustc::hir::Node;
 use rustc::hir::CodegenFnAttrFlags;
 use rustc::session::config::SymbolManglingVersion;
 use rustc::ty::query::Providers;
-use rustc::ty::{self, TyCtxt, Instance};
+use rustc::ty::{self, Ty, TyCtxt, Instance};
 use rustc::mir::mono::{MonoItem, InstantiationMode};
 
 use syntax_pos::symbol::InternedString;
@@ -112,6 +112,25 @@ pub fn provide(providers: &mut Providers<'_>) {
     };
 }
 
+/// Returns the path of `def_id` as it appears in the (demangled) symbols
+/// shown in backtraces, e.g. `<alloc::vec::Vec<T> as core::ops::drop::Drop>::drop`,
+/// assuming the legacy symbol mangling scheme, which is the default.
+///
+/// Frames of inlined functions show up under this path too, as backtraces
+/// resolve them through debuginfo, to the linkage name of the function that
+/// got inlined, not to a synthetic symbol. The only symbols not covered are
+/// those of shims, which have no path of their own, e.g. a vtable shim is
+/// shown as the path of its method followed by `{{vtable-shim}}`.
+pub fn backtrace_path_str(tcx: TyCtxt<'_, 'tcx, 'tcx>, def_id: DefId) -> String {
+    legacy::demangled_path(tcx, def_id)
+}
+
+/// Returns `ty` as it appears in the (demangled) symbols shown in
+/// backtraces, see `backtrace_path_str`.
+pub fn backtrace_type_str(tcx: TyCtxt<'_, 'tcx, 'tcx>, ty: Ty<'tcx>) -> String {
+    legacy::demangled_type(tcx, ty)
+}
+
 fn symbol_name(tcx: TyCtxt<'_, 'tcx, 'tcx>, instance: Instance<'tcx>) -> InternedString {
     let def_id = instance.def_id();
     let substs = instance.substs;
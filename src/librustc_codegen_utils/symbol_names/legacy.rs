@@ -1,4 +1,4 @@
-use rustc::hir::def_id::CrateNum;
+use rustc::hir::def_id::{CrateNum, DefId};
 use rustc::hir::map::{DefPathData, DisambiguatedDefPathData};
 use rustc::ich::NodeIdHashingMode;
 use rustc::mir::interpret::{ConstValue, Scalar};
@@ -68,6 +68,26 @@ pub(super) fn mangle(
     printer.path.finish(hash)
 }
 
+/// Returns the symbol path of `def_id` the way it appears in backtraces,
+/// i.e. demangled, and without the trailing hash (as printed by `{:#}`).
+pub(super) fn demangled_path(tcx: TyCtxt<'_, 'tcx, 'tcx>, def_id: DefId) -> String {
+    SymbolPrinter {
+        tcx,
+        path: SymbolPath::new(),
+        keep_within_component: false,
+    }.print_def_path(def_id, &[]).unwrap().path.finish_demangled()
+}
+
+/// Returns `ty` the way it appears in backtraces, e.g. in the `<T as Trait>`
+/// component of the demangled symbol of a trait method's implementation.
+pub(super) fn demangled_type(tcx: TyCtxt<'_, 'tcx, 'tcx>, ty: Ty<'tcx>) -> String {
+    SymbolPrinter {
+        tcx,
+        path: SymbolPath::new(),
+        keep_within_component: true,
+    }.print_type(tcx.erase_regions(&ty)).unwrap().path.finish_demangled()
+}
+
 fn get_symbol_hash<'a, 'tcx>(
     tcx: TyCtxt<'a, 'tcx, 'tcx>,
 
@@ -152,6 +172,11 @@ fn get_symbol_hash<'a, 'tcx>(
 struct SymbolPath {
     result: String,
     temp_buf: String,
+
+    /// The path as it will be demangled (e.g. in backtraces), which is
+    /// what's written to `temp_buf`, without the need for escaping.
+    demangled: String,
+    demangled_temp_buf: String,
 }
 
 impl SymbolPath {
@@ -159,6 +184,8 @@ impl SymbolPath {
         let mut result = SymbolPath {
             result: String::with_capacity(64),
             temp_buf: String::with_capacity(16),
+            demangled: String::new(),
+            demangled_temp_buf: String::new(),
         };
         result.result.push_str("_ZN"); // _Z == Begin name-sequence, N == nested
         result
@@ -168,9 +195,20 @@ impl SymbolPath {
         if !self.temp_buf.is_empty() {
             let _ = write!(self.result, "{}{}", self.temp_buf.len(), self.temp_buf);
             self.temp_buf.clear();
+
+            if !self.demangled.is_empty() {
+                self.demangled.push_str("::");
+            }
+            self.demangled.push_str(&self.demangled_temp_buf);
+            self.demangled_temp_buf.clear();
         }
     }
 
+    fn finish_demangled(mut self) -> String {
+        self.finalize_pending_component();
+        self.demangled
+    }
+
     fn finish(mut self, hash: u64) -> String {
         self.finalize_pending_component();
         // E = end name-sequence
@@ -232,6 +270,13 @@ impl Printer<'tcx, 'tcx> for SymbolPrinter<'_, 'tcx> {
         }
     }
 
+    fn print_fn_sig(
+        self,
+        sig: ty::FnSig<'tcx>,
+    ) -> Result<Self::Type, Self::Error> {
+        self.pretty_print_fn_sig(sig)
+    }
+
     fn print_dyn_existential(
         mut self,
         predicates: &'tcx ty::List<ty::ExistentialPredicate<'tcx>>,
@@ -408,6 +453,15 @@ impl fmt::Write for SymbolPrinter<'_, '_> {
         // NVPTX assembly has more strict naming rules than gas, so additionally, dots
         // are replaced with '$' there.
 
+        // Demangling undoes the escaping, except for `-`, which is
+        // indistinguishable from the `.` replacing it (see below).
+        for c in s.chars() {
+            self.path.demangled_temp_buf.push(match c {
+                '-' => '.',
+                c => c,
+            });
+        }
+
         for c in s.chars() {
             if self.path.temp_buf.is_empty() {
                 match c {
@@ -1,15 +1,22 @@
 //! Walks the crate looking for items/impl-items/trait-items that have
-//! either a `rustc_symbol_name` or `rustc_def_path` attribute and
-//! generates an error giving, respectively, the symbol name or
-//! def-path. This is used for unit testing the code that generates
-//! paths etc in all kinds of annoying scenarios.
+//! either a `rustc_symbol_name`, `rustc_def_path` or `rustc_backtrace_path`
+//! attribute and generates an error giving, respectively, the symbol name,
+//! def-path or backtrace path (for the item and the closures in its body).
+//! This is used for unit testing the code that generates paths etc in all
+//! kinds of annoying scenarios.
 
 use rustc::hir;
-use rustc::ty::{TyCtxt, Instance};
+use rustc::hir::intravisit::{self, NestedVisitorMap, Visitor};
+use rustc::session::config::SymbolManglingVersion;
+use rustc::ty::{self, TyCtxt, Instance};
+use syntax::ast::Attribute;
 use syntax::symbol::{Symbol, sym};
 
+use crate::symbol_names::backtrace_path_str;
+
 const SYMBOL_NAME: Symbol = sym::rustc_symbol_name;
 const DEF_PATH: Symbol = sym::rustc_def_path;
+const BACKTRACE_PATH: Symbol = sym::rustc_backtrace_path;
 
 pub fn report_symbol_names<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
     // if the `rustc_attrs` feature is not enabled, then the
@@ -47,6 +54,22 @@ impl<'a, 'tcx> SymbolNamesTest<'a, 'tcx> {
             } else if attr.check_name(DEF_PATH) {
                 let path = tcx.def_path_str(def_id);
                 tcx.sess.span_err(attr.span, &format!("def-path({})", path));
+            } else if attr.check_name(BACKTRACE_PATH) {
+                // for now, can only use on monomorphic names
+                self.report_backtrace_path(attr, Instance::mono(tcx, def_id));
+                let body_id = tcx.hir().maybe_body_owned_by_by_hir_id(hir_id);
+                if let Some(body_id) = body_id {
+                    let mut finder = ClosureFinder { tcx, closures: vec![] };
+                    finder.visit_body(tcx.hir().body(body_id));
+                    let tables = tcx.typeck_tables_of(def_id);
+                    for closure_hir_id in finder.closures {
+                        if let ty::Closure(closure_def_id, substs) =
+                            tables.node_type(closure_hir_id).sty {
+                            let instance = Instance::new(closure_def_id, substs.substs);
+                            self.report_backtrace_path(attr, instance);
+                        }
+                    }
+                }
             }
 
             // (*) The formatting of `tag({})` is chosen so that tests can elect
@@ -56,6 +79,29 @@ impl<'a, 'tcx> SymbolNamesTest<'a, 'tcx> {
     }
 }
 
+impl<'a, 'tcx> SymbolNamesTest<'a, 'tcx> {
+    /// Reports the backtrace path of `instance`, and whether it differs from
+    /// its demangled (legacy) symbol name, as printed by `{:#}`.
+    fn report_backtrace_path(&mut self, attr: &Attribute, instance: Instance<'tcx>) {
+        let tcx = self.tcx;
+        let path = backtrace_path_str(tcx, instance.def_id());
+        tcx.sess.span_err(attr.span, &format!("backtrace-path({})", path));
+        if tcx.sess.opts.debugging_opts.symbol_mangling_version != SymbolManglingVersion::Legacy {
+            return;
+        }
+        let mangled = tcx.symbol_name(instance);
+        if let Ok(demangling) = rustc_demangle::try_demangle(&mangled.as_str()) {
+            let demangled = format!("{:#}", demangling);
+            if demangled != path {
+                tcx.sess.span_err(attr.span, &format!(
+                    "backtrace-path differs from demangling-alt({})",
+                    demangled,
+                ));
+            }
+        }
+    }
+}
+
 impl<'a, 'tcx> hir::itemlikevisit::ItemLikeVisitor<'tcx> for SymbolNamesTest<'a, 'tcx> {
     fn visit_item(&mut self, item: &'tcx hir::Item) {
         self.process_attrs(item.hir_id);
@@ -69,3 +115,22 @@ impl<'a, 'tcx> hir::itemlikevisit::ItemLikeVisitor<'tcx> for SymbolNamesTest<'a,
         self.process_attrs(impl_item.hir_id);
     }
 }
+
+/// Collects the closures (but not the generators) in a body, in source order.
+struct ClosureFinder<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    closures: Vec<hir::HirId>,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for ClosureFinder<'a, 'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'tcx> {
+        NestedVisitorMap::OnlyBodies(&self.tcx.hir())
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr) {
+        if let hir::ExprKind::Closure(.., None) = expr.node {
+            self.closures.push(expr.hir_id);
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
@@ -33,6 +33,7 @@ use rustc_metadata::creader::CrateLoader;
 use rustc_metadata::cstore::{self, CStore};
 use rustc_mir as mir;
 use rustc_passes::{self, ast_validation, hir_stats, loops, rvalue_promotion, layout_test};
+use rustc_passes::print_type_test;
 use rustc_plugin as plugin;
 use rustc_plugin::registry::Registry;
 use rustc_privacy;
@@ -957,6 +958,8 @@ fn analysis<'tcx>(
 
     time(sess, "layout testing", || layout_test::test_layout(tcx));
 
+    time(sess, "type printing testing", || print_type_test::test_print_type(tcx));
+
     // Avoid overwhelming user with errors if borrow checking failed.
     // I'm not sure how helpful this is, to be honest, but it avoids a
     // lot of annoying errors in the compile-fail tests (basically,
@@ -73,6 +73,10 @@ impl<'tcx> Printer<'tcx, 'tcx> for AbsolutePathPrinter<'_, 'tcx> {
         }
     }
 
+    fn print_fn_sig(self, sig: ty::FnSig<'tcx>) -> Result<Self::Type, Self::Error> {
+        self.pretty_print_fn_sig(sig)
+    }
+
     fn print_const(
         self,
         _: &'tcx ty::Const<'tcx>,
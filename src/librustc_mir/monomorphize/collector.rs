@@ -489,10 +489,16 @@ fn check_type_length_limit<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
             // An iterator of all byte positions including the end of the string.
             let positions = || s.char_indices().map(|(i, _)| i).chain(iter::once(s.len()));
 
+            let before = &s[..positions().nth(before).unwrap_or(s.len())];
+            let after = &s[positions().rev().nth(after).unwrap_or(0)..];
+            let omitted = s.chars().count().saturating_sub(
+                before.chars().count() + after.chars().count(),
+            );
             let shrunk = format!(
-                "{before}...{after}",
-                before = &s[..positions().nth(before).unwrap_or(s.len())],
-                after = &s[positions().rev().nth(after).unwrap_or(0)..],
+                "{before}{elision}{after}",
+                before = before,
+                elision = ty::print::PrintOptions::default().elision_marker(omitted, "char"),
+                after = after,
             );
 
             // Only use the shrunk version if it's really shorter.
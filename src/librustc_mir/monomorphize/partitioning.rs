@@ -673,7 +673,7 @@ fn characteristic_def_id_of_mono_item<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
             if tcx.trait_of_item(def_id).is_some() {
                 let self_ty = instance.substs.type_at(0);
                 // This is an implementation of a trait method.
-                return characteristic_def_id_of_type(self_ty).or(Some(def_id));
+                return characteristic_def_id_of_type(tcx, self_ty).or(Some(def_id));
             }
 
             if let Some(impl_def_id) = tcx.impl_of_method(def_id) {
@@ -684,7 +684,7 @@ fn characteristic_def_id_of_mono_item<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>,
                     ty::ParamEnv::reveal_all(),
                     &tcx.type_of(impl_def_id),
                 );
-                if let Some(def_id) = characteristic_def_id_of_type(impl_self_ty) {
+                if let Some(def_id) = characteristic_def_id_of_type(tcx, impl_self_ty) {
                     return Some(def_id);
                 }
             }
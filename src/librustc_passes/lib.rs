@@ -27,6 +27,7 @@ pub mod rvalue_promotion;
 pub mod hir_stats;
 pub mod layout_test;
 pub mod loops;
+pub mod print_type_test;
 
 __build_diagnostic_array! { librustc_passes, DIAGNOSTICS }
 
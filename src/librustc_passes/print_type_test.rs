@@ -0,0 +1,906 @@
+//! Walks the crate looking for items (including trait items, impl items and
+//! enum variants) that have a `rustc_print_type` attribute, and generates an
+//! error giving what `FmtPrinter` prints for them, e.g. `print-type(Vec<u8>)`.
+//! This is used for unit testing the printing options, which are listed in
+//! the attribute, e.g. `#[rustc_print_type(tuple_indices)]`, with a bare
+//! `#[rustc_print_type]` printing with the default ones.
+//!
+//! Type aliases and functions are printed by their type, consts by their
+//! (unevaluated) value, and everything else by its path. The options are
+//! the names of the `PrintOptions` fields, and the following:
+//!
+//! * `verbose`, `single_line` and `always_fully_qualified`, as well as
+//!   `region_naming = "..."` (e.g. `"greek"`), `type_depth_limit = "N"`
+//!   and `print_crate_prefix = "true"`, set the `FmtPrinterBuilder`
+//!   options of the same name.
+//! * `max_path_segments = "N"`, `max_line_width = "N"` and
+//!   `inline_param_bounds = "N"` set the `FmtPrinter` fields of the same name,
+//!   and so do `full_path_crates = "crate, ..."` and
+//!   `impl_markers = "path::to::Trait, ..."`, from crate names and trait paths,
+//!   and `self_ty = "path::to::Type"` sets `FmtPrinter::self_ty` to the type
+//!   of that struct, enum or union.
+//! * `type_aliases = "path::to::Alias, ..."` adds those type aliases (of ADTs)
+//!   to `FmtPrinter::type_aliases`, fixing the generic args of the ADT which
+//!   don't mention the alias's own parameters.
+//! * `bound_region_constraints = "'a, ..."` constrains the late-bound regions
+//!   of those names to outlive `'static`, through
+//!   `FmtPrinter::bound_region_constraints`.
+//! * `type_position = "return"` (or `"binding"`) sets `FmtPrinter::type_position`.
+//! * `hidden_types` prints opaque types along with their hidden types (from
+//!   `type_of`), through `FmtPrinter::hidden_ty_resolver`.
+//! * `renames = "name => new_name, ..."` prints the path segments of every
+//!   def named `name` as `new_name` instead, through a `NameResolver`.
+//! * `truncated_substs = "N"` keeps only the first `N` generic args of the
+//!   ADT it prints the type of, like the partial substs seen during inference.
+//! * `highlight = "N, ..."` highlights the `N`th types in `Ty::walk` order.
+//! * `predicates` prints each of its `predicates_of` instead, followed by
+//!   its type being well-formed, for type aliases, structs, enums and unions.
+//! * `closures` prints the types of the closures in the body of a function
+//!   (in source order, as of the end of type-checking), instead of its own.
+//! * `repeat = "N"` prints it `N` times with the same printer, also generating
+//!   an error giving how many names were asked of its `NameResolver` (i.e. how
+//!   many path segments were walked), e.g. `resolver-calls(3)`, and another
+//!   listing the names generated for anonymous late-bound regions, if any,
+//!   e.g. `region-names('r, 's)`.
+//! * `print_with_opts` prints types with `Ty::print_with_opts` instead,
+//!   which only supports the `PrintOptions`.
+//! * `to_string` prints types with `ty_to_string` and paths with
+//!   `def_path_to_string` instead, also checking that they print the same
+//!   as `Display` and `def_path_str` respectively.
+//! * `diff` prints type aliases of pairs, `(Expected, Found)`, as the diff
+//!   of their two types instead (see `TyCtxt::type_diff`).
+//! * `fields = "N"` prints types as their `TyCtxt::flattened_fields`, with
+//!   a maximum depth of `N`, from `s`, e.g. `s.0: u8, s.1.x: u32`.
+//! * `gloss` prints types along with their description, as given by
+//!   `TyCtxt::type_with_gloss`, e.g. ``print-type(`bool` is a boolean)``.
+//! * `canonical_key` prints types as their `TyCtxt::canonical_type_key`, with
+//!   the (hex) hashes after each `@` elided as `..`, e.g. `Foo@..<u8>`.
+//! * `segments` prints types as their `TyCtxt::type_path_segments`, with
+//!   their generic args, e.g. `Crate(alloc), Item(vec), Item(Vec)<u8>`.
+//! * `repro` prints types as their (multi-line) `TyCtxt::type_repro`.
+//! * `search_tokens` prints types as their `TyCtxt::search_tokens`.
+//! * `blanket_impls = "path::to::Trait"` prints the (multi-line) headers of
+//!   the `TyCtxt::applicable_blanket_impls` of that trait to types instead,
+//!   one error per impl, e.g. `blanket-impl(impl<T> Trait for T;)`.
+//! * `token_stream` prints types as their `TyCtxt::type_token_stream`, each
+//!   token followed by its depth, e.g. ``print-type(`Vec`@0, `<`@0, `u8`@1, `>`@0)``.
+//! * `mermaid` prints types as their (multi-line) `TyCtxt::type_mermaid_diagram`.
+//! * `coercion = "deref"` (or `"unsize"`) prints type aliases of pairs,
+//!   `(Source, Target)`, as the steps of that kind of coercion from `Source`
+//!   to `Target` instead (see `TyCtxt::coercion_path_str`).
+//! * `byte_layout` prints types as their `TyCtxt::byte_layout_diagram`.
+//! * `method_candidates = "name"` prints types as the (sorted) paths of the
+//!   methods of that name of the local impls for them, as given by
+//!   `TyCtxt::method_candidate_paths`.
+//! * `type_hash` prints type aliases of tuples as the indices of their types,
+//!   grouped by their `TyCtxt::type_hash_str` digests (e.g. `[0, 2], [1]`),
+//!   also checking that the digests are 32 hex digits.
+//! * `region_vars` prints types with each of their free regions replaced by
+//!   a fresh region inference variable, numbered in order, whose SCC (under
+//!   `region_vids`) is made up as that of its number halved, e.g. `'?3(scc1)`.
+//! * `json` prints types as `JsonPrinter` trees (see `TyCtxt::type_json`)
+//!   instead, ignoring all the other options, and so does `sexp`, printing
+//!   them as `SexpPrinter` S-expressions, which it also checks round-trip,
+//!   `yaml`, printing them as (multi-line) `YamlPrinter` documents, and
+//!   `prolog`, printing the `PrologPrinter` facts, separated by spaces.
+//! * `hover` prints types through a `HoverPrinter`, also generating an error
+//!   listing the output by the innermost `DefId` printed at each byte, e.g.
+//!   ``hover(`std::vec::Vec<` = std::vec::Vec, `Foo` = Foo, `>` = std::vec::Vec)``.
+//! * `repr_align` prints structs, enums and unions as the `#[repr(align(N))]`
+//!   of their alignment, and consts as that of the alignment they give,
+//!   through `PrettyPrinter::pretty_print_repr_align`.
+//! * `referenced_crates` prints the (sorted) names of the crates referenced
+//!   by types (see `TyCtxt::crates_referenced_by`) instead.
+//! * `printed_hrtb` also generates an error giving whether any `for<...>`
+//!   binder was printed (see `FmtPrinter::printed_hrtb`), e.g. `printed-hrtb(true)`.
+//! * `legend` also generates an error for every entry of `FmtPrinter::legend`.
+
+use rustc::hir;
+use rustc::hir::def::{DefKind, Namespace};
+use rustc::hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
+use rustc::hir::intravisit::{self, NestedVisitorMap, Visitor};
+use rustc::hir::itemlikevisit::ItemLikeVisitor;
+use rustc::infer::RegionVariableOrigin;
+use rustc::mir::interpret::ConstValue;
+use rustc::ty::print::{FmtPrinter, FmtPrinterBuilder, NameResolver, PrettyPrinter, Printer};
+use rustc::ty::print::{PrintOptions, TypeAliasPattern, TypePosition};
+use rustc::ty::print::coercion::CoercionKind;
+use rustc::ty::print::hover::HoverPrinter;
+use rustc::ty::print::{def_path_to_string, ty_to_string, RegionNaming};
+use rustc::ty::print::sexp::Sexp;
+use rustc::ty::subst::{InternalSubsts, Subst, SubstsRef};
+use rustc::ty::{self, Ty, TyCtxt, TypeFoldable};
+use syntax::ast::{Attribute, NestedMetaItem};
+use syntax::symbol::sym;
+
+use std::cell::Cell;
+use std::iter;
+use std::ops::Range;
+
+pub fn test_print_type<'a, 'tcx>(tcx: TyCtxt<'a, 'tcx, 'tcx>) {
+    if tcx.features().rustc_attrs {
+        // if the `rustc_attrs` feature is not enabled, the attribute can't be present
+        tcx.hir()
+            .krate()
+            .visit_all_item_likes(&mut PrintTypeTest { tcx });
+    }
+}
+
+struct PrintTypeTest<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+}
+
+impl<'a, 'tcx> ItemLikeVisitor<'tcx> for PrintTypeTest<'a, 'tcx> {
+    fn visit_item(&mut self, item: &'tcx hir::Item) {
+        self.process_attrs(item.hir_id);
+        if let hir::ItemKind::Enum(ref enum_def, _) = item.node {
+            for variant in &enum_def.variants {
+                self.process_attrs(variant.node.id);
+            }
+        }
+    }
+
+    fn visit_trait_item(&mut self, trait_item: &'tcx hir::TraitItem) {
+        self.process_attrs(trait_item.hir_id);
+    }
+
+    fn visit_impl_item(&mut self, impl_item: &'tcx hir::ImplItem) {
+        self.process_attrs(impl_item.hir_id);
+    }
+}
+
+/// What gets printed for an item.
+#[derive(Copy, Clone)]
+enum Printed<'tcx> {
+    Ty(Ty<'tcx>),
+    Const(&'tcx ty::Const<'tcx>),
+    Path(DefId),
+    Predicate(ty::Predicate<'tcx>),
+}
+
+/// The options listed in a `rustc_print_type` attribute.
+#[derive(Default)]
+struct TestOptions {
+    print: PrintOptions,
+    verbose: bool,
+    single_line: bool,
+    always_fully_qualified: bool,
+    region_naming: Option<RegionNaming>,
+    type_depth_limit: Option<usize>,
+    print_crate_prefix: Option<bool>,
+    max_path_segments: Option<usize>,
+    max_line_width: Option<usize>,
+    inline_param_bounds: Option<usize>,
+    full_path_crates: Vec<String>,
+    impl_markers: Vec<String>,
+    self_ty: Option<String>,
+    type_aliases: Vec<String>,
+    renames: Vec<(String, String)>,
+    bound_region_constraints: Vec<String>,
+    hidden_types: bool,
+    type_position: Option<TypePosition>,
+    truncated_substs: Option<usize>,
+    highlight: Vec<usize>,
+    repeat: Option<usize>,
+    legend: bool,
+    printed_hrtb: bool,
+    predicates: bool,
+    closures: bool,
+    to_string: bool,
+    print_with_opts: bool,
+    diff: bool,
+    type_hash: bool,
+    gloss: bool,
+    canonical_key: bool,
+    segments: bool,
+    repro: bool,
+    search_tokens: bool,
+    blanket_impls: Option<String>,
+    token_stream: bool,
+    mermaid: bool,
+    coercion: Option<CoercionKind>,
+    byte_layout: bool,
+    method_candidates: Option<String>,
+    fields: Option<usize>,
+    region_vars: bool,
+    json: bool,
+    sexp: bool,
+    yaml: bool,
+    prolog: bool,
+    hover: bool,
+    repr_align: bool,
+    referenced_crates: bool,
+}
+
+impl<'a, 'tcx> PrintTypeTest<'a, 'tcx> {
+    fn process_attrs(&mut self, hir_id: hir::HirId) {
+        let tcx = self.tcx;
+        let def_id = tcx.hir().local_def_id_from_hir_id(hir_id);
+        for attr in tcx.get_attrs(def_id).iter() {
+            if attr.check_name(sym::rustc_print_type) {
+                if let Some(options) = self.parse_options(attr) {
+                    self.print(def_id, attr, &options);
+                }
+            }
+        }
+    }
+
+    fn parse_options(&self, attr: &Attribute) -> Option<TestOptions> {
+        let mut options = TestOptions::default();
+        let mut valid = true;
+        for meta_item in attr.meta_item_list().unwrap_or_default() {
+            if !self.parse_option(&mut options, &meta_item) {
+                self.tcx.sess.span_err(meta_item.span(), "unknown `rustc_print_type` option");
+                valid = false;
+            }
+        }
+        if valid { Some(options) } else { None }
+    }
+
+    fn parse_option(&self, options: &mut TestOptions, meta_item: &NestedMetaItem) -> bool {
+        let name = meta_item.name_or_empty();
+        let value = meta_item.value_str().map(|value| value.to_string());
+        let number = value.as_ref().and_then(|value| value.parse::<usize>().ok());
+        let list: Vec<_> = value.as_ref().map_or(vec![], |value| {
+            value.split(',').map(|s| s.trim().to_string()).collect()
+        });
+        match (&*name.as_str(), value.as_ref().map(|value| &value[..])) {
+            ("verbose", None) => options.verbose = true,
+            ("single_line", None) => options.single_line = true,
+            ("always_fully_qualified", None) => options.always_fully_qualified = true,
+            ("legend", None) => options.legend = true,
+            ("printed_hrtb", None) => options.printed_hrtb = true,
+            ("hidden_types", None) => options.hidden_types = true,
+            ("predicates", None) => options.predicates = true,
+            ("closures", None) => options.closures = true,
+            ("to_string", None) => options.to_string = true,
+            ("print_with_opts", None) => options.print_with_opts = true,
+            ("diff", None) => options.diff = true,
+            ("type_hash", None) => options.type_hash = true,
+            ("gloss", None) => options.gloss = true,
+            ("canonical_key", None) => options.canonical_key = true,
+            ("segments", None) => options.segments = true,
+            ("repro", None) => options.repro = true,
+            ("search_tokens", None) => options.search_tokens = true,
+            ("token_stream", None) => options.token_stream = true,
+            ("mermaid", None) => options.mermaid = true,
+            ("byte_layout", None) => options.byte_layout = true,
+            ("region_vars", None) => options.region_vars = true,
+            ("json", None) => options.json = true,
+            ("sexp", None) => options.sexp = true,
+            ("yaml", None) => options.yaml = true,
+            ("prolog", None) => options.prolog = true,
+            ("hover", None) => options.hover = true,
+            ("repr_align", None) => options.repr_align = true,
+            ("referenced_crates", None) => options.referenced_crates = true,
+            ("region_naming", Some(region_naming)) => {
+                options.region_naming = Some(match region_naming {
+                    "letters" => RegionNaming::Letters,
+                    "alphabetic" => RegionNaming::Alphabetic,
+                    "numbered" => RegionNaming::Numbered,
+                    "greek" => RegionNaming::Greek,
+                    "anonymous" => RegionNaming::Anonymous,
+                    _ => return false,
+                });
+            }
+            ("type_position", Some(position)) => {
+                options.type_position = Some(match position {
+                    "return" => TypePosition::Return,
+                    "binding" => TypePosition::Binding,
+                    _ => return false,
+                });
+            }
+            ("coercion", Some(kind)) => {
+                options.coercion = Some(match kind {
+                    "deref" => CoercionKind::Deref,
+                    "unsize" => CoercionKind::Unsize,
+                    _ => return false,
+                });
+            }
+            ("print_crate_prefix", Some(crate_prefix)) => {
+                options.print_crate_prefix = crate_prefix.parse().ok();
+                return options.print_crate_prefix.is_some();
+            }
+            ("type_depth_limit", Some(_)) => {
+                options.type_depth_limit = number;
+                return number.is_some();
+            }
+            ("max_path_segments", Some(_)) => {
+                options.max_path_segments = number;
+                return number.is_some();
+            }
+            ("max_line_width", Some(_)) => {
+                options.max_line_width = number;
+                return number.is_some();
+            }
+            ("inline_param_bounds", Some(_)) => {
+                options.inline_param_bounds = number;
+                return number.is_some();
+            }
+            ("fields", Some(_)) => {
+                options.fields = number;
+                return number.is_some();
+            }
+            ("truncated_substs", Some(_)) => {
+                options.truncated_substs = number;
+                return number.is_some();
+            }
+            ("highlight", Some(_)) => {
+                options.highlight = list.iter().filter_map(|n| n.parse().ok()).collect();
+                return options.highlight.len() == list.len();
+            }
+            ("repeat", Some(_)) => {
+                options.repeat = number.filter(|&n| n > 0);
+                return options.repeat.is_some();
+            }
+            ("full_path_crates", Some(_)) => options.full_path_crates = list,
+            ("impl_markers", Some(_)) => options.impl_markers = list,
+            ("type_aliases", Some(_)) => options.type_aliases = list,
+            ("bound_region_constraints", Some(_)) => options.bound_region_constraints = list,
+            ("blanket_impls", Some(path)) => options.blanket_impls = Some(path.to_string()),
+            ("method_candidates", Some(name)) => {
+                options.method_candidates = Some(name.to_string());
+            }
+            ("self_ty", Some(path)) => options.self_ty = Some(path.to_string()),
+            ("renames", Some(_)) => {
+                for rename in &list {
+                    let mut names = rename.split("=>").map(|name| name.trim().to_string());
+                    match (names.next(), names.next(), names.next()) {
+                        (Some(from), Some(to), None) => options.renames.push((from, to)),
+                        _ => return false,
+                    }
+                }
+            }
+            (name, None) => return options.print.enable(name),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Returns the trait printed as `path` (by `def_path_str`), if any.
+    fn find_trait(&self, path: &str) -> Option<DefId> {
+        let tcx = self.tcx;
+        tcx.all_traits(LOCAL_CRATE).iter().cloned().find(|&def_id| {
+            tcx.def_path_str(def_id) == path
+        })
+    }
+
+    /// Returns the local item of one of the `kinds` printed as `path`, if any.
+    fn find_local_item(&self, path: &str, kinds: &[DefKind]) -> Option<DefId> {
+        let tcx = self.tcx;
+        let mut def_ids = tcx.hir().krate().items.keys().map(|&hir_id| {
+            tcx.hir().local_def_id_from_hir_id(hir_id)
+        });
+        def_ids.find(|&def_id| {
+            tcx.def_kind(def_id).map_or(false, |kind| kinds.contains(&kind)) &&
+                tcx.def_path_str(def_id) == path
+        })
+    }
+
+    /// Returns the type of the struct, enum or union printed as `path`, if any.
+    fn find_adt_ty(&self, path: &str) -> Option<Ty<'tcx>> {
+        let kinds = [DefKind::Struct, DefKind::Enum, DefKind::Union];
+        self.find_local_item(path, &kinds).map(|def_id| self.tcx.type_of(def_id))
+    }
+
+    /// Returns the pattern of the type alias (of an ADT) printed as `path`, if
+    /// any, fixing the generic args which don't mention the alias's own.
+    fn find_type_alias(&self, path: &str) -> Option<TypeAliasPattern<'tcx>> {
+        let def_id = self.find_local_item(path, &[DefKind::TyAlias])?;
+        match self.tcx.type_of(def_id).sty {
+            ty::Adt(def, substs) => Some(TypeAliasPattern {
+                name: path.to_string(),
+                adt_def_id: def.did,
+                fixed_args: substs.iter().cloned().enumerate().filter(|(_, arg)| {
+                    !arg.needs_subst()
+                }).collect(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns what gets printed for `def_id`, and in which namespace.
+    fn printed(&self, def_id: DefId) -> (Printed<'tcx>, Namespace) {
+        let tcx = self.tcx;
+        match tcx.def_kind(def_id) {
+            Some(DefKind::TyAlias) | Some(DefKind::Fn) | Some(DefKind::Method) => {
+                (Printed::Ty(tcx.type_of(def_id)), Namespace::TypeNS)
+            }
+            Some(DefKind::Const) | Some(DefKind::AssocConst) => {
+                let ct = tcx.mk_const(ty::Const {
+                    val: ConstValue::Unevaluated(def_id, InternalSubsts::identity_for_item(
+                        tcx,
+                        def_id,
+                    )),
+                    ty: tcx.type_of(def_id),
+                });
+                (Printed::Const(ct), Namespace::ValueNS)
+            }
+            Some(DefKind::Static) | Some(DefKind::Ctor(..)) => {
+                (Printed::Path(def_id), Namespace::ValueNS)
+            }
+            _ => (Printed::Path(def_id), Namespace::TypeNS),
+        }
+    }
+
+    fn print(&self, def_id: DefId, attr: &Attribute, options: &TestOptions) {
+        let tcx = self.tcx;
+        if options.predicates {
+            for &(predicate, _) in &tcx.predicates_of(def_id).predicates {
+                self.print_one(Printed::Predicate(predicate), Namespace::TypeNS, attr, options);
+            }
+            match tcx.def_kind(def_id) {
+                Some(DefKind::TyAlias) | Some(DefKind::Struct) |
+                Some(DefKind::Enum) | Some(DefKind::Union) => {
+                    let well_formed = ty::Predicate::WellFormed(tcx.type_of(def_id));
+                    let printed = Printed::Predicate(well_formed);
+                    self.print_one(printed, Namespace::TypeNS, attr, options);
+                }
+                _ => {}
+            }
+            return;
+        }
+        if !options.closures {
+            let (printed, ns) = self.printed(def_id);
+            self.print_one(printed, ns, attr, options);
+            return;
+        }
+
+        let body_id = tcx.hir().as_local_hir_id(def_id)
+            .and_then(|hir_id| tcx.hir().maybe_body_owned_by_by_hir_id(hir_id));
+        if let Some(body_id) = body_id {
+            let mut finder = ClosureFinder { tcx, closures: vec![] };
+            finder.visit_body(tcx.hir().body(body_id));
+            let tables = tcx.typeck_tables_of(def_id);
+            for hir_id in finder.closures {
+                let ty = tables.node_type(hir_id);
+                self.print_one(Printed::Ty(ty), Namespace::TypeNS, attr, options);
+            }
+        }
+    }
+
+    fn print_one(
+        &self,
+        mut printed: Printed<'tcx>,
+        ns: Namespace,
+        attr: &Attribute,
+        options: &TestOptions,
+    ) {
+        let tcx = self.tcx;
+        if let (Some(n), Printed::Ty(ty)) = (options.truncated_substs, printed) {
+            if let ty::Adt(def, substs) = ty.sty {
+                let substs = tcx.intern_substs(&substs[..n.min(substs.len())]);
+                printed = Printed::Ty(tcx.mk_adt(def, substs));
+            }
+        }
+        if options.to_string {
+            let (printed, expected) = match printed {
+                Printed::Ty(ty) => (ty_to_string(tcx, ty), ty.to_string()),
+                Printed::Path(def_id) => {
+                    (def_path_to_string(tcx, def_id, &[]), tcx.def_path_str(def_id))
+                }
+                _ => return,
+            };
+            if printed != expected {
+                let msg = format!("print-type differs from the expected `{}`", expected);
+                tcx.sess.span_err(attr.span, &msg);
+            }
+            tcx.sess.span_err(attr.span, &format!("print-type({})", printed));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.print_with_opts, printed) {
+            let s = ty.print_with_opts(tcx, options.print);
+            tcx.sess.span_err(attr.span, &format!("print-type({})", s));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.diff, printed) {
+            let msg = match ty.sty {
+                ty::Tuple(tys) if tys.len() == 2 => {
+                    tcx.type_diff(tys[0].expect_ty(), tys[1].expect_ty())
+                }
+                _ => format!("`diff` expects a pair of types, found `{}`", ty),
+            };
+            tcx.sess.span_err(attr.span, &format!("print-type({})", msg));
+            return;
+        }
+        if let (Some(max_depth), Printed::Ty(ty)) = (options.fields, printed) {
+            let fields: Vec<_> = tcx.flattened_fields("s", ty, max_depth).into_iter().map(
+                |(path, ty)| format!("{}: {}", path, ty),
+            ).collect();
+            tcx.sess.span_err(attr.span, &format!("print-type({})", fields.join(", ")));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.gloss, printed) {
+            let (printed, gloss) = tcx.type_with_gloss(ty);
+            tcx.sess.span_err(attr.span, &format!("print-type(`{}` is {})", printed, gloss));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.canonical_key, printed) {
+            // The hashes change with the standard library, so they're elided.
+            let mut key = String::new();
+            let mut in_hash = false;
+            for c in tcx.canonical_type_key(ty).chars() {
+                if in_hash && c.is_ascii_hexdigit() {
+                    continue;
+                }
+                in_hash = c == '@';
+                key.push(c);
+                if in_hash {
+                    key.push_str("..");
+                }
+            }
+            tcx.sess.span_err(attr.span, &format!("print-type({})", key));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.segments, printed) {
+            let segments: Vec<_> = tcx.type_path_segments(ty, true).map(|segment| {
+                let mut s = format!("{:?}({})", segment.kind, segment.name);
+                if !segment.args.is_empty() {
+                    let args: Vec<_> = segment.args.iter().map(|arg| arg.to_string()).collect();
+                    s.push_str(&format!("<{}>", args.join(", ")));
+                }
+                s
+            }).collect();
+            tcx.sess.span_err(attr.span, &format!("print-type({})", segments.join(", ")));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.repro, printed) {
+            let repro = tcx.type_repro(ty);
+            tcx.sess.span_err(attr.span, &format!("print-type({})", repro.trim_end()));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.search_tokens, printed) {
+            let tokens = tcx.search_tokens(ty).join(", ");
+            tcx.sess.span_err(attr.span, &format!("print-type({})", tokens));
+            return;
+        }
+        if let (Some(path), Printed::Ty(ty)) = (&options.blanket_impls, printed) {
+            match self.find_trait(path) {
+                Some(trait_def_id) => {
+                    for header in tcx.applicable_blanket_impl_headers(trait_def_id, ty) {
+                        tcx.sess.span_err(attr.span, &format!("blanket-impl({})", header));
+                    }
+                }
+                None => tcx.sess.span_err(attr.span, &format!("unknown trait `{}`", path)),
+            }
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.token_stream, printed) {
+            let tokens: Vec<_> = tcx.type_token_stream(ty).into_iter().map(|(token, depth)| {
+                format!("`{}`@{}", token, depth)
+            }).collect();
+            tcx.sess.span_err(attr.span, &format!("print-type({})", tokens.join(", ")));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.mermaid, printed) {
+            let diagram = tcx.type_mermaid_diagram(ty);
+            tcx.sess.span_err(attr.span, &format!("print-type({})", diagram.trim_end()));
+            return;
+        }
+        if let (Some(kind), Printed::Ty(ty)) = (options.coercion, printed) {
+            let msg = match ty.sty {
+                ty::Tuple(tys) if tys.len() == 2 => {
+                    tcx.coercion_path_str(tys[0].expect_ty(), tys[1].expect_ty(), kind)
+                }
+                _ => format!("`coercion` expects a pair of types, found `{}`", ty),
+            };
+            tcx.sess.span_err(attr.span, &format!("print-type({})", msg));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.byte_layout, printed) {
+            let diagram = tcx.byte_layout_diagram(ty::ParamEnv::reveal_all(), ty);
+            tcx.sess.span_err(attr.span, &format!("print-type({})", diagram));
+            return;
+        }
+        if let (Some(name), Printed::Ty(ty)) = (&options.method_candidates, printed) {
+            let mut candidates = vec![];
+            for item in tcx.hir().krate().items.values() {
+                if let hir::ItemKind::Impl(..) = item.node {
+                    let impl_def_id = tcx.hir().local_def_id_from_hir_id(item.hir_id);
+                    if tcx.type_of(impl_def_id) != ty {
+                        continue;
+                    }
+                    for assoc_item in tcx.associated_items(impl_def_id) {
+                        if assoc_item.kind == ty::AssocKind::Method &&
+                            *assoc_item.ident.as_str() == name[..] {
+                            let substs = InternalSubsts::identity_for_item(tcx, assoc_item.def_id);
+                            candidates.push((assoc_item.def_id, substs));
+                        }
+                    }
+                }
+            }
+            let paths = tcx.method_candidate_paths(&candidates).join(", ");
+            tcx.sess.span_err(attr.span, &format!("print-type({})", paths));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.type_hash, printed) {
+            let msg = match ty.sty {
+                ty::Tuple(tys) => {
+                    let mut classes: Vec<(String, Vec<usize>)> = vec![];
+                    for (i, ty) in tys.iter().map(|ty| ty.expect_ty()).enumerate() {
+                        let hash = tcx.type_hash_str(ty);
+                        if hash.len() != 32 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                            tcx.sess.span_err(attr.span, "print-type digest isn't 32 hex digits");
+                        }
+                        match classes.iter_mut().find(|(class_hash, _)| *class_hash == hash) {
+                            Some((_, indices)) => indices.push(i),
+                            None => classes.push((hash, vec![i])),
+                        }
+                    }
+                    let classes: Vec<_> = classes.iter().map(|(_, indices)| {
+                        format!("{:?}", indices)
+                    }).collect();
+                    classes.join(", ")
+                }
+                _ => format!("`type_hash` expects a tuple of types, found `{}`", ty),
+            };
+            tcx.sess.span_err(attr.span, &format!("print-type({})", msg));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.region_vars, printed) {
+            let s = tcx.infer_ctxt().enter(|infcx| {
+                let ty = infcx.tcx.fold_regions(&ty, &mut false, |_, _| {
+                    infcx.next_region_var(RegionVariableOrigin::MiscVariable(attr.span))
+                });
+                let scc = |vid: ty::RegionVid| Some(vid.index() / 2);
+                let mut s = String::new();
+                let mut cx = FmtPrinter::new(infcx.tcx, &mut s, Namespace::TypeNS);
+                cx.region_vid_scc = Some(Box::new(&scc));
+                cx.set_print_options(options.print);
+                let _ = cx.print_type(ty);
+                s
+            });
+            tcx.sess.span_err(attr.span, &format!("print-type({})", s));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.json, printed) {
+            tcx.sess.span_err(attr.span, &format!("print-type({})", tcx.type_json(ty)));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.sexp, printed) {
+            let sexp = tcx.type_sexp(ty);
+            if Sexp::parse(&sexp).map(|parsed| parsed.to_string()) != Some(sexp.clone()) {
+                tcx.sess.span_err(attr.span, "print-type S-expression doesn't round-trip");
+            }
+            tcx.sess.span_err(attr.span, &format!("print-type({})", sexp));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.yaml, printed) {
+            let yaml = tcx.type_yaml(ty);
+            tcx.sess.span_err(attr.span, &format!("print-type({})", yaml.trim_end()));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.prolog, printed) {
+            let facts = tcx.type_prolog(ty).join(" ");
+            tcx.sess.span_err(attr.span, &format!("print-type({})", facts));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.hover, printed) {
+            let hover = match HoverPrinter::new(tcx, ns).print(|cx| cx.print_type(ty)) {
+                Ok(hover) => hover.finish(),
+                Err(_) => {
+                    tcx.sess.span_err(attr.span, "print-type failed");
+                    return;
+                }
+            };
+            let mut runs: Vec<(Range<usize>, DefId)> = vec![];
+            for offset in 0..hover.text.len() {
+                if let Some(def_id) = hover.def_id_at(offset) {
+                    match runs.last_mut() {
+                        Some((range, last)) if *last == def_id && range.end == offset => {
+                            range.end += 1;
+                        }
+                        _ => runs.push((offset..offset + 1, def_id)),
+                    }
+                }
+            }
+            let runs: Vec<_> = runs.into_iter().map(|(range, def_id)| {
+                format!("`{}` = {}", &hover.text[range], tcx.def_path_str(def_id))
+            }).collect();
+            tcx.sess.span_err(attr.span, &format!("print-type({})", hover.text));
+            tcx.sess.span_err(attr.span, &format!("hover({})", runs.join(", ")));
+            return;
+        }
+        if let (true, Printed::Ty(ty)) = (options.referenced_crates, printed) {
+            let mut names: Vec<_> = tcx.crates_referenced_by(ty, false).into_iter().map(|cnum| {
+                tcx.crate_name(cnum).to_string()
+            }).collect();
+            names.sort();
+            tcx.sess.span_err(attr.span, &format!("print-type({})", names.join(", ")));
+            return;
+        }
+        if options.repr_align {
+            let printed_def_kind = match printed {
+                Printed::Path(def_id) => tcx.def_kind(def_id),
+                _ => None,
+            };
+            let align = match (printed, printed_def_kind) {
+                (Printed::Const(ct), _) => ct,
+                (Printed::Path(def_id), Some(DefKind::Struct)) |
+                (Printed::Path(def_id), Some(DefKind::Enum)) |
+                (Printed::Path(def_id), Some(DefKind::Union)) => {
+                    ty::Const::from_usize(tcx, tcx.adt_def(def_id).repr.align as u64)
+                }
+                _ => {
+                    tcx.sess.span_err(attr.span, "`repr_align` expects an ADT or a const");
+                    return;
+                }
+            };
+            let mut s = String::new();
+            if FmtPrinter::new(tcx, &mut s, ns).pretty_print_repr_align(align).is_err() {
+                tcx.sess.span_err(attr.span, "print-type failed");
+                return;
+            }
+            tcx.sess.span_err(attr.span, &format!("print-type({})", s));
+            return;
+        }
+
+        let mut builder = FmtPrinterBuilder::new(tcx, ns)
+            .single_line(options.single_line)
+            .always_fully_qualified(options.always_fully_qualified)
+            .options(options.print);
+        if options.verbose {
+            builder = builder.verbose(true);
+        }
+        if let Some(region_naming) = options.region_naming {
+            builder = builder.region_naming(region_naming);
+        }
+        if let Some(limit) = options.type_depth_limit {
+            builder = builder.type_depth_limit(limit);
+        }
+        if let Some(crate_prefix) = options.print_crate_prefix {
+            builder = builder.print_crate_prefix(crate_prefix);
+        }
+
+        let resolver = CountingResolver::default();
+        let renaming_resolver = RenamingResolver { tcx, renames: &options.renames };
+        let bound_region_constraints = |br: ty::BoundRegion| match br {
+            ty::BrNamed(_, name) => {
+                let constrained = options.bound_region_constraints.contains(&name.to_string());
+                if constrained { vec![tcx.lifetimes.re_static] } else { vec![] }
+            }
+            _ => vec![],
+        };
+        let hidden_ty_resolver = |def_id: DefId, substs: SubstsRef<'tcx>| {
+            Some(tcx.type_of(def_id).subst(tcx, substs))
+        };
+        let mut s = String::new();
+        let mut cx = builder.build(&mut s);
+        if options.repeat.is_some() {
+            cx.def_name_resolver = Some(Box::new(&resolver));
+        } else if !options.renames.is_empty() {
+            cx.def_name_resolver = Some(Box::new(&renaming_resolver));
+        }
+        if !options.bound_region_constraints.is_empty() {
+            cx.bound_region_constraints = Some(Box::new(&bound_region_constraints));
+        }
+        if let Some(position) = options.type_position {
+            cx.type_position = position;
+        }
+        if options.hidden_types {
+            cx.hidden_ty_resolver = Some(Box::new(&hidden_ty_resolver));
+        }
+        cx.max_path_segments = options.max_path_segments;
+        cx.max_line_width = options.max_line_width;
+        cx.inline_param_bounds = options.inline_param_bounds;
+        for name in &options.full_path_crates {
+            let mut crates = iter::once(LOCAL_CRATE).chain(tcx.crates().iter().cloned());
+            let cnum = crates.find(|&cnum| *tcx.crate_name(cnum).as_str() == name[..]);
+            match cnum {
+                Some(cnum) => { cx.full_path_crates.insert(cnum); }
+                None => tcx.sess.span_err(attr.span, &format!("unknown crate `{}`", name)),
+            }
+        }
+        for path in &options.impl_markers {
+            match self.find_trait(path) {
+                Some(trait_def_id) => cx.impl_markers.push(trait_def_id),
+                None => tcx.sess.span_err(attr.span, &format!("unknown trait `{}`", path)),
+            }
+        }
+        if let Some(path) = &options.self_ty {
+            match self.find_adt_ty(path) {
+                Some(self_ty) => cx.self_ty = Some(self_ty),
+                None => tcx.sess.span_err(attr.span, &format!("unknown type `{}`", path)),
+            }
+        }
+        for path in &options.type_aliases {
+            match self.find_type_alias(path) {
+                Some(alias) => cx.type_aliases.push(alias),
+                None => tcx.sess.span_err(attr.span, &format!("unknown type alias `{}`", path)),
+            }
+        }
+        if let Printed::Ty(ty) = printed {
+            for &n in &options.highlight {
+                if let Some(highlighted) = ty.walk().nth(n) {
+                    cx.highlighted_tys.insert(highlighted);
+                }
+            }
+        }
+
+        let repeat = options.repeat.unwrap_or(1);
+        let cx = (0..repeat).try_fold(cx, |cx, _| match printed {
+            Printed::Ty(ty) => cx.print_type(ty),
+            Printed::Const(ct) => cx.print_const(ct),
+            Printed::Path(def_id) => cx.print_def_path(def_id, &[]),
+            Printed::Predicate(ref predicate) => cx.print_predicate(predicate),
+        });
+        let (legend, region_names, printed_hrtb) = match cx {
+            Ok(cx) => (cx.legend().to_vec(), cx.region_names().to_vec(), cx.printed_hrtb()),
+            Err(_) => {
+                tcx.sess.span_err(attr.span, "print-type failed");
+                return;
+            }
+        };
+
+        // The formatting of `tag({})` is chosen so that tests can elect to
+        // test the entirety of the string, if they choose, or else just some subset.
+        // Every repetition prints the same, so only the first is shown.
+        tcx.sess.span_err(attr.span, &format!("print-type({})", &s[..s.len() / repeat]));
+        if options.repeat.is_some() {
+            let calls = resolver.calls.get();
+            tcx.sess.span_err(attr.span, &format!("resolver-calls({})", calls));
+            if !region_names.is_empty() {
+                let names: Vec<_> = region_names.iter().map(|name| name.to_string()).collect();
+                tcx.sess.span_err(attr.span, &format!("region-names({})", names.join(", ")));
+            }
+        }
+        if options.printed_hrtb {
+            tcx.sess.span_err(attr.span, &format!("printed-hrtb({})", printed_hrtb));
+        }
+        if options.legend {
+            for (short, full) in legend {
+                tcx.sess.span_err(attr.span, &format!("legend({} = {})", short, full));
+            }
+        }
+    }
+}
+
+/// Counts the names asked for by a `FmtPrinter`, without providing any.
+#[derive(Default)]
+struct CountingResolver {
+    calls: Cell<usize>,
+}
+
+impl NameResolver for CountingResolver {
+    fn def_name(&self, _def_id: DefId) -> Option<String> {
+        self.calls.set(self.calls.get() + 1);
+        None
+    }
+
+    fn crate_name(&self, _cnum: CrateNum) -> Option<String> {
+        self.calls.set(self.calls.get() + 1);
+        None
+    }
+}
+
+/// Renames the defs that have one of the `renames`, leaving the crates alone.
+struct RenamingResolver<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    renames: &'a [(String, String)],
+}
+
+impl NameResolver for RenamingResolver<'_, '_> {
+    fn def_name(&self, def_id: DefId) -> Option<String> {
+        let name = self.tcx.def_key(def_id).disambiguated_data.data.get_opt_name()?;
+        self.renames.iter().find(|(from, _)| *from == *name.as_str()).map(|(_, to)| to.clone())
+    }
+}
+
+/// Collects the closures (but not the generators) in a body, in source order.
+struct ClosureFinder<'a, 'tcx: 'a> {
+    tcx: TyCtxt<'a, 'tcx, 'tcx>,
+    closures: Vec<hir::HirId>,
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for ClosureFinder<'a, 'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'tcx> {
+        NestedVisitorMap::OnlyBodies(&self.tcx.hir())
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr) {
+        if let hir::ExprKind::Closure(.., None) = expr.node {
+            self.closures.push(expr.hir_id);
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
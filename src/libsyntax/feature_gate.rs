@@ -1136,6 +1136,13 @@ pub const BUILTIN_ATTRIBUTES: &[BuiltinAttribute] = &[
             is just used for rustc unit tests \
             and will never be stable",
         cfg_fn!(rustc_attrs))),
+    (sym::rustc_print_type, Whitelisted, template!(Word, List: "option1, option2, ..."),
+    Gated(Stability::Unstable,
+        sym::rustc_attrs,
+        "the `#[rustc_print_type]` attribute \
+            is just used for rustc unit tests \
+            and will never be stable",
+        cfg_fn!(rustc_attrs))),
     (sym::rustc_layout_scalar_valid_range_start, Whitelisted, template!(List: "value"),
     Gated(Stability::Unstable,
         sym::rustc_attrs,
@@ -1253,6 +1260,10 @@ pub const BUILTIN_ATTRIBUTES: &[BuiltinAttribute] = &[
                                         sym::rustc_attrs,
                                         "internal rustc attributes will never be stable",
                                         cfg_fn!(rustc_attrs))),
+    (sym::rustc_backtrace_path, Whitelisted, template!(Word), Gated(Stability::Unstable,
+                                              sym::rustc_attrs,
+                                              "internal rustc attributes will never be stable",
+                                              cfg_fn!(rustc_attrs))),
     (sym::rustc_mir, Whitelisted, template!(List: "arg1, arg2, ..."), Gated(Stability::Unstable,
                                     sym::rustc_attrs,
                                     "the `#[rustc_mir]` attribute \
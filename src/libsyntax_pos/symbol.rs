@@ -523,6 +523,7 @@ symbols! {
         rustc_allow_const_fn_ptr,
         rustc_args_required_const,
         rustc_attrs,
+        rustc_backtrace_path,
         rustc_clean,
         rustc_const_unstable,
         rustc_conversion_suggestion,
@@ -555,6 +556,7 @@ symbols! {
         rustc_peek_definite_init,
         rustc_peek_maybe_init,
         rustc_peek_maybe_uninit,
+        rustc_print_type,
         rustc_private,
         rustc_proc_macro_decls,
         rustc_promotable,
@@ -0,0 +1,13 @@
+#![feature(associated_type_bounds)]
+
+trait Tr1 { type As1; }
+struct S1;
+impl Tr1 for S1 { type As1 = u8; }
+
+fn def_et1() -> impl Tr1<As1: Copy> { S1 }
+
+fn main() {
+    let _: u32 = def_et1();
+    //~^ ERROR mismatched types
+    //~| found type `impl Tr1<As1: std::marker::Copy>`
+}
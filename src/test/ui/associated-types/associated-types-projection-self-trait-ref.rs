@@ -0,0 +1,18 @@
+// Test the printing of trait refs whose `Self` type is a projection.
+
+pub trait Convert<T> {
+    fn convert(&self) -> T;
+}
+
+pub trait Get {
+    type Value;
+
+    fn get(&self) -> Self::Value;
+}
+
+fn foo<G: Get>(g: G) -> u8 {
+    Convert::<u8>::convert(&g.get()) //~ ERROR E0277
+}
+
+pub fn main() {
+}
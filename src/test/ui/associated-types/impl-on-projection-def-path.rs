@@ -0,0 +1,29 @@
+// Check that impls on projections are printed by their trait-qualified path,
+// when they're in the same module as the trait of the projection.
+
+#![feature(rustc_attrs)]
+#![allow(dead_code)]
+
+trait Bar {
+    fn method(&self);
+}
+
+mod m {
+    use super::Bar;
+
+    pub trait Foo {
+        type Assoc;
+    }
+
+    impl Foo for u8 {
+        type Assoc = u16;
+    }
+
+    impl Bar for <u8 as Foo>::Assoc {
+        #[rustc_def_path]
+        //~^ ERROR def-path(<<u8 as m::Foo>::Assoc as Bar>::method)
+        fn method(&self) {}
+    }
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+// Const args are printed symbolically (as written, for anonymous consts),
+// without being evaluated, while array lengths, which type-checking already
+// evaluated, are printed as their values.
+
+#![feature(const_generics)]
+//~^ WARN the feature `const_generics` is incomplete and may cause the compiler to crash
+
+struct ArrayWrapper<const N: usize>([u8; N]);
+
+const LEN: usize = 4;
+
+fn take(_: ()) {}
+
+fn generic<const N: usize>(wrapper: ArrayWrapper<{N}>) {
+    take(wrapper);
+    //~^ ERROR mismatched types
+}
+
+fn main() {
+    let wrapper: ArrayWrapper<{LEN}> = ArrayWrapper([0; LEN]);
+    take(wrapper);
+    //~^ ERROR mismatched types
+    take([0u8; LEN]);
+    //~^ ERROR mismatched types
+}
@@ -0,0 +1,12 @@
+// compile-flags: -Z verbose
+// Check that args equal to the defaults of their parameters are printed with `-Z verbose`.
+
+use std::collections::HashMap;
+
+fn take(_: ()) {}
+
+fn main() {
+    let map: HashMap<u8, u16> = HashMap::new();
+    take(map);
+    //~^ ERROR mismatched types
+}
@@ -0,0 +1,11 @@
+// Check that args equal to the defaults of their parameters aren't printed.
+
+use std::collections::HashMap;
+
+fn take(_: ()) {}
+
+fn main() {
+    let map: HashMap<u8, u16> = HashMap::new();
+    take(map);
+    //~^ ERROR mismatched types
+}
@@ -0,0 +1,26 @@
+// Check that the bounds of trait objects are printed in the same order,
+// regardless of the order they were written in.
+
+use std::fmt::Debug;
+
+trait Pair {
+    type B;
+    type A;
+}
+
+fn take(_: ()) {}
+
+fn main() {
+    let a: Box<dyn Sync + Debug + Send> = Box::new(0u8);
+    take(a);
+    //~^ ERROR mismatched types
+    let b: Box<dyn Send + Sync + Debug> = Box::new(0u8);
+    take(b);
+    //~^ ERROR mismatched types
+    let c: Option<Box<dyn Pair<B = u8, A = u16>>> = None;
+    take(c);
+    //~^ ERROR mismatched types
+    let d: Option<Box<dyn Pair<A = u16, B = u8>>> = None;
+    take(d);
+    //~^ ERROR mismatched types
+}
@@ -0,0 +1,17 @@
+// Check that the `for<...>` prefix of higher-ranked fn pointers is printed
+// for their bound regions, and omitted entirely without any.
+
+fn by_ref(_: &u8) {}
+
+fn by_value(_: u8) {}
+
+fn take(_: ()) {}
+
+fn main() {
+    let a: for<'a> fn(&'a u8) = by_ref;
+    take(a);
+    //~^ ERROR mismatched types
+    let b: fn(u8) = by_value;
+    take(b);
+    //~^ ERROR mismatched types
+}
@@ -0,0 +1,18 @@
+// Check the printing of bare function signatures, with and without qualifiers.
+
+extern "C" {
+    fn printf(format: *const u8, ...) -> i32;
+}
+
+fn nothing() {}
+
+fn take(_: ()) {}
+
+fn main() {
+    let a: unsafe extern "C" fn(*const u8, ...) -> i32 = printf;
+    take(a);
+    //~^ ERROR mismatched types
+    let b: fn() = nothing;
+    take(b);
+    //~^ ERROR mismatched types
+}
@@ -0,0 +1,17 @@
+// Args matching the defaults of their parameters are elided from type paths,
+// but still printed in the turbofish of functions in value paths.
+
+#![allow(invalid_type_param_default)]
+
+struct Wrapper<T = u8>(T);
+
+fn generic<T = u8>(_: T) {}
+
+fn take(_: ()) {}
+
+fn main() {
+    take(Wrapper::<u8>(0));
+    //~^ ERROR mismatched types
+    take(generic::<u8>);
+    //~^ ERROR mismatched types
+}
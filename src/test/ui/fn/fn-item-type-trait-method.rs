@@ -0,0 +1,18 @@
+// Test that trait methods in fn item types are printed qualified with
+// the implementing type, and with all of the trait's arguments substituted.
+
+trait Extend<A> {
+    fn extend<I>(&mut self, iter: I);
+}
+
+impl<A> Extend<A> for Vec<A> {
+    fn extend<I>(&mut self, _: I) {}
+}
+
+fn eq<T>(x: T, y: T) { }
+
+fn main() {
+    eq(<Vec<u32> as Extend<u32>>::extend::<()>, <Vec<u8> as Extend<u8>>::extend::<()>);
+    //~^ ERROR mismatched types
+    //~| expected u32, found u8
+}
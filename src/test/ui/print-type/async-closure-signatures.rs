@@ -0,0 +1,23 @@
+// ignore-tidy-linelength
+// edition:2018
+
+// Check that `async_closure_signatures` marks `async` closures, along
+// with their future-returning signature, and leaves other closures alone.
+
+#![feature(async_await, rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(closures)]
+//~^ ERROR print-type([closure@$DIR/async-closure-signatures.rs:14:13: 14:33])
+fn f() {
+    let _ = async move |x: u8| x;
+}
+
+#[rustc_print_type(closures, async_closure_signatures)]
+//~^ ERROR print-type([async closure@$DIR/async-closure-signatures.rs:21:13: 21:33; fn(u8) -> impl Future<Output = u8>])
+//~| ERROR print-type([closure@$DIR/async-closure-signatures.rs:22:13: 22:22])
+fn g() {
+    let _ = async move |x: u8| x;
+    let _ = |x: u8| x;
+}
@@ -0,0 +1,25 @@
+// Check that `applicable_blanket_impls` only gives the blanket impls whose
+// bounds the type may meet, less specialized ones first.
+
+#![feature(rustc_attrs, specialization)]
+
+trait Tr {}
+impl<T: ?Sized> Tr for T {}
+impl<T: Clone> Tr for T {}
+
+struct NotClone;
+
+fn main() {}
+
+#[rustc_print_type(blanket_impls = "Tr")]
+//~^ ERROR blanket-impl(impl<T> Tr for T
+//~| ERROR blanket-impl(impl<T> Tr for T
+type A = String;
+
+#[rustc_print_type(blanket_impls = "Tr")]
+//~^ ERROR blanket-impl(impl<T> Tr for T
+type B = str;
+
+#[rustc_print_type(blanket_impls = "Tr")]
+//~^ ERROR blanket-impl(impl<T> Tr for T
+type C = NotClone;
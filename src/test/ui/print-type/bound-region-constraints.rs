@@ -0,0 +1,19 @@
+// Check that `FmtPrinter::bound_region_constraints` are printed after the
+// late-bound regions of `for<...>` binders, only for the constrained regions,
+// and that nothing is printed for them without it.
+
+#![feature(rustc_attrs)]
+
+#[rustc_print_type(bound_region_constraints = "'a")]
+//~^ ERROR print-type(for<'a, 'b where 'a: 'static> fn(&'a u8, &'b u8))
+type A = for<'a, 'b> fn(&'a u8, &'b u8);
+
+#[rustc_print_type(bound_region_constraints = "'a, 'b")]
+//~^ ERROR print-type(for<'a, 'b where 'a: 'static, 'b: 'static> fn(&'a u8, &'b u8))
+type B = for<'a, 'b> fn(&'a u8, &'b u8);
+
+#[rustc_print_type]
+//~^ ERROR print-type(for<'a, 'b> fn(&'a u8, &'b u8))
+type C = for<'a, 'b> fn(&'a u8, &'b u8);
+
+fn main() {}
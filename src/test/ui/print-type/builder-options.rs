@@ -0,0 +1,38 @@
+// edition:2018
+
+// Check that each `FmtPrinterBuilder` option takes effect, and that
+// the options left out keep their defaults.
+
+#![feature(rustc_attrs)]
+
+struct Local;
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type((&'a Local, for<'r, 's> fn(&'r u8, &'s u8), std::vec::Vec<std::vec::Vec<u8>>))
+type A<'a> = (&'a Local, fn(&u8, &u8), Vec<Vec<u8>>);
+
+#[rustc_print_type(verbose)]
+//~^ ERROR print-type(&ReEarlyBound(0, 'a) Local)
+type B<'a> = &'a Local;
+
+#[rustc_print_type(region_naming = "alphabetic")]
+//~^ ERROR print-type(for<'a, 'b> fn(&'a u8, &'b u8))
+type C = fn(&u8, &u8);
+
+#[rustc_print_type(type_depth_limit = "1")]
+//~^ ERROR print-type(std::vec::Vec<...>)
+type D = Vec<Vec<u8>>;
+
+#[rustc_print_type(print_crate_prefix = "true")]
+//~^ ERROR print-type(crate::Local)
+type E = Local;
+
+#[rustc_print_type(region_naming = "numbered", print_crate_prefix = "true")]
+//~^ ERROR print-type(for<'1> fn(&'1 crate::Local))
+type F = fn(&Local);
+
+#[rustc_print_type(region_naming = "greek")]
+//~^ ERROR print-type(for<'α, 'β> fn(&'α u8, &'β u8))
+type G = fn(&u8, &u8);
@@ -0,0 +1,29 @@
+// only-64bit
+
+// Check the byte-layout diagrams `byte_layout_diagram` prints for structs,
+// enums with their discriminant in a tag or in a niche, and generic types.
+
+#![feature(rustc_attrs)]
+
+#[repr(C)]
+struct Foo { a: u8, b: u32 }
+
+enum E { A(u32), B }
+
+fn main() {}
+
+#[rustc_print_type(byte_layout)]
+//~^ ERROR print-type(Foo: [a:0..1][pad:1..4][b:4..8])
+type A = Foo;
+
+#[rustc_print_type(byte_layout)]
+//~^ ERROR print-type(E: A{[discr:0..1][pad:1..4][0:4..8]} B{[discr:0..1][pad:1..8]})
+type B = E;
+
+#[rustc_print_type(byte_layout)]
+//~^ ERROR print-type(std::option::Option<&'static u8>: None{[niche:0..8]} Some{[0:0..8]})
+type C = Option<&'static u8>;
+
+#[rustc_print_type(byte_layout)]
+//~^ ERROR print-type((u8, T): <no layout: the type `T` has an unknown layout>)
+type D<T> = (u8, T);
@@ -0,0 +1,24 @@
+// ignore-tidy-linelength
+
+// Check that `canonical_type_key` renumbers bound regions within their binder,
+// so alpha-equivalent types get the same key, and erases free regions.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(canonical_key)]
+//~^ ERROR print-type((extern "Rust" fn(&'^0.1 u8) -> (), extern "Rust" fn(&'^0.1 u8) -> ()))
+type A = (for<'a> fn(&'a u8), for<'b> fn(&'b u8));
+
+#[rustc_print_type(canonical_key)]
+//~^ ERROR print-type(extern "Rust" fn(&'^0.1 u8, extern "Rust" fn(&'^1.1 u8, &'^0.1 u8) -> ()) -> ())
+type B = for<'a> fn(&'a u8, for<'b> fn(&'a u8, &'b u8));
+
+#[rustc_print_type(canonical_key)]
+//~^ ERROR print-type((&'_ mut [u8; 2usize: usize], *const str))
+type C<'a> = (&'a mut [u8; 2], *const str);
+
+#[rustc_print_type(canonical_key)]
+//~^ ERROR print-type(std::vec::Vec@..<&'_ u8>)
+type D = Vec<&'static u8>;
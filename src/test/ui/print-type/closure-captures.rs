@@ -0,0 +1,26 @@
+// ignore-tidy-linelength
+
+// Check that `closure_captures` lists the variables captured by
+// closures by name, with by-reference captures as references.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(closures)]
+//~^ ERROR print-type([closure@$DIR/closure-captures.rs:15:13: 15:23 x:&u32, y:&std::string::String])
+fn f() {
+    let x = 1u32;
+    let y = String::new();
+    let _ = || (x, &y);
+}
+
+#[rustc_print_type(closures, closure_captures)]
+//~^ ERROR print-type([closure@$DIR/closure-captures.rs:24:13: 24:23 capturing: x: &u32, y: &std::string::String])
+//~| ERROR print-type([closure@$DIR/closure-captures.rs:25:13: 25:22 capturing: x: u32])
+fn g() {
+    let x = 1u32;
+    let y = String::new();
+    let _ = || (x, &y);
+    let _ = move || x;
+}
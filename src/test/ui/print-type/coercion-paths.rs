@@ -0,0 +1,24 @@
+// ignore-tidy-linelength
+
+// Check the steps `coercion_path_str` prints for deref and unsizing coercions,
+// including where they break, when they can't reach the target type.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(coercion = "deref")]
+//~^ ERROR print-type(&std::boxed::Box<std::string::String> → &std::string::String → &str)
+type A = (&'static Box<String>, &'static str);
+
+#[rustc_print_type(coercion = "deref")]
+//~^ ERROR print-type(&std::vec::Vec<u8> → &[u8] (doesn't coerce further to `&'static str`))
+type B = (&'static Vec<u8>, &'static str);
+
+#[rustc_print_type(coercion = "unsize")]
+//~^ ERROR print-type(&[u8; 4] → &[u8])
+type C = (&'static [u8; 4], &'static [u8]);
+
+#[rustc_print_type(coercion = "unsize")]
+//~^ ERROR print-type(&[u8; 4] (doesn't coerce further to `&'static [u16]`))
+type D = (&'static [u8; 4], &'static [u16]);
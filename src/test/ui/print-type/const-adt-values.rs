@@ -0,0 +1,47 @@
+// Check that struct and enum consts are printed by their fields, as they'd be
+// written as expressions, including nested ones and enum variants of every
+// kind, and that only their paths are printed without `const_values`.
+
+#![feature(rustc_attrs)]
+
+struct P {
+    x: u8,
+    y: bool,
+}
+
+struct T(u8, char);
+struct W(P);
+
+enum E {
+    A(u16),
+    B { v: i8 },
+    C,
+}
+
+#[rustc_print_type(const_values)]
+//~^ ERROR print-type(PP /* = P { x: 3u8, y: true } */)
+#[rustc_print_type]
+//~^ ERROR print-type(PP)
+const PP: P = P { x: 3, y: true };
+
+#[rustc_print_type(const_values)]
+//~^ ERROR print-type(TT /* = T(1u8, 'a') */)
+const TT: T = T(1, 'a');
+
+#[rustc_print_type(const_values)]
+//~^ ERROR print-type(WW /* = W(P { x: 0u8, y: false }) */)
+const WW: W = W(P { x: 0, y: false });
+
+#[rustc_print_type(const_values)]
+//~^ ERROR print-type(EA /* = E::A(7u16) */)
+const EA: E = E::A(7);
+
+#[rustc_print_type(const_values)]
+//~^ ERROR print-type(EB /* = E::B { v: -1i8 } */)
+const EB: E = E::B { v: -1 };
+
+#[rustc_print_type(const_values)]
+//~^ ERROR print-type(EC /* = E::C */)
+const EC: E = E::C;
+
+fn main() {}
@@ -0,0 +1,29 @@
+// ignore-tidy-linelength
+// normalize-stderr-test "ByRef\(.*\) : " -> "ByRef(..) : "
+
+// Check that `const_array_elems` prints const arrays by their elements, with
+// the repeat syntax for all-equal elements, and at most 16 of them otherwise
+// (including for partially-uniform arrays), and that they're printed by their
+// (debug) representation without it.
+
+#![feature(rustc_attrs)]
+
+#[rustc_print_type(const_values, const_array_elems)]
+//~^ ERROR print-type(ZEROS /* = [0u8; 1000] */)
+#[rustc_print_type(const_values)]
+//~^ ERROR print-type(ZEROS /* = ByRef(
+const ZEROS: [u8; 1000] = [0; 1000];
+
+#[rustc_print_type(const_values, const_array_elems)]
+//~^ ERROR print-type(FEW /* = [1u8, 2u8, 3u8] */)
+const FEW: [u8; 3] = [1, 2, 3];
+
+#[rustc_print_type(const_values, const_array_elems)]
+//~^ ERROR print-type(MANY /* = [0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, 0u8, ...] */)
+const MANY: [u8; 20] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+
+#[rustc_print_type(const_values, const_array_elems)]
+//~^ ERROR print-type(FLAGS /* = [true, false] */)
+const FLAGS: [bool; 2] = [true, false];
+
+fn main() {}
@@ -0,0 +1,24 @@
+// Check that `const_callability` marks `const fn`s, and those only callable
+// in const contexts with a feature gate enabled, but nothing when disabled.
+
+#![feature(rustc_attrs, rustc_const_unstable, staged_api)]
+#![unstable(feature = "humans", issue = "0")]
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(fn() -> u32 {f})
+const fn f() -> u32 { 0 }
+
+#[rustc_print_type(const_callability)]
+//~^ ERROR print-type(fn() -> u32 {g /* const-callable */})
+const fn g() -> u32 { 0 }
+
+#[rustc_print_type(const_callability)]
+//~^ ERROR print-type(fn() -> u32 {h})
+fn h() -> u32 { 0 }
+
+#[rustc_print_type(const_callability)]
+//~^ ERROR print-type(fn() -> u32 {i /* const-callable with #![feature(const_i)] */})
+#[rustc_const_unstable(feature = "const_i")]
+const fn i() -> u32 { 0 }
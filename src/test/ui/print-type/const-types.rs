@@ -0,0 +1,16 @@
+// Check that `const_types` annotates symbolic consts with their type.
+
+#![feature(const_generics, rustc_attrs)]
+//~^ WARN the feature `const_generics` is incomplete and may cause the compiler to crash
+
+struct S<const N: usize>;
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(fn() -> S<N> {f::<N>})
+fn f<const N: usize>() -> S<{N}> { S }
+
+#[rustc_print_type(const_types)]
+//~^ ERROR print-type(fn() -> S<N: usize> {g::<N: usize>})
+fn g<const N: usize>() -> S<{N}> { S }
@@ -0,0 +1,32 @@
+// Check that `const_values` prints the values of (associated) consts after
+// their paths, including consts defined by `const fn` calls, but not of
+// consts which can't be evaluated, and only their paths without it.
+
+#![feature(rustc_attrs)]
+
+const fn answer() -> u8 {
+    42
+}
+
+struct S;
+struct W<T>(T);
+
+#[rustc_print_type(const_values)]
+//~^ ERROR print-type(N /* = 3usize */)
+const N: usize = 1 + 2;
+
+impl S {
+    #[rustc_print_type(const_values)]
+    //~^ ERROR print-type(S::ANSWER /* = 42u8 */)
+    #[rustc_print_type]
+    //~^ ERROR print-type(S::ANSWER)
+    const ANSWER: u8 = answer();
+}
+
+impl<T> W<T> {
+    #[rustc_print_type(const_values)]
+    //~^ ERROR print-type(W::<T>::SIZE)
+    const SIZE: usize = std::mem::size_of::<T>();
+}
+
+fn main() {}
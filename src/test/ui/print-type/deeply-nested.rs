@@ -0,0 +1,34 @@
+// ignore-tidy-linelength
+
+// Check that printing types nested much more deeply than the default depth
+// limit (here 10000 levels) doesn't overflow the stack, but elides the
+// subtypes beyond the limit instead.
+
+#![feature(rustc_attrs)]
+
+struct O<T>(T);
+
+type D1<T> = O<T>;
+type D2<T> = D1<D1<T>>;
+type D4<T> = D2<D2<T>>;
+type D8<T> = D4<D4<T>>;
+type D16<T> = D8<D8<T>>;
+type D32<T> = D16<D16<T>>;
+type D64<T> = D32<D32<T>>;
+type D128<T> = D64<D64<T>>;
+type D256<T> = D128<D128<T>>;
+type D512<T> = D256<D256<T>>;
+type D1024<T> = D512<D512<T>>;
+type D2048<T> = D1024<D1024<T>>;
+type D4096<T> = D2048<D2048<T>>;
+type D8192<T> = D4096<D4096<T>>;
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(O<O<O<
+type A = D8192<D1024<D512<D256<D16<u8>>>>>;
+
+#[rustc_print_type(type_depth_limit = "3", elision_counts)]
+//~^ ERROR print-type(O<O<O<…(9998 more types)…>>>)
+type B = D8192<D1024<D512<D256<D16<u8>>>>>;
@@ -0,0 +1,28 @@
+// Check that `def_id_suffixes` appends the `DefId` of each segment of a path
+// to it, including those of generic args, but not when disabled. The indices
+// aren't stable, so only the (local) crate numbers are checked.
+
+// normalize-stderr-test "\{0:\d+\}" -> "{0:N}"
+
+#![feature(rustc_attrs)]
+
+mod a {
+    pub mod b {
+        pub struct F;
+    }
+    pub struct G<T>(T);
+}
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(a::b::F)
+type A = a::b::F;
+
+#[rustc_print_type(def_id_suffixes)]
+//~^ ERROR print-type(a{0:
+type B = a::b::F;
+
+#[rustc_print_type(def_id_suffixes)]
+//~^ ERROR print-type(a{0:
+type C = a::G<a::b::F>;
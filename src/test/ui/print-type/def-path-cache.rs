@@ -0,0 +1,46 @@
+// ignore-tidy-linelength
+
+// Check that `FmtPrinter` walks each path without generic args only once,
+// reusing what it printed the next times, even when printing it 1000 times,
+// but not when it'd print differently, e.g. at a depth where more gets elided.
+
+#![feature(rustc_attrs)]
+
+mod a {
+    pub mod b {
+        pub struct C;
+    }
+}
+
+fn main() {}
+
+#[rustc_print_type(repeat = "1")]
+//~^ ERROR print-type(a::b::C)
+//~| ERROR resolver-calls(3)
+type A = a::b::C;
+
+#[rustc_print_type(repeat = "1000")]
+//~^ ERROR print-type(a::b::C)
+//~| ERROR resolver-calls(3)
+type B = a::b::C;
+
+#[rustc_print_type(repeat = "1")]
+//~^ ERROR print-type((a::b::C, a::b::C))
+//~| ERROR resolver-calls(3)
+type D = (a::b::C, a::b::C);
+
+trait Tr {
+    fn m();
+}
+
+impl Tr for Vec<Vec<u8>> {
+    fn m() {
+        struct Inner;
+
+        #[rustc_print_type(type_depth_limit = "3", legend)]
+        //~^ ERROR print-type((<std::vec::Vec<...> as Tr>::m::Inner, std::boxed::Box<<... as Tr>::m::Inner>))
+        //~| ERROR legend(... = std::vec::Vec<u8>)
+        //~| ERROR legend(... = std::vec::Vec<std::vec::Vec<u8>>)
+        type Nested = (Inner, Box<Inner>);
+    }
+}
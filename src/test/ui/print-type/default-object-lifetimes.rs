@@ -0,0 +1,29 @@
+// Check that `default_object_lifetimes_elided` only omits the lifetime
+// bounds of trait objects which are the default ones, i.e. that of the
+// reference they're behind, and `'static` anywhere else.
+
+#![feature(rustc_attrs)]
+
+trait Trait {}
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(&'a (dyn Trait + 'a))
+type A<'a> = &'a dyn Trait;
+
+#[rustc_print_type(default_object_lifetimes_elided)]
+//~^ ERROR print-type(&'a dyn Trait)
+type B<'a> = &'a dyn Trait;
+
+#[rustc_print_type(default_object_lifetimes_elided)]
+//~^ ERROR print-type(&'a (dyn Trait + 'static))
+type C<'a> = &'a (dyn Trait + 'static);
+
+#[rustc_print_type(default_object_lifetimes_elided)]
+//~^ ERROR print-type(std::boxed::Box<dyn Trait>)
+type D = Box<dyn Trait>;
+
+#[rustc_print_type(default_object_lifetimes_elided)]
+//~^ ERROR print-type(std::boxed::Box<(dyn Trait + 'a)>)
+type E<'a> = Box<dyn Trait + 'a>;
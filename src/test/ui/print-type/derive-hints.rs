@@ -0,0 +1,40 @@
+// ignore-tidy-linelength
+
+// Check that `derive_hints` lists the derivable traits each ADT supports,
+// whether their implementations are derived or written by hand.
+
+#![feature(rustc_attrs)]
+
+#[derive(Clone, Debug)]
+struct Derived;
+
+struct Manual;
+impl Clone for Manual {
+    fn clone(&self) -> Self {
+        Manual
+    }
+}
+
+struct Neither;
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(Derived)
+type A = Derived;
+
+#[rustc_print_type(derive_hints)]
+//~^ ERROR print-type(Derived /* derives: Clone, Debug */)
+type B = Derived;
+
+#[rustc_print_type(derive_hints)]
+//~^ ERROR print-type(Manual /* derives: Clone */)
+type C = Manual;
+
+#[rustc_print_type(derive_hints)]
+//~^ ERROR print-type(Neither)
+type D = Neither;
+
+#[rustc_print_type(derive_hints)]
+//~^ ERROR print-type(std::vec::Vec<Derived /* derives: Clone, Debug */> /* derives: Clone, Debug, Default */)
+type E = Vec<Derived>;
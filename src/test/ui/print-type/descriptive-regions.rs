@@ -0,0 +1,20 @@
+// Check that `descriptive_regions` describes anonymous regions where
+// their origin is known, and leaves named ones alone. `fn_param_names`
+// is used to get the signatures of fn items with free regions, instead
+// of late-bound ones.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(fn_param_names)]
+//~^ ERROR print-type(fn(x: &u8) {f})
+fn f(x: &u8) { let _ = x; }
+
+#[rustc_print_type(fn_param_names, descriptive_regions)]
+//~^ ERROR print-type(fn(x: &'<anonymous lifetime of the function> u8) {g})
+fn g(x: &u8) { let _ = x; }
+
+#[rustc_print_type(fn_param_names, descriptive_regions)]
+//~^ ERROR print-type(fn(x: &'a u8) {h})
+fn h<'a>(x: &'a u8) { let _ = x; }
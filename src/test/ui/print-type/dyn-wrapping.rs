@@ -0,0 +1,21 @@
+// Check that trait object types that don't fit in `max_line_width` are
+// printed with every bound after the principal trait on its own line, with
+// the bounds aligned, and on a single line when they fit or without it.
+
+#![feature(rustc_attrs)]
+
+trait Tr {}
+
+#[rustc_print_type(max_line_width = "40")]
+//~^ ERROR print-type(&'a (dyn Tr
+type A<'a> = &'a (dyn Tr + Send + Sync + 'a);
+
+#[rustc_print_type(max_line_width = "80")]
+//~^ ERROR print-type(&'a (dyn Tr + std::marker::Send + std::marker::Sync + 'a))
+type B<'a> = &'a (dyn Tr + Send + Sync + 'a);
+
+#[rustc_print_type]
+//~^ ERROR print-type(&'a (dyn Tr + std::marker::Send + std::marker::Sync + 'a))
+type C<'a> = &'a (dyn Tr + Send + Sync + 'a);
+
+fn main() {}
@@ -0,0 +1,37 @@
+// ignore-tidy-linelength
+
+// Check that types elided beyond the depth limit are counted, and that all
+// those in the same list of generic args, or of tuple elements, adjacent or
+// not, are merged into one marker, at every depth at which they're elided,
+// while types without any components are always printed.
+
+#![feature(rustc_attrs)]
+
+struct Pair<A, B>(A, B);
+
+#[rustc_print_type(type_depth_limit = "2")]
+//~^ ERROR print-type((std::vec::Vec<...>, Pair<..., u8>, u8))
+type A = (Vec<Vec<u8>>, Pair<Vec<u8>, u8>, u8);
+
+#[rustc_print_type(type_depth_limit = "2", elision_counts)]
+//~^ ERROR print-type((std::vec::Vec<…(2 more types)…>, Pair<…(2 more types)…, u8>, u8))
+type B = (Vec<Vec<u8>>, Pair<Vec<u8>, u8>, u8);
+
+#[rustc_print_type(type_depth_limit = "1", elision_counts)]
+//~^ ERROR print-type((…(7 more types)…, u8))
+type C = (Vec<Vec<u8>>, Pair<Vec<u8>, u8>, u8);
+
+#[rustc_print_type(type_depth_limit = "3", elision_counts)]
+//~^ ERROR print-type(Pair<Pair<std::vec::Vec<…(2 more types)…>, ()>, Pair<u8, Pair<…(2 more types)…, u8>>>)
+type D = Pair<Pair<Vec<Vec<u8>>, ()>, Pair<u8, Pair<Vec<u8>, u8>>>;
+
+#[rustc_print_type(type_depth_limit = "1", elision_counts, legend)]
+//~^ ERROR print-type((…(4 more types)…, u8, char))
+//~| ERROR legend(…(4 more types)… = std::vec::Vec<u8>, std::option::Option<char>)
+type E = (Vec<u8>, u8, Option<char>, char);
+
+#[rustc_print_type(type_depth_limit = "1")]
+//~^ ERROR print-type((..., u8))
+type F = (Vec<u8>, u8, Vec<u8>);
+
+fn main() {}
@@ -0,0 +1,18 @@
+// Check that `empty_generic_brackets` prints `<>` after generic types whose
+// arguments were all omitted (here, as defaults), but not after non-generic
+// ones, and that nothing is printed in their place without it.
+
+#![feature(rustc_attrs)]
+
+struct Foo<T = u8>(T);
+struct Bar;
+
+#[rustc_print_type(empty_generic_brackets)]
+//~^ ERROR print-type((Foo<>, Foo<u16>, Bar))
+type A = (Foo, Foo<u16>, Bar);
+
+#[rustc_print_type]
+//~^ ERROR print-type((Foo, Foo<u16>, Bar))
+type B = (Foo, Foo<u16>, Bar);
+
+fn main() {}
@@ -0,0 +1,26 @@
+// Check that `evaluated_consts` prints consts by their values, where those
+// can be computed, and by their paths otherwise, as they are by default.
+
+#![feature(rustc_attrs)]
+
+struct W<T>(T);
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(LEN)
+const LEN: usize = 2 + 2;
+
+#[rustc_print_type(evaluated_consts)]
+//~^ ERROR print-type(4usize)
+const LEN2: usize = 2 + 2;
+
+#[rustc_print_type(evaluated_consts)]
+//~^ ERROR print-type(true)
+const FLAG: bool = LEN == 4;
+
+impl<T> W<T> {
+    #[rustc_print_type(evaluated_consts)]
+    //~^ ERROR print-type(W::<T>::SIZE)
+    const SIZE: usize = std::mem::size_of::<T>();
+}
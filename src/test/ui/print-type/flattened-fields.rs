@@ -0,0 +1,26 @@
+// Check that `flattened_fields` flattens structs and tuples into their leaf
+// fields, up to the maximum depth, leaving enums and unit structs as they are.
+
+#![feature(rustc_attrs)]
+
+struct Point { x: u32, y: u32 }
+struct S { a: Point, b: bool }
+struct U;
+
+fn main() {}
+
+#[rustc_print_type(fields = "8")]
+//~^ ERROR print-type(s.a.x: u32, s.a.y: u32, s.b: bool)
+type A = S;
+
+#[rustc_print_type(fields = "1")]
+//~^ ERROR print-type(s.a: Point, s.b: bool)
+type B = S;
+
+#[rustc_print_type(fields = "8")]
+//~^ ERROR print-type(s.0: u8, s.1: (), s.2.0.x: u32, s.2.0.y: u32, s.2.1: std::option::Option<U>)
+type C = (u8, (), (Point, Option<U>));
+
+#[rustc_print_type(fields = "8")]
+//~^ ERROR print-type(s: U)
+type D = U;
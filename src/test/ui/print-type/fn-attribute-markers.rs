@@ -0,0 +1,22 @@
+// Check that `fn_attribute_markers` lists the behavior-relevant
+// attributes of fn items, in a single marker, and nothing without any.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(fn() {f})
+#[cold]
+fn f() {}
+
+#[rustc_print_type(fn_attribute_markers)]
+//~^ ERROR print-type(fn() {g /* attrs: cold, no_mangle */})
+#[cold]
+#[no_mangle]
+fn g() {}
+
+#[rustc_print_type(fn_attribute_markers)]
+//~^ ERROR print-type(fn() {h})
+#[inline]
+fn h() {}
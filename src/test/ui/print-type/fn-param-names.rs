@@ -0,0 +1,20 @@
+// Check that `fn_param_names` prints the names of the parameters of
+// fn items, falling back to their index for those bound by other patterns
+// (including `_`), while anonymous lifetimes stay elided, as they would be
+// in source.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(fn(i32, i32) -> i32 {f})
+fn f(x: i32, y: i32) -> i32 { x + y }
+
+#[rustc_print_type(fn_param_names)]
+//~^ ERROR print-type(fn(x: i32, y: i32) -> i32 {g})
+fn g(x: i32, mut y: i32) -> i32 { y += x; y }
+
+#[rustc_print_type(fn_param_names)]
+//~^ ERROR print-type(fn(_0: (u8, u8), _1: u8, z: &u8) {h})
+fn h((_a, _b): (u8, u8), _: u8, z: &u8) { let _ = z; }
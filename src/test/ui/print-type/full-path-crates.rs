@@ -0,0 +1,18 @@
+// Check that `full_path_crates` makes the items of the given crates be
+// printed with their full (defining) path, and only those.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type((std::vec::Vec<u8>, std::option::Option<u8>))
+type A = (Vec<u8>, Option<u8>);
+
+#[rustc_print_type(full_path_crates = "alloc")]
+//~^ ERROR print-type((alloc::vec::Vec<u8>, std::option::Option<u8>))
+type B = (Vec<u8>, Option<u8>);
+
+#[rustc_print_type(full_path_crates = "alloc, core")]
+//~^ ERROR print-type((alloc::vec::Vec<u8>, core::option::Option<u8>))
+type C = (Vec<u8>, Option<u8>);
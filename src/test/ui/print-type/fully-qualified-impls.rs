@@ -0,0 +1,25 @@
+// ignore-tidy-linelength
+
+// Check that `always_fully_qualified` prints the paths of impls co-located
+// with their self type or trait in full, from the name of the local crate,
+// instead of as `<Type as Trait>`.
+
+#![feature(rustc_attrs)]
+
+mod m {
+    pub struct S;
+
+    pub trait Tr {
+        fn f();
+    }
+
+    impl Tr for S {
+        #[rustc_print_type]
+        //~^ ERROR print-type(fn() {<m::S as m::Tr>::f})
+        #[rustc_print_type(always_fully_qualified)]
+        //~^ ERROR print-type(fn() {fully_qualified_impls::m::<impl fully_qualified_impls::m::Tr for fully_qualified_impls::m::S>::f})
+        fn f() {}
+    }
+}
+
+fn main() {}
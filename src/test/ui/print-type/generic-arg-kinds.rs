@@ -0,0 +1,23 @@
+// ignore-tidy-linelength
+
+// Check that lifetime, type and const args in the same list are each printed
+// by their own kind's printing method (see `Printer::print_generic_arg`).
+
+#![feature(const_generics, rustc_attrs)]
+//~^ WARN the feature `const_generics` is incomplete and may cause the compiler to crash
+
+struct M<'a, T, const N: usize>(&'a T);
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(M<'a, u8, {3}>)
+type A<'a> = M<'a, u8, {3}>;
+
+#[rustc_print_type(const_types)]
+//~^ ERROR print-type(M<'a, u8, {3}: usize>)
+type B<'a> = M<'a, u8, {3}>;
+
+#[rustc_print_type(json)]
+//~^ ERROR print-type({"args":[{"kind":"Named","name":"'a"},{"kind":"Uint","name":"u8"},{"kind":"Const","ty":{"kind":"Uint","name":"usize"},"value":"{3}"}],"def_path":[{"name":"generic_arg_kinds"},{"disambiguator":0,"name":"M"}],"kind":"Adt"})
+type C<'a> = M<'a, u8, {3}>;
@@ -0,0 +1,30 @@
+// ignore-tidy-linelength
+
+// Check the descriptions `type_with_gloss` gives of well-known library types,
+// of composite types, from those of their components, and of other types.
+
+#![feature(rustc_attrs)]
+
+struct Local;
+
+fn main() {}
+
+#[rustc_print_type(gloss)]
+//~^ ERROR print-type(`std::vec::Vec<u32>` is a growable array of 32-bit unsigned integers)
+type A = Vec<u32>;
+
+#[rustc_print_type(gloss)]
+//~^ ERROR print-type(`std::option::Option<(bool, char)>` is an optional tuple of a boolean and a character)
+type B = Option<(bool, char)>;
+
+#[rustc_print_type(gloss)]
+//~^ ERROR print-type(`&'static mut [std::result::Result<u8, ()>]` is a mutable reference to a slice of results of either 8-bit unsigned integers or unit values)
+type C = &'static mut [Result<u8, ()>];
+
+#[rustc_print_type(gloss)]
+//~^ ERROR print-type(`[usize; 3]` is an array of 3 pointer-sized unsigned integers)
+type D = [usize; 3];
+
+#[rustc_print_type(gloss)]
+//~^ ERROR print-type(`std::boxed::Box<Local>` is a box containing a value of type `Local`)
+type E = Box<Local>;
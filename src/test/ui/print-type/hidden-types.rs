@@ -0,0 +1,33 @@
+// Check that `hidden_types` prints opaque types along with their hidden types,
+// with the opaque type's generic args substituted in, and that they're
+// printed alone without it.
+
+#![feature(existential_type, rustc_attrs)]
+
+existential type Foo: Copy;
+
+fn define() -> Foo {
+    0u16
+}
+
+#[rustc_print_type(hidden_types)]
+//~^ ERROR print-type(Foo /* = u16 */)
+type A = Foo;
+
+#[rustc_print_type(hidden_types)]
+//~^ ERROR print-type(fn() -> impl std::marker::Copy /* = u8 */ {f})
+#[rustc_print_type]
+//~^ ERROR print-type(fn() -> impl std::marker::Copy {f})
+fn f() -> impl Copy {
+    0u8
+}
+
+#[rustc_print_type(hidden_types)]
+//~^ ERROR print-type(fn(T) -> impl std::marker::Copy /* = (T, u8) */ {g::<T>})
+fn g<T: Copy>(t: T) -> impl Copy {
+    (t, 0u8)
+}
+
+fn main() {
+    define();
+}
@@ -0,0 +1,22 @@
+// Check that highlighted types are wrapped in markers wherever they're printed,
+// nesting inside each other, and that nothing else is.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(highlight = "2")]
+//~^ ERROR print-type(std::vec::Vec<std::option::Option<{u32}>>)
+type A = Vec<Option<u32>>;
+
+#[rustc_print_type(highlight = "0")]
+//~^ ERROR print-type({std::vec::Vec<std::option::Option<u32>>})
+type B = Vec<Option<u32>>;
+
+#[rustc_print_type(highlight = "1, 2")]
+//~^ ERROR print-type(std::vec::Vec<{std::option::Option<{u32}>}>)
+type C = Vec<Option<u32>>;
+
+#[rustc_print_type(highlight = "1")]
+//~^ ERROR print-type(({u8}, {u8}, char))
+type D = (u8, u8, char);
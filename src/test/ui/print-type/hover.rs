@@ -0,0 +1,24 @@
+// Check that `HoverPrinter` maps every byte of a path to the innermost def
+// printed there, and the bytes outside of any path to none.
+
+#![feature(rustc_attrs)]
+
+struct Foo;
+struct Bar<T>(T);
+
+fn main() {}
+
+#[rustc_print_type(hover)]
+//~^ ERROR print-type(std::vec::Vec<Foo>)
+//~| ERROR hover(`std::vec::Vec<` = std::vec::Vec, `Foo` = Foo, `>` = std::vec::Vec)
+type A = Vec<Foo>;
+
+#[rustc_print_type(hover)]
+//~^ ERROR print-type((&'static Foo, Bar<u8>))
+//~| ERROR hover(`Foo` = Foo, `Bar<u8>` = Bar)
+type B = (&'static Foo, Bar<u8>);
+
+#[rustc_print_type(hover)]
+//~^ ERROR print-type(fn(Foo) -> Bar<Foo>)
+//~| ERROR hover(`Foo` = Foo, `Bar<` = Bar, `Foo` = Foo, `>` = Bar)
+type C = fn(Foo) -> Bar<Foo>;
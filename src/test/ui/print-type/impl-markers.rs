@@ -0,0 +1,28 @@
+// Check that `impl_markers` lists the given traits implemented by every
+// ADT, leaving out implementations depending on generic parameters.
+
+#![feature(rustc_attrs)]
+
+trait Marker {}
+impl Marker for u8 {}
+impl<T: Marker> Marker for Vec<T> {}
+
+struct Local;
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(std::vec::Vec<u8>)
+type A = Vec<u8>;
+
+#[rustc_print_type(impl_markers = "Marker, std::marker::Send")]
+//~^ ERROR print-type(std::vec::Vec<u8> /* implements: Marker, Send */)
+type B = Vec<u8>;
+
+#[rustc_print_type(impl_markers = "Marker")]
+//~^ ERROR print-type(std::vec::Vec<Local>)
+type C = Vec<Local>;
+
+#[rustc_print_type(impl_markers = "Marker")]
+//~^ ERROR print-type(std::vec::Vec<T>)
+type D<T> = Vec<T>;
@@ -0,0 +1,34 @@
+// Check that `inline_param_bounds` follows type parameters passed as generic
+// args with the bounds that the item they're passed to places on them, only
+// the first time each is printed, and with at most the given number of them.
+
+#![feature(rustc_attrs)]
+
+pub struct Sorted<T: Ord>(Vec<T>);
+pub struct Pair<A: Clone + Default + Eq, B>(A, B);
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(Sorted<T>)
+type A<T> = Sorted<T>;
+
+#[rustc_print_type(inline_param_bounds = "2")]
+//~^ ERROR print-type(Sorted<T: std::cmp::Ord>)
+type B<T> = Sorted<T>;
+
+#[rustc_print_type(inline_param_bounds = "2")]
+//~^ ERROR print-type((Sorted<T: std::cmp::Ord>, Sorted<T>))
+type C<T> = (Sorted<T>, Sorted<T>);
+
+#[rustc_print_type(inline_param_bounds = "2")]
+//~^ ERROR print-type(Pair<A: std::clone::Clone + std::default::Default + ..., B>)
+type D<A, B> = Pair<A, B>;
+
+#[rustc_print_type(inline_param_bounds = "0")]
+//~^ ERROR print-type(Pair<A: ..., u8>)
+type E<A> = Pair<A, u8>;
+
+#[rustc_print_type(inline_param_bounds = "3")]
+//~^ ERROR print-type(Pair<u8, Sorted<u8>>)
+type F = Pair<u8, Sorted<u8>>;
@@ -0,0 +1,29 @@
+// Check that `iterator_summaries` prints iterators by their item type,
+// however deeply nested their adapters are, but only once it's known.
+
+#![feature(rustc_attrs)]
+
+use std::iter::{Filter, Map};
+use std::vec::IntoIter;
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(std::iter::Map<std::vec::IntoIter<u8>, fn(u8) -> u16>)
+type A = Map<IntoIter<u8>, fn(u8) -> u16>;
+
+#[rustc_print_type(iterator_summaries)]
+//~^ ERROR print-type(impl Iterator<Item = u16>)
+type B = Map<IntoIter<u8>, fn(u8) -> u16>;
+
+#[rustc_print_type(iterator_summaries)]
+//~^ ERROR print-type(impl Iterator<Item = u16>)
+type C = Filter<Map<IntoIter<u8>, fn(u8) -> u16>, fn(&u16) -> bool>;
+
+#[rustc_print_type(iterator_summaries)]
+//~^ ERROR print-type(std::vec::Vec<u8>)
+type D = Vec<u8>;
+
+#[rustc_print_type(iterator_summaries)]
+//~^ ERROR print-type(std::vec::IntoIter<T>)
+type E<T> = IntoIter<T>;
@@ -0,0 +1,24 @@
+// ignore-tidy-linelength
+
+// Check the shape of the JSON trees `json` prints for ADTs with generic args,
+// references and fn pointers.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(json)]
+//~^ ERROR print-type({"args":[{"kind":"Ref","mutable":false,"pointee":{"kind":"Uint","name":"u32"},"region":{"kind":"Named","name":"'a"}}],"def_path":[{"name":"core"},{"disambiguator":0,"name":"option"},{"disambiguator":0,"name":"Option"}],"kind":"Adt"})
+type A<'a> = Option<&'a u32>;
+
+#[rustc_print_type(json)]
+//~^ ERROR print-type({"args":[{"elements":[{"kind":"Uint","name":"u8"},{"kind":"Char"}],"kind":"Tuple"}],"def_path":[{"name":"alloc"},{"disambiguator":0,"name":"vec"},{"disambiguator":0,"name":"Vec"}],"kind":"Adt"})
+type B = Vec<(u8, char)>;
+
+#[rustc_print_type(json)]
+//~^ ERROR print-type({"kind":"Ref","mutable":true,"pointee":{"kind":"Str"},"region":{"kind":"Named","name":"'static"}})
+type C = &'static mut str;
+
+#[rustc_print_type(json)]
+//~^ ERROR print-type({"abi":"Rust","c_variadic":false,"inputs":[{"kind":"Uint","name":"u8"}],"kind":"FnPtr","output":{"kind":"Bool"},"unsafe":false})
+type D = fn(u8) -> bool;
@@ -0,0 +1,34 @@
+// ignore-tidy-linelength
+
+// Check that the legend maps each path collapsed under `max_path_segments`,
+// and each elision marker, back to what it stands for.
+
+#![feature(rustc_attrs)]
+
+mod a { pub mod b { pub mod c { pub mod d { pub struct S; } } } }
+
+#[rustc_print_type(max_path_segments = "2", legend)]
+//~^ ERROR print-type(...::d::S)
+//~| ERROR legend(... = a::b::c)
+type P = a::b::c::d::S;
+
+#[rustc_print_type(type_depth_limit = "2", elision_counts, legend)]
+//~^ ERROR print-type(std::option::Option<std::option::Option<…(2 more types)…>>)
+//~| ERROR legend(…(2 more types)… = std::option::Option<u8>)
+type A = Option<Option<Option<u8>>>;
+
+#[rustc_print_type(type_depth_limit = "1", elision_counts, legend)]
+//~^ ERROR print-type((…(5 more types)…))
+//~| ERROR legend(…(5 more types)… = std::vec::Vec<u8>, std::option::Option<std::vec::Vec<u8>>)
+type B = (Vec<u8>, Option<Vec<u8>>);
+
+#[rustc_print_type(type_depth_limit = "2", legend)]
+//~^ ERROR print-type(std::boxed::Box<(dyn ... + 'static)>)
+//~| ERROR legend(... = std::marker::Send)
+type C = Box<dyn Send>;
+
+#[rustc_print_type(type_depth_limit = "2")]
+//~^ ERROR print-type(std::boxed::Box<(dyn ... + 'static)>)
+type D = Box<dyn Send>;
+
+fn main() {}
@@ -0,0 +1,25 @@
+// ignore-tidy-linelength
+
+// Check that `lifetime_only_args` prints the erased args of paths whose
+// args are all lifetimes (here, the types of closure captures, as of the end
+// of type-checking), but not those of paths with other args, and that
+// they're omitted without it.
+
+#![feature(rustc_attrs)]
+
+struct Ref<'a>(&'a u8);
+struct Mixed<'a, T>(&'a T);
+
+#[rustc_print_type(closures, lifetime_only_args)]
+//~^ ERROR print-type([closure@$DIR/lifetime-only-args.rs:16:13: 16:27 r:Ref<'_>, m:Mixed<u8>])
+fn f(r: Ref<'_>, m: Mixed<'_, u8>) {
+    let _ = move || (r, m);
+}
+
+#[rustc_print_type(closures)]
+//~^ ERROR print-type([closure@$DIR/lifetime-only-args.rs:22:13: 22:27 r:Ref, m:Mixed<u8>])
+fn g(r: Ref<'_>, m: Mixed<'_, u8>) {
+    let _ = move || (r, m);
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+// Check that `max_line_width` measures generic args as they get printed,
+// i.e. with the same options, here the (long) locality markers.
+
+#![feature(rustc_attrs)]
+
+struct Local;
+
+fn main() {}
+
+#[rustc_print_type(max_line_width = "30")]
+//~^ ERROR print-type(std::option::Option<Local>)
+type A = Option<Local>;
+
+#[rustc_print_type(max_line_width = "30", locality_markers)]
+//~^ ERROR print-type(std::option::Option<
+type B = Option<Local>;
+
+#[rustc_print_type(max_line_width = "40", locality_markers)]
+//~^ ERROR print-type(std::option::Option<Local /* local */> /* foreign */)
+type C = Option<Local>;
@@ -0,0 +1,20 @@
+// Check that `locality_markers` marks ADTs as local or foreign,
+// and leaves everything else (e.g. primitives and type parameters) alone.
+
+#![feature(rustc_attrs)]
+
+struct Local;
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(std::vec::Vec<Local>)
+type A = Vec<Local>;
+
+#[rustc_print_type(locality_markers)]
+//~^ ERROR print-type(std::vec::Vec<Local /* local */> /* foreign */)
+type B = Vec<Local>;
+
+#[rustc_print_type(locality_markers)]
+//~^ ERROR print-type((u8, T))
+type C<T> = (u8, T);
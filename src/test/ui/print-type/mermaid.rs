@@ -0,0 +1,18 @@
+// Check the flowcharts `type_mermaid_diagram` draws, with the links listed
+// after everything their target links to, and `&` and `>` escaped.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(mermaid)]
+//~^ ERROR print-type(graph TD
+type A<'a> = Option<&'a u32>;
+
+#[rustc_print_type(mermaid)]
+//~^ ERROR print-type(graph TD
+type B = (u8, fn(char) -> !);
+
+#[rustc_print_type(mermaid)]
+//~^ ERROR print-type(graph TD
+type C = Box<dyn Iterator<Item = u8>>;
@@ -0,0 +1,40 @@
+// Check that `TyCtxt::method_candidate_paths` lists inherent methods first,
+// followed by trait methods by their (sorted) fully qualified paths, and
+// nothing for types without such methods.
+
+#![feature(rustc_attrs)]
+
+struct Foo;
+struct Bar;
+
+trait B {
+    fn f(&self);
+}
+
+trait A {
+    fn f(&self);
+}
+
+impl B for Foo {
+    fn f(&self) {}
+}
+
+impl Foo {
+    fn f(&self) {}
+}
+
+impl A for Foo {
+    fn f(&self) {}
+}
+
+#[rustc_print_type(method_candidates = "f")]
+//~^ ERROR print-type(Foo::f, <Foo as A>::f, <Foo as B>::f)
+#[rustc_print_type]
+//~^ ERROR print-type(Foo)
+type F = Foo;
+
+#[rustc_print_type(method_candidates = "f")]
+//~^ ERROR print-type()
+type G = Bar;
+
+fn main() {}
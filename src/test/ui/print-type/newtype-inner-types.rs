@@ -0,0 +1,31 @@
+// Check that `newtype_inner_types` prints the primitive wrapped by
+// newtype indices (non-generic single-field tuple structs), and only theirs.
+
+#![feature(rustc_attrs)]
+
+struct NodeId(u32);
+struct Generic<T>(T);
+struct Named { id: u32 }
+struct Wrapper(String);
+
+#[rustc_print_type]
+//~^ ERROR print-type(NodeId)
+type A = NodeId;
+
+#[rustc_print_type(newtype_inner_types)]
+//~^ ERROR print-type(NodeId(u32))
+type B = NodeId;
+
+#[rustc_print_type(newtype_inner_types)]
+//~^ ERROR print-type(Generic<u32>)
+type C = Generic<u32>;
+
+#[rustc_print_type(newtype_inner_types)]
+//~^ ERROR print-type(Named)
+type D = Named;
+
+#[rustc_print_type(newtype_inner_types)]
+//~^ ERROR print-type(Wrapper)
+type E = Wrapper;
+
+fn main() {}
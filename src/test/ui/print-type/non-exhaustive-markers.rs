@@ -0,0 +1,31 @@
+// Check that `non_exhaustive_markers` marks `#[non_exhaustive]` structs
+// and enums, but not enums with only some `#[non_exhaustive]` variants.
+
+#![feature(non_exhaustive, rustc_attrs)]
+
+#[non_exhaustive] pub struct Config { pub verbose: bool }
+#[non_exhaustive] pub enum Error { Io, Parse }
+pub enum Event { #[non_exhaustive] Key { code: u32 }, Quit }
+pub struct Plain;
+
+#[rustc_print_type]
+//~^ ERROR print-type(Config)
+type A = Config;
+
+#[rustc_print_type(non_exhaustive_markers)]
+//~^ ERROR print-type(Config /* non_exhaustive */)
+type B = Config;
+
+#[rustc_print_type(non_exhaustive_markers)]
+//~^ ERROR print-type(std::vec::Vec<Error /* non_exhaustive */>)
+type C = Vec<Error>;
+
+#[rustc_print_type(non_exhaustive_markers)]
+//~^ ERROR print-type(Event)
+type D = Event;
+
+#[rustc_print_type(non_exhaustive_markers)]
+//~^ ERROR print-type(Plain)
+type E = Plain;
+
+fn main() {}
@@ -0,0 +1,43 @@
+// Check that `object_safety_markers` marks the paths of traits which
+// aren't object-safe, with the first reason why, and only those, but
+// not when they're merely the prefixes of other paths (e.g. of methods).
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(Unmarked)
+trait Unmarked: Sized {}
+
+#[rustc_print_type(object_safety_markers)]
+//~^ ERROR print-type(NoReceiver /* not object-safe: method `new` has no receiver */)
+trait NoReceiver {
+    type Output;
+
+    #[rustc_print_type(object_safety_markers)]
+    //~^ ERROR print-type(fn() -> Self {<Self as NoReceiver>::new})
+    fn new() -> Self;
+}
+
+#[rustc_print_type(object_safety_markers)]
+//~^ ERROR print-type(Safe)
+trait Safe {
+    fn get(&self) -> u8;
+}
+
+#[rustc_print_type(object_safety_markers)]
+//~^ ERROR print-type(std::boxed::Box<(dyn Safe + 'static)>)
+type A = Box<dyn Safe>;
+
+#[rustc_print_type(object_safety_markers)]
+//~^ ERROR print-type(<u8 as NoReceiver>::Output)
+type B = <u8 as NoReceiver>::Output;
+
+impl NoReceiver for u8 {
+    type Output = u8;
+
+    fn new() -> u8 {
+        0
+    }
+}
@@ -0,0 +1,23 @@
+// Check that the bounds of `impl Trait` types are printed with the other
+// traits first, in their declared order, followed by the sorted auto traits.
+
+#![feature(optin_builtin_traits, rustc_attrs)]
+
+trait First {}
+trait Second {}
+auto trait Zed {}
+auto trait Alpha {}
+
+struct S;
+impl First for S {}
+impl Second for S {}
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(fn() -> impl First+Second+Alpha+Zed+std::marker::Send {f})
+fn f() -> impl Zed + First + Send + Alpha + Second { S }
+
+#[rustc_print_type]
+//~^ ERROR print-type(fn() -> impl Second+First+std::marker::Send+std::marker::Sync {g})
+fn g() -> impl Sync + Second + Send + First { S }
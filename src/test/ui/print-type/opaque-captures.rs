@@ -0,0 +1,21 @@
+// Check that `opaque_captures` lists the lifetimes captured by
+// `impl Trait` types, and nothing for those which capture none.
+
+#![feature(rustc_attrs)]
+
+trait Trait {}
+impl<T> Trait for T {}
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(for<'a> fn(&'a u8) -> impl Trait {f})
+fn f<'a>(x: &'a u8) -> impl Trait + 'a { x }
+
+#[rustc_print_type(opaque_captures)]
+//~^ ERROR print-type(for<'a> fn(&'a u8) -> impl Trait+captures<'a> {f})
+fn g<'a>(x: &'a u8) -> impl Trait + 'a { x }
+
+#[rustc_print_type(opaque_captures)]
+//~^ ERROR print-type(fn(T) -> impl Trait {h::<T>})
+fn h<T>(x: T) -> impl Trait { x }
@@ -0,0 +1,38 @@
+// Check that the items of impls on an opaque type alias are printed with the
+// shorter `<Type as Trait>` form when the impl is co-located with the alias,
+// the same as for any other type, and as `<impl Trait for Type>` otherwise.
+
+#![feature(existential_type, rustc_attrs)]
+
+mod t {
+    pub trait Tr {
+        fn f();
+    }
+}
+
+mod m {
+    pub existential type Foo: Copy;
+    pub existential type Bar: Copy;
+
+    pub fn define() -> (Foo, Bar) {
+        (0u8, 0u16)
+    }
+
+    impl super::t::Tr for Foo {
+        #[rustc_print_type]
+        //~^ ERROR print-type(fn() {<m::Foo as t::Tr>::f})
+        fn f() {}
+    }
+}
+
+mod n {
+    impl super::t::Tr for super::m::Bar {
+        #[rustc_print_type]
+        //~^ ERROR print-type(fn() {n::<impl t::Tr for m::Bar>::f})
+        fn f() {}
+    }
+}
+
+fn main() {
+    m::define();
+}
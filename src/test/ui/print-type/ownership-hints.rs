@@ -0,0 +1,31 @@
+// Check that `ownership_hints` describes the ownership semantics of the
+// standard smart pointers, but not of user-defined ones.
+
+#![feature(rustc_attrs)]
+
+use std::rc::Rc;
+use std::sync::Arc;
+
+struct MyBox<T>(Box<T>);
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(std::boxed::Box<u8>)
+type A = Box<u8>;
+
+#[rustc_print_type(ownership_hints)]
+//~^ ERROR print-type(std::boxed::Box<u8> /* ownership: heap-allocated owned u8 */)
+type B = Box<u8>;
+
+#[rustc_print_type(ownership_hints)]
+//~^ ERROR print-type(std::rc::Rc<u8> /* ownership: shared owned u8 */)
+type C = Rc<u8>;
+
+#[rustc_print_type(ownership_hints)]
+//~^ ERROR print-type(std::sync::Arc<u8> /* ownership: thread-safe shared owned u8 */)
+type D = Arc<u8>;
+
+#[rustc_print_type(ownership_hints)]
+//~^ ERROR print-type(MyBox<u8>)
+type E = MyBox<u8>;
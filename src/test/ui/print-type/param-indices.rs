@@ -0,0 +1,24 @@
+// Check that `param_indices` annotates type parameters and early-bound
+// lifetimes with their index, wherever they're printed.
+
+#![feature(rustc_attrs)]
+
+trait Trait<T> {}
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(&'a (T, U))
+type A<'a, T, U> = &'a (T, U);
+
+#[rustc_print_type(param_indices)]
+//~^ ERROR print-type(&'a#0 (T#1, U#2))
+type B<'a, T, U> = &'a (T, U);
+
+#[rustc_print_type(param_indices)]
+//~^ ERROR print-type(std::boxed::Box<(dyn Trait<T#0> + 'static)>)
+type C<T> = Box<dyn Trait<T>>;
+
+#[rustc_print_type(param_indices)]
+//~^ ERROR print-type(fn(T#0) -> T#0 {f::<T#0>})
+fn f<T>(x: T) -> T { x }
@@ -0,0 +1,29 @@
+// Check that `max_path_segments` abbreviates a 6-segment path as printed by
+// `path_append`, counting the segments of each path (e.g. generic args)
+// separately, and that it's left as is when it's short enough or disabled.
+
+#![feature(rustc_attrs)]
+
+mod a { pub mod b { pub mod c { pub mod d { pub mod e { pub struct F; } } } } }
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(a::b::c::d::e::F)
+type A = a::b::c::d::e::F;
+
+#[rustc_print_type(max_path_segments = "2")]
+//~^ ERROR print-type(...::e::F)
+type B = a::b::c::d::e::F;
+
+#[rustc_print_type(max_path_segments = "5")]
+//~^ ERROR print-type(...::b::c::d::e::F)
+type C = a::b::c::d::e::F;
+
+#[rustc_print_type(max_path_segments = "6")]
+//~^ ERROR print-type(a::b::c::d::e::F)
+type D = a::b::c::d::e::F;
+
+#[rustc_print_type(max_path_segments = "2")]
+//~^ ERROR print-type(std::option::Option<...::e::F>)
+type E = Option<a::b::c::d::e::F>;
@@ -0,0 +1,23 @@
+// Check the printing of each kind of predicate found in where clauses,
+// including higher-ranked ones, and of types being well-formed.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(predicates)]
+//~^ ERROR print-type('b : 'a)
+//~| ERROR print-type(T: std::marker::Sized)
+//~| ERROR print-type(T : 'a)
+//~| ERROR print-type(T: std::iter::Iterator)
+//~| ERROR print-type(<T as std::iter::Iterator>::Item == u8)
+//~| ERROR print-type(for<'c> &'c T: std::cmp::PartialEq<&'c T>)
+fn f<'a, 'b: 'a, T: 'a + Iterator<Item = u8>>() where for<'c> &'c T: PartialEq<&'c T> {}
+
+#[rustc_print_type(predicates)]
+//~^ ERROR print-type(T: std::marker::Sized)
+//~| ERROR print-type(T : 'a)
+//~| ERROR print-type(S<'a, T> well-formed)
+struct S<'a, T> {
+    x: &'a T,
+}
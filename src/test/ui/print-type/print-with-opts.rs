@@ -0,0 +1,18 @@
+// Check that `print_with_opts` prints with the options it's given, and with
+// none of them (like a default `FmtPrinter`) otherwise.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(print_with_opts)]
+//~^ ERROR print-type((u8, std::option::Option<T>))
+type A<T> = (u8, Option<T>);
+
+#[rustc_print_type(print_with_opts, tuple_indices)]
+//~^ ERROR print-type((.0: u8, .1: std::option::Option<T>))
+type B<T> = (u8, Option<T>);
+
+#[rustc_print_type(print_with_opts, tuple_indices, param_indices)]
+//~^ ERROR print-type((.0: u8, .1: std::option::Option<T#0>))
+type C<T> = (u8, Option<T>);
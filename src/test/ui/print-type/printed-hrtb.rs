@@ -0,0 +1,26 @@
+// Check that `FmtPrinter::printed_hrtb` tells whether any `for<...>` binder
+// was printed, including nested ones, but not for types without late-bound
+// regions, and that nothing is generated for it without `printed_hrtb`.
+
+#![feature(rustc_attrs)]
+
+#[rustc_print_type(printed_hrtb)]
+//~^ ERROR print-type(for<'a> fn(&'a u8))
+//~| ERROR printed-hrtb(true)
+type A = for<'a> fn(&'a u8);
+
+#[rustc_print_type(printed_hrtb)]
+//~^ ERROR print-type(std::vec::Vec<for<'a> fn(&'a u8)>)
+//~| ERROR printed-hrtb(true)
+type B = Vec<for<'a> fn(&'a u8)>;
+
+#[rustc_print_type(printed_hrtb)]
+//~^ ERROR print-type(fn(&'static u8))
+//~| ERROR printed-hrtb(false)
+type C = fn(&'static u8);
+
+#[rustc_print_type]
+//~^ ERROR print-type(for<'a> fn(&'a u8))
+type D = for<'a> fn(&'a u8);
+
+fn main() {}
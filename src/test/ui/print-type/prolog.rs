@@ -0,0 +1,26 @@
+// ignore-tidy-linelength
+
+// Check the facts `prolog` prints, with a fresh identifier for every subterm
+// (even when it's the same as an earlier one), and quoted atoms.
+
+#![feature(rustc_attrs)]
+
+struct P<T>(T);
+
+fn main() {}
+
+#[rustc_print_type(prolog)]
+//~^ ERROR print-type(crate(t0, 'core'). segment(t1, t0, 'option', 0). segment(t2, t1, 'Option', 0). primitive(t3, 'u32'). generic_args(t4, t2, [t3]). adt(t5, t4).)
+type A = Option<u32>;
+
+#[rustc_print_type(prolog)]
+//~^ ERROR print-type(region(t0, '\'a'). primitive(t1, 'char'). slice(t2, t1). ref(t3, t0, mut, t2).)
+type B<'a> = &'a mut [char];
+
+#[rustc_print_type(prolog)]
+//~^ ERROR print-type(primitive(t0, 'u8'). primitive(t1, 'u8'). primitive(t2, '!'). fn_ptr(t3, [t1], t2). tuple(t4, [t0, t3]).)
+type C = (u8, fn(u8) -> !);
+
+#[rustc_print_type(prolog)]
+//~^ ERROR print-type(crate(t0, 'prolog'). segment(t1, t0, 'P', 0). crate(t2, 'prolog'). segment(t3, t2, 'P', 0). primitive(t4, 'bool'). generic_args(t5, t3, [t4]). adt(t6, t5). generic_args(t7, t1, [t6]). adt(t8, t7).)
+type D = P<P<bool>>;
@@ -0,0 +1,27 @@
+// normalize-stderr-test "alloc\d+" -> "allocN"
+
+// Check that raw pointer consts are printed by their address (or as `null`),
+// or by the allocation they point into, with the offset into it, and that
+// only their paths are printed without `const_values`.
+
+#![feature(rustc_attrs)]
+
+#[rustc_print_type(const_values)]
+//~^ ERROR print-type(NULL /* = null */)
+#[rustc_print_type]
+//~^ ERROR print-type(NULL)
+const NULL: *const u8 = 0 as *const u8;
+
+#[rustc_print_type(const_values)]
+//~^ ERROR print-type(ADDR /* = ptr<0x10> */)
+const ADDR: *const u8 = 16 as *const u8;
+
+#[rustc_print_type(const_values)]
+//~^ ERROR print-type(ALLOC /* = ptr<alloc
+const ALLOC: *const u8 = &0u8;
+
+#[rustc_print_type(const_values)]
+//~^ ERROR print-type(OFFSET /* = ptr<alloc
+const OFFSET: *const u8 = &[1u8, 2][1];
+
+fn main() {}
@@ -0,0 +1,28 @@
+// Check that `crates_referenced_by` records the defining crates of the paths
+// in a type, including those of the generic args that are left out of the
+// printed paths for being the defaults of their parameters.
+
+#![feature(rustc_attrs)]
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub struct Local<T = Vec<u8>>(T);
+
+fn main() {}
+
+#[rustc_print_type(referenced_crates)]
+//~^ ERROR print-type(alloc)
+type A = Vec<Rc<u32>>;
+
+#[rustc_print_type(referenced_crates)]
+//~^ ERROR print-type(alloc, std)
+type B = HashMap<String, u8>;
+
+#[rustc_print_type(referenced_crates)]
+//~^ ERROR print-type(alloc)
+type C = Local;
+
+#[rustc_print_type(referenced_crates)]
+//~^ ERROR print-type()
+type D = Local<u8>;
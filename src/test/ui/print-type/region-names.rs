@@ -0,0 +1,30 @@
+// Check that `FmtPrinter` generates (and interns) the name of each anonymous
+// late-bound region only once, reusing it in every binder and every
+// repetition, and doesn't generate any for named regions.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(repeat = "3")]
+//~^ ERROR print-type(for<'r> fn(&'r u8))
+//~| ERROR resolver-calls(0)
+//~| ERROR region-names('r)
+type A = fn(&u8);
+
+#[rustc_print_type(repeat = "1")]
+//~^ ERROR print-type((for<'r> fn(&'r u8), for<'r> fn(&'r u16)))
+//~| ERROR resolver-calls(0)
+//~| ERROR region-names('r)
+type B = (fn(&u8), fn(&u16));
+
+#[rustc_print_type(repeat = "2")]
+//~^ ERROR print-type(for<'r, 's> fn(&'r u8, &'s u8))
+//~| ERROR resolver-calls(0)
+//~| ERROR region-names('r, 's)
+type C = fn(&u8, &u8);
+
+#[rustc_print_type(repeat = "2")]
+//~^ ERROR print-type(for<'a> fn(&'a u8))
+//~| ERROR resolver-calls(0)
+type D = for<'a> fn(&'a u8);
@@ -0,0 +1,18 @@
+// Check that `region_vids` prints region inference variables by their index,
+// followed by their SCC when known, but that they're omitted when disabled.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(region_vars)]
+//~^ ERROR print-type(&&u8)
+type A<'a> = &'a &'static u8;
+
+#[rustc_print_type(region_vars, region_vids)]
+//~^ ERROR print-type(&'?0(scc0) &'?1(scc0) u8)
+type B<'a> = &'a &'static u8;
+
+#[rustc_print_type(region_vars, region_vids)]
+//~^ ERROR print-type(std::cell::Ref<'?0(scc0), &'?1(scc0) std::cell::Ref<'?2(scc1), u8>>)
+type C<'a, 'b> = std::cell::Ref<'a, &'b std::cell::Ref<'static, u8>>;
@@ -0,0 +1,22 @@
+// Check that a `NameResolver` renames each segment of a path after the def
+// it belongs to, including the visible parents of the defs of other crates.
+
+#![feature(rustc_attrs)]
+
+mod a {
+    pub struct Vec;
+}
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(std::vec::Vec<u8>)
+type A = Vec<u8>;
+
+#[rustc_print_type(renames = "vec => vector, Vec => Vector")]
+//~^ ERROR print-type(std::vector::Vector<u8>)
+type B = Vec<u8>;
+
+#[rustc_print_type(renames = "a => z, Vec => Unit")]
+//~^ ERROR print-type((z::Unit, std::vec::Unit<u8>))
+type C = (a::Vec, Vec<u8>);
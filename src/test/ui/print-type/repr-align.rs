@@ -0,0 +1,23 @@
+// Check that `repr_align` prints explicit alignments as `#[repr(align(N))]`,
+// with symbolic alignments (here, an unevaluated const) printed as-is and
+// nothing printed for the default alignment, and that ADTs are still
+// printed by their path without it.
+
+#![feature(rustc_attrs)]
+
+#[rustc_print_type(repr_align)]
+//~^ ERROR print-type(#[repr(align(8))])
+#[rustc_print_type]
+//~^ ERROR print-type(Aligned)
+#[repr(align(8))]
+struct Aligned;
+
+#[rustc_print_type(repr_align)]
+//~^ ERROR print-type()
+struct Unaligned;
+
+#[rustc_print_type(repr_align)]
+//~^ ERROR print-type(#[repr(align(ALIGN))])
+const ALIGN: usize = 16;
+
+fn main() {}
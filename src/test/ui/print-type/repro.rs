@@ -0,0 +1,23 @@
+// Check that `type_repro` replaces the local ADTs and traits by stubs with
+// the same generics (and associated types), keeping the library ones as is,
+// and turns the type parameters into those of the `repro` function.
+
+#![feature(rustc_attrs)]
+
+struct Local<'a, T>(&'a T);
+trait Tr { type Item; }
+impl Tr for u8 { type Item = (); }
+
+fn main() {}
+
+#[rustc_print_type(repro)]
+//~^ ERROR print-type(struct S0<'a0, A0>(std::marker::PhantomData<(&'a0 (), A0,)>);
+type A<'a> = Local<'a, Vec<Box<dyn Tr<Item = u8>>>>;
+
+#[rustc_print_type(repro)]
+//~^ ERROR print-type(fn repro<T>() {
+type B<T> = (T, Option<T>);
+
+#[rustc_print_type(repro)]
+//~^ ERROR print-type(trait T0 { type Item; }
+type C = <u8 as Tr>::Item;
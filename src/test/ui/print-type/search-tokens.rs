@@ -0,0 +1,20 @@
+// Check that `search_tokens` gives the lowercased names in a type (including
+// defaulted generic args and associated type bindings) once each, in order.
+
+#![feature(rustc_attrs)]
+
+struct MyType;
+
+fn main() {}
+
+#[rustc_print_type(search_tokens)]
+//~^ ERROR print-type(vec, hashmap, string, u32, randomstate)
+type A = Vec<std::collections::HashMap<String, u32>>;
+
+#[rustc_print_type(search_tokens)]
+//~^ ERROR print-type(str, u8, option, bool)
+type B = (&'static str, [u8; 4], Option<&'static str>, fn(u8) -> bool);
+
+#[rustc_print_type(search_tokens)]
+//~^ ERROR print-type(box, iterator, item, mytype)
+type C = Box<dyn Iterator<Item = MyType>>;
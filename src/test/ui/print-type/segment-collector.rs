@@ -0,0 +1,34 @@
+// Check the segments `type_path_segments` collects, with their generic args,
+// for plain, qualified and impl paths, and that types without paths have none.
+
+#![feature(rustc_attrs)]
+
+struct Foo;
+
+mod m {
+    impl super::Foo {
+        #[rustc_print_type(segments)]
+        //~^ ERROR print-type(Crate(segment_collector), Item(m), Impl(<impl Foo>), Item(baz))
+        pub fn baz() {}
+    }
+}
+
+impl Foo {
+    #[rustc_print_type(segments)]
+    //~^ ERROR print-type(Qualified(Foo), Item(bar))
+    fn bar() {}
+}
+
+fn main() {}
+
+#[rustc_print_type(segments)]
+//~^ ERROR print-type(Crate(alloc), Item(vec), Item(Vec)<u32>)
+type A = Vec<u32>;
+
+#[rustc_print_type(segments)]
+//~^ ERROR print-type(Crate(std), Item(collections), Item(hash), Item(map), Item(HashMap)<u8, bool>)
+type B = std::collections::HashMap<u8, bool>;
+
+#[rustc_print_type(segments)]
+//~^ ERROR print-type()
+type C = &'static Foo;
@@ -0,0 +1,23 @@
+// Check that `FmtPrinter::self_ty` is printed in place of the `Self`
+// parameter, including in the trait-qualified paths, but not in place of
+// other parameters at index `0`, and that `Self` stays `Self` without it.
+
+#![feature(rustc_attrs)]
+
+struct Foo;
+
+#[rustc_print_type(self_ty = "Foo")]
+//~^ ERROR print-type(std::option::Option<T>)
+type First<T> = Option<T>;
+
+trait Tr {
+    #[rustc_print_type(self_ty = "Foo")]
+    //~^ ERROR print-type(for<'r> fn(&'r Foo) -> Foo {<Foo as Tr>::f})
+    fn f(&self) -> Self;
+
+    #[rustc_print_type]
+    //~^ ERROR print-type(for<'r> fn(&'r Self) -> Self {<Self as Tr>::g})
+    fn g(&self) -> Self;
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+// ignore-tidy-linelength
+
+// Check the S-expressions `sexp` prints (and checks round-trip) for
+// parameterized structs, trait objects and references.
+
+#![feature(rustc_attrs)]
+
+struct P<'a, T, U = u8>(&'a T, U);
+
+fn main() {}
+
+#[rustc_print_type(sexp)]
+//~^ ERROR print-type((adt sexp::P (lt 'a) (ty u16) (ty u8)))
+type A<'a> = P<'a, u16>;
+
+#[rustc_print_type(sexp)]
+//~^ ERROR print-type((adt alloc::boxed::Box (ty (dyn (bounds (trait core::fmt::Debug) (auto core::marker::Send)) 'static))))
+type B = Box<dyn std::fmt::Debug + Send>;
+
+#[rustc_print_type(sexp)]
+//~^ ERROR print-type((ref 'a mut (slice (adt core::option::Option (ty char)))))
+type C<'a> = &'a mut [Option<char>];
@@ -0,0 +1,39 @@
+// Check that `short_qualified_traits` prints the traits of qualified paths by
+// their name alone (along with their generic args), unless another trait has
+// the same name, and that they're printed by their full paths without it.
+
+#![feature(rustc_attrs)]
+
+trait Conv<X> {
+    type Out;
+}
+
+mod a {
+    pub trait Dup {
+        type Out;
+    }
+}
+
+mod b {
+    pub trait Dup {
+        type Out;
+    }
+}
+
+#[rustc_print_type(short_qualified_traits)]
+//~^ ERROR print-type(<T as Iterator>::Item)
+type A<T> = <T as Iterator>::Item;
+
+#[rustc_print_type(short_qualified_traits)]
+//~^ ERROR print-type(<std::vec::Vec<T> as Conv<u8>>::Out)
+type B<T> = <Vec<T> as Conv<u8>>::Out;
+
+#[rustc_print_type(short_qualified_traits)]
+//~^ ERROR print-type(<T as a::Dup>::Out)
+type C<T> = <T as a::Dup>::Out;
+
+#[rustc_print_type]
+//~^ ERROR print-type(<T as std::iter::Iterator>::Item)
+type D<T> = <T as Iterator>::Item;
+
+fn main() {}
@@ -0,0 +1,33 @@
+// ignore-tidy-linelength
+
+// Check that `signature_mode` prints closures as the traits they implement
+// (with their signatures) and omits the hidden types of `impl Trait` types,
+// and that closures are printed by their spans without it.
+
+#![feature(rustc_attrs)]
+
+#[rustc_print_type(closures, signature_mode)]
+//~^ ERROR print-type(impl Fn(u8) -> bool)
+//~| ERROR print-type(impl FnMut())
+//~| ERROR print-type(impl FnOnce() -> std::string::String)
+fn f() {
+    let _ = |x: u8| x > 0;
+    let mut count = 0;
+    let _ = || count += 1;
+    let s = String::new();
+    let _ = move || s;
+}
+
+#[rustc_print_type(closures)]
+//~^ ERROR print-type([closure@$DIR/signature-mode.rs:24:13: 24:26])
+fn g() {
+    let _ = |x: u8| x > 0;
+}
+
+#[rustc_print_type(hidden_types, signature_mode)]
+//~^ ERROR print-type(fn() -> impl std::marker::Copy {h})
+fn h() -> impl Copy {
+    0u8
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+// Check that `single_line` keeps generic args on the same line, even when
+// `max_line_width` would otherwise wrap them.
+
+#![feature(rustc_attrs)]
+
+struct Local;
+
+fn main() {}
+
+#[rustc_print_type(max_line_width = "30", locality_markers)]
+//~^ ERROR print-type(std::option::Option<
+type A = Option<Local>;
+
+#[rustc_print_type(single_line, max_line_width = "30", locality_markers)]
+//~^ ERROR print-type(std::option::Option<Local /* local */> /* foreign */)
+type B = Option<Local>;
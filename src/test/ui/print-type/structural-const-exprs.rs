@@ -0,0 +1,23 @@
+// Check that `structural_const_exprs` prints anonymous consts by their structure,
+// while by default they're printed as written.
+
+#![feature(const_generics, rustc_attrs)]
+//~^ WARN the feature `const_generics` is incomplete and may cause the compiler to crash
+
+struct S<const N: usize>;
+
+const LEN: usize = 2;
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(S<{LEN*2}>)
+type A = S<{LEN*2}>;
+
+#[rustc_print_type(structural_const_exprs)]
+//~^ ERROR print-type(S<{ LEN * 2 }>)
+type B = S<{LEN*2}>;
+
+#[rustc_print_type(structural_const_exprs)]
+//~^ ERROR print-type(S<{ (LEN + 1) * (-(2 - LEN)) }>)
+type C = S<{(LEN+1)*-(2-LEN)}>;
@@ -0,0 +1,32 @@
+// Check that `ty_to_string` and `def_path_to_string` print the same as
+// `Display` and `def_path_str`, for types and for paths.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(to_string)]
+//~^ ERROR print-type(std::vec::Vec<&'static str>)
+type A = Vec<&'static str>;
+
+#[rustc_print_type(to_string)]
+//~^ ERROR print-type(for<'r> fn(&'r u8) -> bool)
+type B = fn(&u8) -> bool;
+
+#[rustc_print_type(to_string)]
+//~^ ERROR print-type(std::boxed::Box<(dyn std::ops::Fn() + 'static)>)
+type C = Box<dyn Fn()>;
+
+#[rustc_print_type(to_string)]
+//~^ ERROR print-type(S)
+struct S;
+
+mod m {
+    #[rustc_print_type(to_string)]
+    //~^ ERROR print-type(m::E)
+    pub enum E {
+        #[rustc_print_type(to_string)]
+        //~^ ERROR print-type(m::E::V)
+        V,
+    }
+}
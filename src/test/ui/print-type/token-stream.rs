@@ -0,0 +1,20 @@
+// ignore-tidy-linelength
+
+// Check the tokens `type_token_stream` splits types into, with the whitespace
+// preceding them, and their depth within brackets.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(token_stream)]
+//~^ ERROR print-type(`std`@0, `::`@0, `vec`@0, `::`@0, `Vec`@0, `<`@0, `u8`@1, `>`@0)
+type A = Vec<u8>;
+
+#[rustc_print_type(token_stream)]
+//~^ ERROR print-type(`(`@0, `&`@1, `'static`@1, ` str`@1, `,`@1, ` [`@1, `u8`@2, `;`@2, ` 2`@2, `]`@1, `)`@0)
+type B = (&'static str, [u8; 2]);
+
+#[rustc_print_type(token_stream)]
+//~^ ERROR print-type(`fn`@0, `(`@0, `u8`@1, `)`@0, ` ->`@0, ` bool`@0)
+type C = fn(u8) -> bool;
@@ -0,0 +1,34 @@
+// ignore-tidy-linelength
+
+// Check that trait method paths are qualified with the implementing type, or
+// `Self`, with all of the trait's arguments substituted, and that the `Self`
+// parameter alone doesn't count as the trait having generic arguments, which
+// `empty_generic_brackets` would otherwise print as `Tr<>`.
+
+#![feature(rustc_attrs)]
+
+trait Tr {
+    #[rustc_print_type(empty_generic_brackets)]
+    //~^ ERROR print-type(fn() {<Self as Tr>::f})
+    fn f();
+}
+
+trait Ext<A> {
+    #[rustc_print_type(empty_generic_brackets)]
+    //~^ ERROR print-type(for<'r> fn(&'r mut Self, I) {<Self as Ext<A>>::ext::<I>})
+    fn ext<I>(&mut self, _: I);
+}
+
+impl Tr for u8 {
+    #[rustc_print_type(empty_generic_brackets)]
+    //~^ ERROR print-type(fn() {<u8 as Tr>::f})
+    fn f() {}
+}
+
+impl<A> Ext<A> for Vec<A> {
+    #[rustc_print_type]
+    //~^ ERROR print-type(for<'r> fn(&'r mut std::vec::Vec<A>, I) {<std::vec::Vec<A> as Ext<A>>::ext::<I>})
+    fn ext<I>(&mut self, _: I) {}
+}
+
+fn main() {}
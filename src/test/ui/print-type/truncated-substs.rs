@@ -0,0 +1,24 @@
+// Check that types whose substs lack the args of trailing parameters, as
+// seen during inference, print the args they have, without eliding any.
+
+#![feature(rustc_attrs)]
+
+struct S<A, B = A>(A, B);
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(S<u8>)
+type A = S<u8, u8>;
+
+#[rustc_print_type(truncated_substs = "1")]
+//~^ ERROR print-type(S<u8>)
+type B = S<u8, u16>;
+
+#[rustc_print_type(truncated_substs = "0")]
+//~^ ERROR print-type(S)
+type C = S<u8, u16>;
+
+#[rustc_print_type(truncated_substs = "1", verbose)]
+//~^ ERROR print-type(S<u8>)
+type D = S<u8, u16>;
@@ -0,0 +1,39 @@
+// Check that `tuple_indices` prefixes tuple elements with their field index,
+// including those of single-element and nested tuples, and array elements
+// with the range of their indices, if any, but not when disabled.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type((i32, bool, char))
+type A = (i32, bool, char);
+
+#[rustc_print_type(tuple_indices)]
+//~^ ERROR print-type((.0: i32, .1: bool, .2: char))
+type B = (i32, bool, char);
+
+#[rustc_print_type(tuple_indices)]
+//~^ ERROR print-type((.0: i32,))
+type C = (i32,);
+
+#[rustc_print_type(tuple_indices)]
+//~^ ERROR print-type((.0: u8, .1: (.0: u16, .1: u32)))
+type D = (u8, (u16, u32));
+
+#[rustc_print_type(tuple_indices)]
+//~^ ERROR print-type(())
+type E = ();
+
+#[rustc_print_type]
+//~^ ERROR print-type([i32; 3])
+type F = [i32; 3];
+
+#[rustc_print_type(tuple_indices)]
+//~^ ERROR print-type([.0..3: i32; 3])
+type G = [i32; 3];
+
+#[rustc_print_type(tuple_indices)]
+//~^ ERROR print-type([.0..2: (.0: u8, .1: [u16; 0]); 2])
+type H = [(u8, [u16; 0]); 2];
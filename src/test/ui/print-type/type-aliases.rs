@@ -0,0 +1,28 @@
+// Check that `FmtPrinter::type_aliases` are printed in place of the types they
+// expand to (the first that matches, when several do), but only when every
+// argument they fix matches, and that types are printed as-is without them.
+
+#![feature(rustc_attrs)]
+
+struct Error;
+
+type Res<T> = Result<T, Error>;
+type ByteRes = Result<u8, Error>;
+
+#[rustc_print_type(type_aliases = "Res")]
+//~^ ERROR print-type(std::vec::Vec<Res<u8>>)
+type A = Vec<Result<u8, Error>>;
+
+#[rustc_print_type(type_aliases = "ByteRes, Res")]
+//~^ ERROR print-type((ByteRes, Res<u16>))
+type B = (Result<u8, Error>, Result<u16, Error>);
+
+#[rustc_print_type(type_aliases = "Res")]
+//~^ ERROR print-type(std::result::Result<u8, ()>)
+type C = Result<u8, ()>;
+
+#[rustc_print_type]
+//~^ ERROR print-type(std::result::Result<u8, Error>)
+type D = Result<u8, Error>;
+
+fn main() {}
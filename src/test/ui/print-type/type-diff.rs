@@ -0,0 +1,30 @@
+// Check that `print_type_diff` prints the same types as is, and highlights
+// only differing args, or whole types when their arity differs.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(diff)]
+//~^ ERROR print-type(std::vec::Vec<u8> vs std::vec::Vec<u8>)
+type A = (Vec<u8>, Vec<u8>);
+
+#[rustc_print_type(diff)]
+//~^ ERROR print-type(std::result::Result<u8, «u32»> vs std::result::Result<u8, «i32»>)
+type B = (Result<u8, u32>, Result<u8, i32>);
+
+#[rustc_print_type(diff)]
+//~^ ERROR print-type([&'static «u8»; «2»] vs [&'static «u16»; «3»])
+type C = ([&'static u8; 2], [&'static u16; 3]);
+
+#[rustc_print_type(diff)]
+//~^ ERROR print-type(«(u8, u16)» vs «(u8,)»)
+type D = ((u8, u16), (u8,));
+
+#[rustc_print_type(diff)]
+//~^ ERROR print-type(«fn(u8)» vs «fn(u8, u8)»)
+type E = (fn(u8), fn(u8, u8));
+
+#[rustc_print_type(diff)]
+//~^ ERROR print-type(fn(u8) -> «u8» vs fn(u8) -> «()»)
+type F = (fn(u8) -> u8, fn(u8));
@@ -0,0 +1,22 @@
+// Check that `type_hash_str` gives the same digest to the same types, even
+// when they only differ in their lifetimes, and different ones otherwise.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type(type_hash)]
+//~^ ERROR print-type([0, 1], [2], [3, 5], [4])
+type A<'a> = (&'a u8, &'static u8, &'a mut u8, Vec<u8>, Vec<u16>, Vec<u8>);
+
+#[rustc_print_type(type_hash)]
+//~^ ERROR print-type([0, 1], [2, 3], [4])
+type B = (fn(u8), fn(u8) -> (), for<'a> fn(&'a u8), fn(&'static u8), unsafe fn(u8));
+
+#[rustc_print_type(type_hash)]
+//~^ ERROR print-type([0], [1], [2, 4], [3])
+type C = (Option<u8>, Result<u8, ()>, [u8; 2], [u8; 3], [u8; 2]);
+
+#[rustc_print_type(type_hash)]
+//~^ ERROR print-type(`type_hash` expects a tuple of types, found `u8`)
+type D = u8;
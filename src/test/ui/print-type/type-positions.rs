@@ -0,0 +1,27 @@
+// Check that `impl Trait` types defined by an alias are printed by their
+// bounds when written in return position, and by the alias's name when
+// written in a binding, which is also how they're printed by default.
+
+#![feature(existential_type, rustc_attrs)]
+
+existential type Foo: Copy;
+
+fn define() -> Foo {
+    0u8
+}
+
+#[rustc_print_type(type_position = "return")]
+//~^ ERROR print-type(impl std::marker::Copy)
+type A = Foo;
+
+#[rustc_print_type(type_position = "binding")]
+//~^ ERROR print-type(Foo)
+type B = Foo;
+
+#[rustc_print_type]
+//~^ ERROR print-type(Foo)
+type C = Foo;
+
+fn main() {
+    define();
+}
@@ -0,0 +1,22 @@
+// Check that `unsized_markers` marks unsized types, right after them,
+// and leaves types whose sizedness depends on generic parameters alone.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+#[rustc_print_type]
+//~^ ERROR print-type(&'static [u8])
+type A = &'static [u8];
+
+#[rustc_print_type(unsized_markers)]
+//~^ ERROR print-type((&'static [u8] /* unsized */, &'static str /* unsized */))
+type B = (&'static [u8], &'static str);
+
+#[rustc_print_type(unsized_markers)]
+//~^ ERROR print-type(std::boxed::Box<(dyn std::marker::Send + 'static) /* unsized */>)
+type C = Box<dyn Send>;
+
+#[rustc_print_type(unsized_markers)]
+//~^ ERROR print-type(&'static T)
+type D<T> = &'static T;
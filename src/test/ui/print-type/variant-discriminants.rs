@@ -0,0 +1,26 @@
+// Check that `variant_discriminants` appends the discriminant value
+// to the paths of enum variants, including implicit discriminants, which
+// are known even for generic enums.
+
+#![feature(rustc_attrs)]
+
+fn main() {}
+
+enum E {
+    #[rustc_print_type]
+    //~^ ERROR print-type(E::A)
+    A = 3,
+    #[rustc_print_type(variant_discriminants)]
+    //~^ ERROR print-type(E::B = 4)
+    B,
+    #[rustc_print_type(variant_discriminants)]
+    //~^ ERROR print-type(E::C = -1)
+    C = -1,
+}
+
+enum G<T> {
+    X(T),
+    #[rustc_print_type(variant_discriminants)]
+    //~^ ERROR print-type(G::Y = 1)
+    Y,
+}
@@ -0,0 +1,25 @@
+// Check the YAML documents `yaml` prints, including for the flow-style empty
+// sequences and the scalars that have to be quoted not to be read as
+// booleans or nulls (or, for regions and `!`, not to be plain at all).
+
+#![feature(rustc_attrs)]
+#![allow(non_camel_case_types)]
+
+struct y;
+struct n;
+struct Null;
+struct On;
+
+fn main() {}
+
+#[rustc_print_type(yaml)]
+//~^ ERROR print-type(kind: ref
+type A<'a> = &'a Option<u8>;
+
+#[rustc_print_type(yaml)]
+//~^ ERROR print-type(kind: tuple
+type B = (y, n, Null, On);
+
+#[rustc_print_type(yaml)]
+//~^ ERROR print-type(kind: fn_ptr
+type C = fn(()) -> !;
@@ -0,0 +1,16 @@
+// Check that reference types print their lifetime before `mut`, in all positions.
+
+fn take(_: ()) {}
+
+fn main() {
+    let a: Option<&'static mut u8> = None;
+    take(a);
+    //~^ ERROR mismatched types
+    let b: Vec<&'static (u8, &'static mut u16)> = Vec::new();
+    take(b);
+    //~^ ERROR mismatched types
+    let mut x = 0u8;
+    let c: Option<&mut u8> = Some(&mut x);
+    take(c);
+    //~^ ERROR mismatched types
+}
@@ -0,0 +1,37 @@
+// Check that `backtrace_path_str` matches the (legacy) demangled symbols,
+// for functions, closures, and methods of inherent and trait impls.
+
+#![feature(rustc_attrs)]
+#![allow(dead_code)]
+
+mod foo {
+    pub struct Foo;
+}
+
+trait Trait {
+    fn baz(&self);
+}
+
+#[rustc_backtrace_path]
+//~^ ERROR backtrace-path(backtrace_path::main)
+//~| ERROR backtrace-path(backtrace_path::main::{{closure}})
+//~| ERROR backtrace-path(backtrace_path::main::{{closure}})
+//~| ERROR backtrace-path(backtrace_path::main::{{closure}}::{{closure}})
+fn main() {
+    let f = |x: u8| x;
+    f(0);
+    let g = || (|| ())();
+    g();
+}
+
+impl foo::Foo {
+    #[rustc_backtrace_path]
+    //~^ ERROR backtrace-path(backtrace_path::foo::Foo::bar)
+    fn bar() {}
+}
+
+impl Trait for foo::Foo {
+    #[rustc_backtrace_path]
+    //~^ ERROR backtrace-path(<backtrace_path::foo::Foo as backtrace_path::Trait>::baz)
+    fn baz(&self) {}
+}
@@ -154,6 +154,7 @@ const WHITELIST: &[Crate<'_>] = &[
     Crate("termion"),
     Crate("thread_local"),
     Crate("ucd-util"),
+    Crate("unicode-normalization"),
     Crate("unicode-width"),
     Crate("unicode-xid"),
     Crate("unreachable"),